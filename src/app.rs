@@ -1,5 +1,63 @@
-use std::process::Command;
-use crate::db::Database;
+use chrono::{DateTime, Datelike, Local, TimeZone, Timelike, Weekday};
+use std::collections::HashMap;
+use std::process::{Child, Command, Stdio};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Instant;
+use crate::db::{group_into_blocks, Database, Session, SessionBlock, Template, TimerState, DEFAULT_TAG};
+use crate::export::{parse_rest_days, ExportFormat, RestDays};
+use crate::review::{week_review, ReviewCard, WeekData};
+use crate::glyphs::Glyphs;
+use crate::theme::Theme;
+use crate::ui::heatmap::{HeatMetric, HeatScale, HEATMAP_DAYS, SESSION_THRESHOLDS};
+
+/// Longest tag name accepted, in characters
+pub const MAX_TAG_LENGTH: usize = 30;
+
+/// Number of sessions listed in the Home screen's recent activity panel
+pub const RECENT_SESSION_COUNT: usize = 5;
+
+/// Sessions shorter than this (in seconds) are never written to the database
+pub const MIN_SESSION_SECONDS: i64 = 60;
+
+/// Shortest work or break duration, in seconds
+pub const MIN_DURATION_SECONDS: u64 = 60;
+
+/// Default longest work and break durations, in seconds; both can be raised in Settings
+pub const DEFAULT_MAX_WORK_SECONDS: u64 = 7200;
+pub const DEFAULT_MAX_BREAK_SECONDS: u64 = 3600;
+
+/// Upper bound for the configurable maximums, in minutes
+const DURATION_CEILING_MINUTES: u64 = 720;
+
+/// Default and longest gap between the sessions of a work block, in minutes.
+/// The default leaves room for a short break and then some.
+const DEFAULT_BLOCK_GAP_MINUTES: u64 = 20;
+const MAX_BLOCK_GAP_MINUTES: u64 = 240;
+
+/// How many of the latest sessions are grouped into the Recent panel's blocks
+const BLOCK_SOURCE_SESSIONS: usize = 100;
+
+/// Suggestions offered on breaks until the user sets their own
+const DEFAULT_BREAK_SUGGESTIONS: &str = "Stretch; Get water; Look out the window; Take a short walk; Rest your eyes";
+
+/// Longest final countdown, in seconds
+const MAX_FINAL_COUNTDOWN: u64 = 30;
+
+/// How long after a completion it can still be undone, in seconds
+pub const UNDO_WINDOW_SECONDS: u64 = 120;
+
+/// Event loop poll interval while the timer runs or the border flashes, in milliseconds
+const ACTIVE_POLL_MS: u64 = 100;
+
+/// Resetting a session with at least this much progress asks for confirmation first
+pub const RESET_CONFIRM_SECONDS: u64 = 5 * 60;
+
+/// Aligned sessions end on a multiple of this many seconds past midnight (:00 and :30)
+pub const ALIGN_SECONDS: u64 = 30 * 60;
+
+/// Shortest time left that aligning a session's end may produce
+pub const MIN_ALIGNED_SECONDS: u64 = 5 * 60;
 
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub enum Screen {
@@ -8,9 +66,31 @@ pub enum Screen {
     Heatmap,
     TagInput,
     DeleteConfirm,
+    NoteInput,
+    ArchivedTags,
+    DurationInput,
+    SettingInput,
     Settings,
+    TodaySummary,
+    IntentionInput,
+    ManualEntry,
+    BackupDone,
+    ExportDone,
+    WeekReview,
+    CatchUp,
+    MergeTags,
+    BucketSessions,
+    RatingInput,
+    StatsPeek,
+    PurgeConfirm,
+    ResetConfirm,
+    Templates,
+    TemplateInput,
 }
 
+/// Main screens visited in order by `[` and `]`
+pub const SCREEN_RING: [Screen; 3] = [Screen::Home, Screen::Stats, Screen::Heatmap];
+
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub enum PomodoroMode {
     Work,
@@ -21,8 +101,438 @@ pub enum PomodoroMode {
 pub enum StatsView {
     Weekly,
     Monthly,
+    Yearly,
+    Weekdays,
+    Hours,
+}
+
+/// How the timer's progress bar is drawn
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ProgressStyle {
+    Gauge,
+    Braille,
+    Line,
+}
+
+impl ProgressStyle {
+    pub fn name(&self) -> &'static str {
+        match self {
+            ProgressStyle::Gauge => "gauge",
+            ProgressStyle::Braille => "braille",
+            ProgressStyle::Line => "line",
+        }
+    }
+    
+    fn from_name(name: &str) -> Self {
+        match name {
+            "braille" => ProgressStyle::Braille,
+            "line" => ProgressStyle::Line,
+            _ => ProgressStyle::Gauge,
+        }
+    }
+    
+    fn next(&self) -> Self {
+        match self {
+            ProgressStyle::Gauge => ProgressStyle::Braille,
+            ProgressStyle::Braille => ProgressStyle::Line,
+            ProgressStyle::Line => ProgressStyle::Gauge,
+        }
+    }
+}
+
+/// How the Home screen shows the tags
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TagLayout {
+    List, // Side panel beside the timer
+    Tabs, // One-row tab bar above the timer
+}
+
+impl TagLayout {
+    pub fn name(&self) -> &'static str {
+        match self {
+            TagLayout::List => "list",
+            TagLayout::Tabs => "tabs",
+        }
+    }
+    
+    fn from_name(name: &str) -> Self {
+        match name {
+            "tabs" => TagLayout::Tabs,
+            _ => TagLayout::List,
+        }
+    }
+    
+    fn next(&self) -> Self {
+        match self {
+            TagLayout::List => TagLayout::Tabs,
+            TagLayout::Tabs => TagLayout::List,
+        }
+    }
+}
+
+/// How the Stats screen colors its bars
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum BarColoring {
+    Flat,      // Every bar in the primary color
+    Intensity, // Shaded by value against the tallest bar, like the heatmap
+}
+
+impl BarColoring {
+    pub fn name(&self) -> &'static str {
+        match self {
+            BarColoring::Flat => "flat",
+            BarColoring::Intensity => "intensity",
+        }
+    }
+    
+    fn from_name(name: &str) -> Self {
+        match name {
+            "intensity" => BarColoring::Intensity,
+            _ => BarColoring::Flat,
+        }
+    }
+    
+    fn next(&self) -> Self {
+        match self {
+            BarColoring::Flat => BarColoring::Intensity,
+            BarColoring::Intensity => BarColoring::Flat,
+        }
+    }
+}
+
+/// Desktop notification urgency, as understood by `notify-send -u`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Urgency {
+    Low,
+    Normal,
+    Critical,
+}
+
+impl Urgency {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Urgency::Low => "low",
+            Urgency::Normal => "normal",
+            Urgency::Critical => "critical",
+        }
+    }
+    
+    fn from_name(name: &str) -> Self {
+        match name {
+            "low" => Urgency::Low,
+            "critical" => Urgency::Critical,
+            _ => Urgency::Normal,
+        }
+    }
+    
+    fn next(&self) -> Self {
+        match self {
+            Urgency::Low => Urgency::Normal,
+            Urgency::Normal => Urgency::Critical,
+            Urgency::Critical => Urgency::Low,
+        }
+    }
+}
+
+/// When the count of work sessions towards a long break starts over,
+/// besides after each long break
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CycleReset {
+    Never,
+    Daily,
+    Weekly,
+}
+
+impl CycleReset {
+    pub fn name(&self) -> &'static str {
+        match self {
+            CycleReset::Never => "never",
+            CycleReset::Daily => "daily",
+            CycleReset::Weekly => "weekly",
+        }
+    }
+    
+    fn from_name(name: &str) -> Self {
+        match name {
+            "daily" => CycleReset::Daily,
+            "weekly" => CycleReset::Weekly,
+            _ => CycleReset::Never,
+        }
+    }
+    
+    fn next(&self) -> Self {
+        match self {
+            CycleReset::Never => CycleReset::Daily,
+            CycleReset::Daily => CycleReset::Weekly,
+            CycleReset::Weekly => CycleReset::Never,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Setting {
+    Theme,
+    NightTheme,
+    NightHours,
+    ClockFormat,
+    ProgressStyle,
+    TagLayout,
+    BarColoring,
+    DefaultTag,
+    IntentionPrompt,
+    NotePrompt,
+    RatingPrompt,
+    CompletionFlash,
+    Overtime,
+    CountUp,
+    Silent,
+    WorkUrgency,
+    BreakUrgency,
+    NotificationTimeout,
+    Sound,
+    SoundPlayer,
+    SoundPath,
+    TickSound,
+    TickSoundPath,
+    TickInterval,
+    FinalCountdown,
+    FocusMusic,
+    FocusMusicStart,
+    FocusMusicStop,
+    DailyGoal,
+    WeeklyGoal,
+    PomodoroGoal,
+    WeekStart,
+    RestDays,
+    StreakFreezes,
+    HeatmapThresholds,
+    Milestones,
+    BreakReminder,
+    BreakLock,
+    BreakTag,
+    BreakSuggestions,
+    BreakSuggestionList,
+    FocusLock,
+    SkipConfirmations,
+    RecentBlocks,
+    BlockGap,
+    LongBreakEvery,
+    LongBreakLength,
+    CycleReset,
+    MaxWork,
+    MaxBreak,
+    AutoPause,
+    AutosaveInterval,
+    IdlePoll,
+    DayRollover,
+    Retention,
+}
+
+impl Setting {
+    pub const ALL: [Setting; 55] = [
+        Setting::Theme,
+        Setting::NightTheme,
+        Setting::NightHours,
+        Setting::ClockFormat,
+        Setting::ProgressStyle,
+        Setting::TagLayout,
+        Setting::BarColoring,
+        Setting::DefaultTag,
+        Setting::IntentionPrompt,
+        Setting::NotePrompt,
+        Setting::RatingPrompt,
+        Setting::CompletionFlash,
+        Setting::Overtime,
+        Setting::CountUp,
+        Setting::Silent,
+        Setting::WorkUrgency,
+        Setting::BreakUrgency,
+        Setting::NotificationTimeout,
+        Setting::Sound,
+        Setting::SoundPlayer,
+        Setting::SoundPath,
+        Setting::TickSound,
+        Setting::TickSoundPath,
+        Setting::TickInterval,
+        Setting::FinalCountdown,
+        Setting::FocusMusic,
+        Setting::FocusMusicStart,
+        Setting::FocusMusicStop,
+        Setting::DailyGoal,
+        Setting::WeeklyGoal,
+        Setting::PomodoroGoal,
+        Setting::Milestones,
+        Setting::BreakReminder,
+        Setting::BreakLock,
+        Setting::BreakTag,
+        Setting::BreakSuggestions,
+        Setting::BreakSuggestionList,
+        Setting::FocusLock,
+        Setting::SkipConfirmations,
+        Setting::RecentBlocks,
+        Setting::BlockGap,
+        Setting::LongBreakEvery,
+        Setting::LongBreakLength,
+        Setting::CycleReset,
+        Setting::MaxWork,
+        Setting::MaxBreak,
+        Setting::WeekStart,
+        Setting::RestDays,
+        Setting::StreakFreezes,
+        Setting::DayRollover,
+        Setting::Retention,
+        Setting::HeatmapThresholds,
+        Setting::AutoPause,
+        Setting::AutosaveInterval,
+        Setting::IdlePoll,
+    ];
+    
+    pub fn label(&self) -> &'static str {
+        match self {
+            Setting::Theme => "Color theme",
+            Setting::NightTheme => "Night theme (off keeps the color theme all day)",
+            Setting::NightHours => "Night theme hours (start-end)",
+            Setting::ClockFormat => "Clock format",
+            Setting::ProgressStyle => "Progress bar style",
+            Setting::TagLayout => "Tag layout (list beside or tabs above the timer)",
+            Setting::BarColoring => "Stats bar colors (flat or by intensity)",
+            Setting::DefaultTag => "Default tag (selected at first start)",
+            Setting::IntentionPrompt => "Ask for an intention before work sessions",
+            Setting::NotePrompt => "Prompt for a note after work sessions",
+            Setting::RatingPrompt => "Rate focus (1-5) after work sessions",
+            Setting::CompletionFlash => "Flash the timer when a session ends",
+            Setting::Overtime => "Keep counting past zero (overtime)",
+            Setting::CountUp => "Show time elapsed instead of time left",
+            Setting::Silent => "Silent mode (no sounds or notifications)",
+            Setting::WorkUrgency => "Urgency when work ends",
+            Setting::BreakUrgency => "Urgency when a break ends",
+            Setting::NotificationTimeout => "Notification timeout in seconds (0 = default)",
+            Setting::Sound => "Sounds",
+            Setting::SoundPlayer => "Sound players, tried in order",
+            Setting::SoundPath => "Completion sound file",
+            Setting::TickSound => "Ticking sound while running",
+            Setting::TickSoundPath => "Tick sound file",
+            Setting::TickInterval => "Tick every N seconds",
+            Setting::FinalCountdown => "Final countdown (seconds, 0 = off)",
+            Setting::FocusMusic => "Run music commands with work sessions",
+            Setting::FocusMusicStart => "Command when work starts/resumes",
+            Setting::FocusMusicStop => "Command on pause, break or reset",
+            Setting::DailyGoal => "Daily goal in minutes (0 = off)",
+            Setting::WeeklyGoal => "Weekly goal in minutes (0 = off)",
+            Setting::PomodoroGoal => "Daily goal in pomodoros (0 = off)",
+            Setting::Milestones => "Daily milestones in minutes, e.g. 30,60,120",
+            Setting::BreakReminder => "Remind to rest after N min of work in an hour (0 = off)",
+            Setting::BreakLock => "Lock breaks until N% has passed (0 = off)",
+            Setting::BreakTag => "Log breaks under the preceding work session's tags",
+            Setting::BreakSuggestions => "Suggest something to do on each break",
+            Setting::BreakSuggestionList => "Break suggestions, separated by ;",
+            Setting::FocusLock => "Focus lock: q can't quit during work (Q can)",
+            Setting::SkipConfirmations => "Skip confirmations (delete tag, reset, purge)",
+            Setting::RecentBlocks => "Group recent sessions into work blocks",
+            Setting::BlockGap => "Longest gap within a work block (minutes)",
+            Setting::LongBreakEvery => "Long break after N work sessions (0 = off)",
+            Setting::LongBreakLength => "Long break length (minutes)",
+            Setting::CycleReset => "Restart the long break count",
+            Setting::MaxWork => "Longest work session (minutes)",
+            Setting::MaxBreak => "Longest break (minutes)",
+            Setting::WeekStart => "First day of the week (heatmap)",
+            Setting::RestDays => "Rest days that keep the streak, e.g. sat,sun",
+            Setting::StreakFreezes => "Other missed days a streak survives",
+            Setting::HeatmapThresholds => "Absolute heatmap thresholds (minutes)",
+            Setting::AutoPause => "Pause work while viewing Stats/Heatmap",
+            Setting::AutosaveInterval => "Save the running timer every N seconds",
+            Setting::IdlePoll => "Redraw every N ms while stopped (100-1000)",
+            Setting::DayRollover => "New day starts at hour (0-23)",
+            Setting::Retention => "Keep sessions for days (0 = forever, [P] purges)",
+        }
+    }
+    
+    /// Settings edited by typing a value rather than toggling/cycling
+    pub fn is_text(&self) -> bool {
+        matches!(
+            self,
+            Setting::SoundPlayer
+                | Setting::DefaultTag
+                | Setting::SoundPath
+                | Setting::TickSoundPath
+                | Setting::TickInterval
+                | Setting::FinalCountdown
+                | Setting::NotificationTimeout
+                | Setting::FocusMusicStart
+                | Setting::FocusMusicStop
+                | Setting::DailyGoal
+                | Setting::WeeklyGoal
+                | Setting::PomodoroGoal
+                | Setting::HeatmapThresholds
+                | Setting::RestDays
+                | Setting::StreakFreezes
+                | Setting::Milestones
+                | Setting::BreakReminder
+                | Setting::BreakLock
+                | Setting::BreakSuggestionList
+                | Setting::BlockGap
+                | Setting::LongBreakEvery
+                | Setting::LongBreakLength
+                | Setting::MaxWork
+                | Setting::MaxBreak
+                | Setting::AutosaveInterval
+                | Setting::IdlePoll
+                | Setting::DayRollover
+                | Setting::NightHours
+                | Setting::Retention
+        )
+    }
+}
+
+const DEFAULT_SOUND_PLAYER: &str = "paplay, aplay";
+const DEFAULT_TICK_SOUND: &str = "/usr/share/sounds/freedesktop/stereo/audio-volume-change.oga";
+
+fn default_sound_path() -> String {
+    let home = std::env::var("HOME").unwrap_or_else(|_| String::from("/home"));
+    format!("{}/Music/sf/vieboom.mp3", home)
 }
 
+/// Today's progress for the "today at a glance" card
+#[derive(Debug, Clone, Default)]
+pub struct TodaySummary {
+    pub day: chrono::NaiveDate, // The day the figures were loaded for
+    pub sessions: i64,
+    pub seconds: i64,
+    pub week_seconds: i64,
+    pub streak: u32,
+}
+
+impl TodaySummary {
+    fn load(db: &Database, week_start: chrono::NaiveDate) -> Self {
+        let today = db.today();
+        let worked_days: Vec<_> = db.get_heatmap_data().unwrap_or_default().into_iter().map(|(day, _)| day).collect();
+        TodaySummary {
+            day: today,
+            sessions: db.get_session_count_today(),
+            seconds: db.get_total_today(),
+            week_seconds: db.get_total_since(week_start),
+            streak: crate::export::current_streak(&worked_days, today, &RestDays::load(db)),
+        }
+    }
+}
+
+/// Where the app reads the current time from, so tests can pin it
+pub trait Clock {
+    fn now(&self) -> DateTime<Local>;
+}
+
+/// The real wall clock
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> DateTime<Local> {
+        Local::now()
+    }
+}
+
+/// Session in progress, mode, running, and remaining seconds while paused
+type TimerSnapshot = (bool, PomodoroMode, bool, u64);
+
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub enum InputMode {
     Normal,
@@ -36,31 +546,251 @@ pub struct App {
     pub mode: PomodoroMode,
     pub remaining_seconds: u64,
     pub selected_tag_index: usize,
+    pub default_tag: String, // Selected when nothing else was, and used for untagged sessions
+    pub marked_tags: Vec<String>, // Tags combined into the next session, empty = selected tag only
     pub tags: Vec<String>,
+    pub all_tags: Vec<String>,
+    pub archived_tags: Vec<String>,
+    pub archived_index: usize,
+    pub templates: Vec<Template>,
+    pub template_index: usize,
+    pub merge_targets: Vec<String>, // Tags the Stats tag can be merged into
+    pub merge_index: usize,
     pub work_duration: u64,
     pub break_duration: u64,
+    pub long_break_every: u64, // Work sessions per cycle, 0 = no long breaks
+    pub long_break_duration: u64,
+    pub long_break: bool, // The current break is a long one
+    pub cycle_reset: CycleReset,
+    pub max_work_duration: u64,  // Upper clamp for work_duration, in seconds
+    pub max_break_duration: u64, // Upper clamp for break_duration, in seconds
     pub db: Database,
+    pub clock: Box<dyn Clock>,
     pub should_quit: bool,
+    pub status_message: Option<String>, // One-off feedback shown in the help bar
     
     // Stats state
     pub stats_view: StatsView,
-    pub stats_tag_index: usize, // 0 = All, 1+ = specific tag
+    pub stats_tag_index: usize, // 0 = All, 1+ = specific tag (from all_tags)
+    pub stats_breakdown: bool,  // Split "All Tags" into one bar per tag
+    pub stats_by_category: bool, // Split by category ("Study" for "Study/Math") instead of tag
+    pub stats_leaderboard: bool, // Rank tags by focus time in place of the chart
+    pub stats_bucket: usize, // Highlighted bar, clamped to the last one
+    pub stats_ratings: Vec<Option<f64>>, // Average focus rating of each bar, loaded with the chart
+    pub bucket_sessions: Vec<Session>, // Sessions behind the highlighted bar, when drilled into
+    pub bucket_session_index: usize,
+    pub heatmap_tag_index: usize, // 0 = All, 1+ = specific tag (from all_tags)
+    pub heatmap_offset: u32, // Months the heatmap is scrolled back from today
     
     // Input state
     pub input_mode: InputMode,
     pub input_buffer: String,
+    pub input_error: Option<&'static str>,
+    
+    // Settings state
+    pub settings_index: usize,
+    settings_saved: Vec<String>, // Values when the Settings screen opened or was last saved
+    settings_values: Vec<String>, // Values shown on the Settings screen, unsaved ones included
+    settings_draft: HashMap<&'static str, String>, // Config changes held back until Settings are saved
+    pub theme: Theme, // Active palette: the night theme during its hours, else the color theme
+    pub day_theme: Theme,
+    pub night_theme: Option<Theme>,
+    pub night_hours: (u32, u32), // Start and end hour; may wrap past midnight
+    pub ascii: bool, // Plain ASCII stand-ins for emoji and shading
+    pub clock_24h: bool,
+    pub progress_style: ProgressStyle,
+    pub tag_layout: TagLayout,
+    pub bar_coloring: BarColoring,
+    pub intention_prompt: bool,
+    pub note_prompt: bool,
+    pub rating_prompt: bool,
+    pub completion_flash: bool,
+    pub overtime_mode: bool,
+    pub auto_pause: bool,
+    pub focus_lock: bool, // `q` is ignored while work runs; `Q` still quits
+    pub skip_confirmations: bool, // Fast mode: destructive actions happen without asking
+    pub break_follows_work: bool, // Breaks are logged under the tags of the work before them
+    last_work_tags: Vec<String>, // Tags of the last completed work session
+    pub break_suggestions: bool,
+    pub break_suggestion_list: Vec<String>,
+    pub break_suggestion: Option<String>, // Picked as each break starts, shown only during breaks
+    pub compact: bool, // Home shows only the timer, full width
+    pub count_up: bool, // Display elapsed time rather than remaining; completion is unchanged
+    pub incognito: bool, // The current session won't be logged
+    pub abandoned_session: Option<TimerState>, // Left behind by a crash or forced quit
+    pub sound_enabled: bool,
+    pub silent: bool, // Mutes sounds and desktop notifications alike
+    pub work_urgency: Urgency,
+    pub break_urgency: Urgency,
+    pub notification_timeout: u64, // Seconds on screen, 0 = the daemon's default
+    pub sound_player: String, // Comma-separated commands, each taking the sound file
+    sound_failed: Arc<AtomicBool>, // Set by the player thread when no player managed to play
+    pub sound_path: String,
+    pub tick_sound: bool,
+    pub tick_sound_path: String,
+    pub tick_interval: u64,
+    pub final_countdown: u64, // Tick and flash during the last N seconds; 0 turns it off
+    last_countdown_second: Option<u64>, // Remaining second the countdown last ticked at
+    pub focus_music: bool,
+    pub focus_music_start: String, // Shell command, e.g. "mpc play"
+    pub focus_music_stop: String,
+    music_playing: bool, // The start command ran more recently than the stop command
+    pub autosave_interval: u64, // Seconds between timer state saves while nothing changes
+    pub idle_poll_ms: u64, // Event loop poll interval while nothing runs, to save wakeups
+    last_autosave: Option<(Instant, TimerSnapshot)>,
+    pub daily_goal: u64, // Minutes of work per day, 0 = no goal
+    pub weekly_goal: u64, // Minutes of work per week, 0 = no goal
+    pub daily_pomodoro_goal: u64, // Work sessions per day, 0 = no goal
+    pub milestones: Vec<u64>, // Minutes of work per day, ascending
+    pub break_reminder: u64, // Minutes of work within an hour before suggesting a break, 0 = off
+    break_reminder_sent: bool, // Reminded already; reset once a break is logged
+    pub break_lock: u64, // Percent of a break to sit out before work can start, 0 = off
+    next_break_check: Instant,
+    milestones_reached: (chrono::NaiveDate, Vec<u64>), // Milestones already announced, by day
+    pomodoro_goal_reached: Option<chrono::NaiveDate>, // Day the pomodoro goal was last announced
+    pub week_start: Weekday,
+    pub heatmap_absolute: bool, // Bucket heatmap cells by fixed thresholds instead of the busiest day
+    pub heatmap_thresholds: [i64; 3],
+    pub heatmap_sessions: bool, // Shade the heatmap by session count instead of minutes
+    tick_player: Option<Child>, // Last tick sound, to avoid overlapping playback
     
     // Session tracking
     pub session_start: Option<chrono::DateTime<chrono::Local>>,
+    pub last_session_id: Option<i64>,
+    prompt_session: Option<i64>, // Finished work session the rating and note prompts ask about
+    queued_prompt: Option<Screen>, // Prompt waiting for the user to be back on Home
+    pub intention: Option<String>, // Stated before the work session, saved as its note
+    last_completion: Option<(i64, PomodoroMode, Instant)>, // Logged session that can still be undone
+    pub recent_sessions: Vec<Session>,
+    pub last_worked: HashMap<String, String>, // Start of each tag's latest work session
+    pub recent_blocks: bool, // The Recent panel lists work blocks instead of single sessions
+    pub block_gap: u64, // Minutes allowed between sessions of one block
+    pub session_blocks: Vec<SessionBlock>, // Newest first, loaded only while `recent_blocks` is on
+    pub today_summary: TodaySummary, // Reloaded whenever sessions are logged or removed, and at the day rollover
+    cycle_work: u64, // Work sessions towards the next long break, reloaded with `today_summary`
+    pub backup_message: String, // Outcome of the last database backup
+    pub export_format: ExportFormat, // Picked on the Stats screen
+    pub export_message: String, // Outcome of the last session export
+    pub review_cards: Vec<ReviewCard>, // Loaded when the week review opens
+    pub review_index: usize,
+    pub in_overtime: bool,
+    pub overtime_seconds: u64,
+    aligned_length: Option<u64>, // Length of the current session once aligned to a round end time
+    pub pause_started: Option<Instant>, // Set while paused mid-session
+    pub completion_flash_until: Option<Instant>,
+    limit_hint: Option<(String, Instant)>, // Shown in the settings bar when w/W or b/B hit a limit
+    auto_paused: bool, // Paused by leaving Home, resumed on return
 }
 
 impl App {
     pub fn new() -> Result<Self, rusqlite::Error> {
-        let db = Database::new()?;
+        Self::with_database(Database::new()?)
+    }
+    
+    pub fn with_database(db: Database) -> Result<Self, rusqlite::Error> {
         let tags = db.get_tags()?;
+        // Saved by name, since the order changes as tags come and go
+        let selected_tag = db.get_config("selected_tag", "");
+        let default_tag = db.get_config("default_tag", DEFAULT_TAG);
+        let selected_tag_index = tags.iter().position(|tag| *tag == selected_tag)
+            .or_else(|| tags.iter().position(|tag| *tag == default_tag))
+            .unwrap_or(0);
+        let recent_sessions = db.get_recent_sessions(RECENT_SESSION_COUNT)?;
+        let last_worked = db.get_last_session_per_tag().unwrap_or_default();
+        let recent_blocks = db.get_config("recent_blocks", "false") == "true";
+        let block_gap = db.get_config_clamped("block_gap_minutes", DEFAULT_BLOCK_GAP_MINUTES, 0..=MAX_BLOCK_GAP_MINUTES);
+        let session_blocks = if recent_blocks { load_session_blocks(&db, block_gap) } else { Vec::new() };
+        // A clean quit clears the saved state, so one left over means the app died mid-session
+        let abandoned_session = db.load_timer_state()
+            .filter(|state| state.elapsed_seconds as i64 >= MIN_SESSION_SECONDS);
+        let all_tags = with_categories(db.get_all_tags()?);
+        let archived_tags = db.get_archived_tags()?;
+        let templates = db.get_templates()?;
         
-        let work_duration: u64 = db.get_config("work_duration", "1500").parse().unwrap_or(1500);
-        let break_duration: u64 = db.get_config("break_duration", "300").parse().unwrap_or(300);
+        // Durations are clamped on read, so a bad value in the database can't
+        // slip past the limits the settings enforce
+        let ceiling = DURATION_CEILING_MINUTES * 60;
+        let max_work_duration = db.get_config_clamped("max_work_duration", DEFAULT_MAX_WORK_SECONDS, MIN_DURATION_SECONDS..=ceiling);
+        let max_break_duration = db.get_config_clamped("max_break_duration", DEFAULT_MAX_BREAK_SECONDS, MIN_DURATION_SECONDS..=ceiling);
+        let work_duration = db.get_config_clamped("work_duration", 1500, MIN_DURATION_SECONDS..=max_work_duration);
+        let break_duration = db.get_config_clamped("break_duration", 300, MIN_DURATION_SECONDS..=max_break_duration);
+        let long_break_every: u64 = db.get_config("long_break_every", "0").parse().unwrap_or(0);
+        let cycle_reset = CycleReset::from_name(&db.get_config("cycle_reset", "never"));
+        let long_break_duration = db.get_config_clamped("long_break_duration", 900, MIN_DURATION_SECONDS..=max_break_duration);
+        let theme = Theme::by_name(&db.get_config("theme", Theme::DEFAULT.name));
+        let night_theme = match db.get_config("night_theme", "off").as_str() {
+            "off" => None,
+            name => Some(Theme::by_name(name)),
+        };
+        let night_hours = (
+            db.get_config("night_theme_start", "21").parse().unwrap_or(21),
+            db.get_config("night_theme_end", "7").parse().unwrap_or(7),
+        );
+        // Plain ASCII when chosen with `v`, otherwise when the terminal seems to need it
+        let ascii = match db.get_config("ascii_mode", "auto").as_str() {
+            "true" => true,
+            "false" => false,
+            _ => Glyphs::terminal_lacks_unicode(),
+        };
+        let clock_24h = db.get_config("clock_format", "24h") != "12h";
+        let progress_style = ProgressStyle::from_name(&db.get_config("progress_style", "gauge"));
+        let tag_layout = TagLayout::from_name(&db.get_config("tag_layout", "list"));
+        let bar_coloring = BarColoring::from_name(&db.get_config("bar_coloring", "flat"));
+        let export_format = ExportFormat::from_extension(&db.get_config("export_format", "csv"));
+        let intention_prompt = db.get_config("intention_prompt", "false") == "true";
+        let note_prompt = db.get_config("note_prompt", "false") == "true";
+        let rating_prompt = db.get_config("rating_prompt", "false") == "true";
+        let completion_flash = db.get_config("completion_flash", "true") == "true";
+        let overtime_mode = db.get_config("overtime_mode", "false") == "true";
+        let auto_pause = db.get_config("auto_pause", "false") == "true";
+        let focus_lock = db.get_config("focus_lock", "false") == "true";
+        let break_follows_work = db.get_config("break_follows_work", "false") == "true";
+        let break_suggestions = db.get_config("break_suggestions", "false") == "true";
+        let break_suggestion_list = parse_break_suggestions(&db.get_config("break_suggestion_list", DEFAULT_BREAK_SUGGESTIONS))
+            .unwrap_or_else(|_| parse_break_suggestions(DEFAULT_BREAK_SUGGESTIONS).unwrap_or_default());
+        let skip_confirmations = db.get_config("skip_confirmations", "false") == "true";
+        let compact = db.get_config("compact_mode", "false") == "true";
+        let count_up = db.get_config("count_up", "false") == "true";
+        let sound_enabled = db.get_config("sound_enabled", "true") == "true";
+        let silent = db.get_config("silent", "false") == "true";
+        let work_urgency = Urgency::from_name(&db.get_config("work_urgency", "normal"));
+        let break_urgency = Urgency::from_name(&db.get_config("break_urgency", "normal"));
+        let notification_timeout: u64 = db.get_config("notification_timeout", "0").parse().unwrap_or(0);
+        let sound_player = db.get_config("sound_player", DEFAULT_SOUND_PLAYER);
+        let sound_path = db.get_config("sound_path", &default_sound_path());
+        let tick_sound = db.get_config("tick_sound", "false") == "true";
+        let tick_sound_path = db.get_config("tick_sound_path", DEFAULT_TICK_SOUND);
+        let tick_interval: u64 = db.get_config("tick_interval", "1").parse().unwrap_or(1).max(1);
+        let final_countdown = db.get_config_clamped("final_countdown", 0, 0..=MAX_FINAL_COUNTDOWN);
+        let focus_music = db.get_config("focus_music", "false") == "true";
+        let focus_music_start = db.get_config("focus_music_start", "");
+        let focus_music_stop = db.get_config("focus_music_stop", "");
+        let autosave_interval: u64 = db.get_config("autosave_interval", "15").parse().unwrap_or(15).max(1);
+        let idle_poll_ms: u64 = db.get_config("idle_poll_ms", "500").parse().unwrap_or(500).clamp(ACTIVE_POLL_MS, 1000);
+        let heatmap_absolute = db.get_config("heatmap_absolute", "false") == "true";
+        let heatmap_sessions = db.get_config("heatmap_sessions", "false") == "true";
+        let heatmap_thresholds = parse_thresholds(&db.get_config("heatmap_thresholds", "15,30,60")).unwrap_or([15, 30, 60]);
+        let daily_goal: u64 = db.get_config("daily_goal", "0").parse().unwrap_or(0);
+        let weekly_goal: u64 = db.get_config("weekly_goal_minutes", "0").parse().unwrap_or(0);
+        let daily_pomodoro_goal: u64 = db.get_config("daily_pomodoro_goal", "0").parse().unwrap_or(0);
+        let break_reminder: u64 = db.get_config("break_reminder", "0").parse().unwrap_or(0);
+        let break_lock: u64 = db.get_config("break_lock", "0").parse().unwrap_or(0).min(100);
+        let milestones = parse_milestones(&db.get_config("milestones", "")).unwrap_or_default();
+        // Milestones passed before this launch have been announced already
+        let today_minutes = (db.get_total_today() / 60) as u64;
+        let milestones_reached = (
+            db.today(),
+            milestones.iter().copied().filter(|m| *m <= today_minutes).collect(),
+        );
+        let pomodoro_goal_reached = (daily_pomodoro_goal > 0 && db.get_session_count_today() as u64 >= daily_pomodoro_goal)
+            .then(|| db.today());
+        let week_start = if db.get_config("week_start", "monday") == "sunday" {
+            Weekday::Sun
+        } else {
+            Weekday::Mon
+        };
+        let today_summary = TodaySummary::load(&db, week_start_on_or_before(db.today(), week_start));
+        let cycle_work = count_work_in_cycle(&db, cycle_reset, week_start_on_or_before(db.today(), week_start));
         
         Ok(App {
             current_screen: Screen::Home,
@@ -68,17 +798,133 @@ impl App {
             timer_running: false,
             mode: PomodoroMode::Work,
             remaining_seconds: work_duration,
-            selected_tag_index: 0,
+            selected_tag_index,
+            default_tag,
+            marked_tags: Vec::new(),
             tags,
+            all_tags,
+            archived_tags,
+            archived_index: 0,
+            templates,
+            template_index: 0,
+            merge_targets: Vec::new(),
+            merge_index: 0,
             work_duration,
             break_duration,
+            long_break_every,
+            cycle_reset,
+            long_break_duration,
+            long_break: false,
+            max_work_duration,
+            max_break_duration,
             db,
+            clock: Box::new(SystemClock),
             should_quit: false,
+            status_message: None,
             stats_view: StatsView::Weekly,
             stats_tag_index: 0,
+            heatmap_tag_index: 0,
+            heatmap_offset: 0,
+            stats_breakdown: false,
+            stats_by_category: false,
+            stats_leaderboard: false,
+            stats_bucket: usize::MAX,
+            stats_ratings: Vec::new(),
+            bucket_sessions: Vec::new(),
+            bucket_session_index: 0,
             input_mode: InputMode::Normal,
             input_buffer: String::new(),
+            input_error: None,
+            settings_index: 0,
+            settings_saved: Vec::new(),
+            settings_values: Vec::new(),
+            settings_draft: HashMap::new(),
+            theme,
+            day_theme: theme,
+            night_theme,
+            night_hours,
+            ascii,
+            clock_24h,
+            progress_style,
+            tag_layout,
+            bar_coloring,
+            intention_prompt,
+            note_prompt,
+            rating_prompt,
+            completion_flash,
+            overtime_mode,
+            auto_pause,
+            focus_lock,
+            break_follows_work,
+            skip_confirmations,
+            last_work_tags: Vec::new(),
+            break_suggestions,
+            break_suggestion_list,
+            break_suggestion: None,
+            compact,
+            count_up,
+            incognito: false,
+            abandoned_session,
+            sound_enabled,
+            silent,
+            work_urgency,
+            break_urgency,
+            notification_timeout,
+            sound_player,
+            sound_failed: Arc::new(AtomicBool::new(false)),
+            sound_path,
+            tick_sound,
+            tick_sound_path,
+            tick_interval,
+            final_countdown,
+            last_countdown_second: None,
+            focus_music,
+            focus_music_start,
+            focus_music_stop,
+            music_playing: false,
+            autosave_interval,
+            idle_poll_ms,
+            last_autosave: None,
+            daily_goal,
+            weekly_goal,
+            daily_pomodoro_goal,
+            milestones,
+            milestones_reached,
+            pomodoro_goal_reached,
+            break_reminder,
+            break_reminder_sent: false,
+            break_lock,
+            next_break_check: Instant::now(),
+            week_start,
+            heatmap_absolute,
+            heatmap_thresholds,
+            heatmap_sessions,
+            tick_player: None,
             session_start: None,
+            last_session_id: None,
+            prompt_session: None,
+            queued_prompt: None,
+            intention: None,
+            last_completion: None,
+            recent_sessions,
+            last_worked,
+            recent_blocks,
+            block_gap,
+            session_blocks,
+            today_summary,
+            cycle_work,
+            backup_message: String::new(),
+            export_format,
+            export_message: String::new(),
+            review_cards: Vec::new(),
+            review_index: 0,
+            in_overtime: false,
+            overtime_seconds: 0,
+            aligned_length: None,
+            pause_started: None,
+            completion_flash_until: None,
+            limit_hint: None,
+            auto_paused: false,
         })
     }
     
@@ -86,63 +932,384 @@ impl App {
         self.tags.get(self.selected_tag_index).map(|s| s.as_str())
     }
     
+    /// Tags a session is logged under: the marked tags in list order,
+    /// or just the selected tag when none are marked
+    pub fn session_tags(&self) -> Vec<&str> {
+        if self.marked_tags.is_empty() {
+            vec![self.selected_tag().unwrap_or(&self.default_tag)]
+        } else {
+            self.tags
+                .iter()
+                .filter(|tag| self.marked_tags.contains(tag))
+                .map(|tag| tag.as_str())
+                .collect()
+        }
+    }
+    
+    /// Tags the session in progress is logged under: `session_tags`, except that
+    /// a break can follow the work session before it
+    pub fn logged_tags(&self) -> Vec<&str> {
+        if self.mode == PomodoroMode::Break && self.break_follows_work && !self.last_work_tags.is_empty() {
+            self.last_work_tags.iter().map(String::as_str).collect()
+        } else {
+            self.session_tags()
+        }
+    }
+    
+    /// Add the selected tag to, or remove it from, the session's tag combination
+    pub fn toggle_tag_mark(&mut self) {
+        if let Some(tag) = self.selected_tag().map(|tag| tag.to_string()) {
+            if let Some(pos) = self.marked_tags.iter().position(|t| *t == tag) {
+                self.marked_tags.remove(pos);
+            } else {
+                self.marked_tags.push(tag);
+            }
+        }
+    }
+    
     pub fn toggle_timer(&mut self) {
-        if self.timer_running {
+        if self.in_overtime {
+            // Stopping overtime finishes the session
+            self.complete_session();
+        } else if self.timer_running {
             self.timer_running = false;
+            self.pause_started = Some(Instant::now());
+        } else if self.intention_prompt && self.session_start.is_none() && self.mode == PomodoroMode::Work {
+            // Ask what the session is for; answering or skipping starts it
+            self.input_buffer.clear();
+            self.input_mode = InputMode::Editing;
+            self.navigate_to(Screen::IntentionInput);
         } else {
-            self.timer_running = true;
-            if self.session_start.is_none() {
-                self.session_start = Some(chrono::Local::now());
-            }
+            self.start_timer();
+        }
+        self.sync_focus_music();
+    }
+    
+    fn start_timer(&mut self) {
+        self.timer_running = true;
+        self.pause_started = None;
+        if self.session_start.is_none() {
+            self.session_start = Some(self.clock.now());
+        }
+        self.sync_focus_music();
+    }
+    
+    /// Run the focus music start command while work is running and the stop
+    /// command once it isn't, each only when that state changes
+    fn sync_focus_music(&mut self) {
+        let wanted = self.focus_music && !self.silent && self.timer_running && self.mode == PomodoroMode::Work;
+        if wanted == self.music_playing {
+            return;
+        }
+        self.music_playing = wanted;
+        
+        let command = if wanted { &self.focus_music_start } else { &self.focus_music_stop };
+        if command.trim().is_empty() {
+            return;
+        }
+        let command = command.clone();
+        std::thread::spawn(move || {
+            let _ = Command::new("sh")
+                .arg("-c")
+                .arg(&command)
+                .stdout(Stdio::null())
+                .stderr(Stdio::null())
+                .status();
+        });
+    }
+    
+    /// Stop the focus music when quitting mid-session, waiting for the command
+    /// so it isn't cut off by the exit
+    pub fn stop_focus_music(&mut self) {
+        if self.music_playing && !self.focus_music_stop.trim().is_empty() {
+            let _ = Command::new("sh")
+                .arg("-c")
+                .arg(&self.focus_music_stop)
+                .stdout(Stdio::null())
+                .stderr(Stdio::null())
+                .status();
+        }
+        self.music_playing = false;
+    }
+    
+    /// Start the work session with the intention typed in the popup, if any
+    pub fn start_with_intention(&mut self) {
+        let intention = self.input_buffer.trim();
+        self.intention = (!intention.is_empty()).then(|| intention.to_string());
+        self.start_timer();
+    }
+    
+    /// Select the n-th tag (1-based) and start a work session with it. A
+    /// session already in progress, even paused, keeps its tag.
+    pub fn quick_start(&mut self, number: usize) {
+        if number == 0 || number > self.tags.len() {
+            return;
+        }
+        if let Some(seconds) = self.break_unlocks_in() {
+            self.status_message = Some(format!("{} Rest first: the break unlocks in {:02}:{:02}", self.glyphs().lock, seconds / 60, seconds % 60));
+            return;
+        }
+        if self.session_start.is_some() {
+            self.status_message = Some("A session is in progress: finish or reset it first".to_string());
+            return;
+        }
+        self.selected_tag_index = number - 1;
+        self.remember_selected_tag();
+        
+        if self.mode == PomodoroMode::Break && self.session_start.is_none() {
+            self.mode = PomodoroMode::Work;
+            self.reset_timer();
+        }
+        self.toggle_timer();
+    }
+    
+    /// Seconds until a locked break lets work start again, or `None` when
+    /// nothing is locked. A break that hasn't started yet stays locked.
+    pub fn break_unlocks_in(&self) -> Option<u64> {
+        if self.break_lock == 0 || self.mode != PomodoroMode::Break {
+            return None;
+        }
+        let locked_for = self.session_length() * self.break_lock / 100;
+        let elapsed = self.session_length().saturating_sub(self.remaining_seconds);
+        (elapsed < locked_for).then(|| locked_for - elapsed)
+    }
+    
+    /// Reset from the `r` key: immediate for short or unstarted sessions,
+    /// confirmed first once there's real progress to lose
+    pub fn request_reset(&mut self) {
+        if self.session_start.is_some() && self.elapsed_seconds() >= RESET_CONFIRM_SECONDS && !self.skip_confirmations {
+            self.navigate_to(Screen::ResetConfirm);
+        } else {
+            self.reset_timer();
         }
     }
     
     pub fn reset_timer(&mut self) {
+        self.intention = None;
+        self.incognito = false;
         self.timer_running = false;
         self.session_start = None;
-        self.remaining_seconds = match self.mode {
+        self.pause_started = None;
+        self.in_overtime = false;
+        self.overtime_seconds = 0;
+        self.aligned_length = None;
+        self.long_break = self.mode == PomodoroMode::Break && self.long_break_due();
+        self.remaining_seconds = self.session_length();
+        self.sync_focus_music();
+    }
+    
+    /// Full length of a session in the current mode, in seconds
+    pub fn session_length(&self) -> u64 {
+        if let Some(length) = self.aligned_length {
+            return length;
+        }
+        match self.mode {
             PomodoroMode::Work => self.work_duration,
+            PomodoroMode::Break if self.long_break => self.long_break_duration,
             PomodoroMode::Break => self.break_duration,
+        }
+    }
+    
+    /// Stretch or shrink the current session so that it ends on the nearest
+    /// :00 or :30, or with `with_break` so that the break after it does, and
+    /// start it. The configured durations are left alone.
+    pub fn align_session_end(&mut self, with_break: bool) {
+        if self.in_overtime {
+            return;
+        }
+        let following = match (with_break, self.mode) {
+            (true, PomodoroMode::Work) if self.long_break_every > 0 && self.work_in_cycle() + 1 >= self.long_break_every => {
+                self.long_break_duration
+            }
+            (true, PomodoroMode::Work) => self.break_duration,
+            _ => 0,
+        };
+        let now = self.clock.now();
+        let since_midnight = now.num_seconds_from_midnight() as u64;
+        
+        // Round the natural end to the nearest boundary, skipping ones too close to be worth it
+        let natural_end = since_midnight + self.remaining_seconds + following;
+        let mut end = (natural_end + ALIGN_SECONDS / 2) / ALIGN_SECONDS * ALIGN_SECONDS;
+        while end < since_midnight + following + MIN_ALIGNED_SECONDS {
+            end += ALIGN_SECONDS;
+        }
+        let remaining = end - since_midnight - following;
+        let length = self.elapsed_seconds() + remaining;
+        let max = match self.mode {
+            PomodoroMode::Work => self.max_work_duration,
+            PomodoroMode::Break => self.max_break_duration,
         };
+        if length > max {
+            self.status_message = Some(format!("Can't align: the session would run past the {} min limit", max / 60));
+            return;
+        }
+        
+        self.aligned_length = Some(length);
+        self.remaining_seconds = remaining;
+        let end_time = (now + chrono::Duration::seconds((end - since_midnight) as i64)).format(self.clock_format());
+        self.status_message = Some(if following > 0 {
+            format!("Aligned: the break after this session ends at {}", end_time)
+        } else {
+            format!("Aligned: this session ends at {}", end_time)
+        });
+        if !self.timer_running {
+            self.toggle_timer();
+        }
+    }
+    
+    /// First day of the current week, which starts on `week_start`
+    pub fn current_week_start(&self) -> chrono::NaiveDate {
+        week_start_on_or_before(self.db.today(), self.week_start)
+    }
+    
+    /// Minutes worked this week against the weekly goal, or `None` when no goal is set
+    pub fn weekly_goal_progress(&self) -> Option<(u64, u64)> {
+        (self.weekly_goal > 0).then(|| {
+            ((self.db.get_total_since(self.current_week_start()) / 60) as u64, self.weekly_goal)
+        })
+    }
+    
+    /// Whether enough work sessions have been logged since the last long break.
+    /// Counting from the database keeps the cycle right across restarts.
+    fn long_break_due(&self) -> bool {
+        self.long_break_every > 0 && self.work_in_cycle() >= self.long_break_every
+    }
+    
+    /// Work sessions counted towards the next long break
+    fn work_in_cycle(&self) -> u64 {
+        count_work_in_cycle(&self.db, self.cycle_reset, self.current_week_start())
     }
     
-    pub fn tick(&mut self) {
-        if self.timer_running && self.remaining_seconds > 0 {
-            self.remaining_seconds -= 1;
+    /// Position of the current work session in the long break cycle, e.g.
+    /// `(3, 4)` for the third of four, or `None` without long breaks
+    pub fn cycle_position(&self) -> Option<(u64, u64)> {
+        if self.long_break_every == 0 || self.mode != PomodoroMode::Work {
+            return None;
+        }
+        Some(((self.cycle_work + 1).min(self.long_break_every), self.long_break_every))
+    }
+    
+    /// Advance the timer by the whole seconds elapsed since the last tick.
+    /// This can be far more than one after the machine wakes from suspend.
+    pub fn tick(&mut self, elapsed_seconds: u64) {
+        if self.tick_due() {
+            self.play_tick_sound();
+        }
+        
+        if self.timer_running && self.in_overtime {
+            self.overtime_seconds += elapsed_seconds;
+        } else if self.timer_running && self.remaining_seconds > 0 {
+            let overshoot = elapsed_seconds.saturating_sub(self.remaining_seconds);
+            self.remaining_seconds = self.remaining_seconds.saturating_sub(elapsed_seconds);
             
             if self.remaining_seconds == 0 {
-                self.complete_session();
+                if self.overtime_mode && self.mode == PomodoroMode::Work {
+                    self.start_overtime(overshoot);
+                } else {
+                    self.complete_session();
+                }
+            }
+        }
+        
+        // One soft tick per second as the session runs out
+        if self.in_final_countdown() && self.last_countdown_second != Some(self.remaining_seconds) {
+            self.last_countdown_second = Some(self.remaining_seconds);
+            if self.sound_enabled && !self.silent {
+                self.spawn_tick_player();
             }
         }
+        
+        // The reminder queries the database, so check about once a minute
+        if Instant::now() >= self.next_break_check {
+            self.next_break_check = Instant::now() + std::time::Duration::from_secs(60);
+            self.check_break_reminder();
+            
+            // Today's figures start over at the day rollover
+            if self.today_summary.day != self.db.today() {
+                self.refresh_today_summary();
+            }
+        }
+    }
+    
+    /// A suggestion for the break about to start, if they're on. The pick
+    /// comes from the clock's nanoseconds and avoids repeating the last one.
+    fn pick_break_suggestion(&self) -> Option<String> {
+        let list = &self.break_suggestion_list;
+        if !self.break_suggestions || list.is_empty() {
+            return None;
+        }
+        let mut index = self.clock.now().timestamp_subsec_nanos() as usize % list.len();
+        if list.len() > 1 && self.break_suggestion.as_ref() == Some(&list[index]) {
+            index = (index + 1) % list.len();
+        }
+        Some(list[index].clone())
+    }
+    
+    /// Keep a finished work session running, counting up until stopped
+    fn start_overtime(&mut self, overshoot: u64) {
+        self.in_overtime = true;
+        self.overtime_seconds = overshoot;
+        
+        self.play_notification_sound();
+        self.send_notification_with(self.work_urgency, "Pomodoro++", "Time's up! Counting overtime until you stop.");
     }
     
     fn complete_session(&mut self) {
-        let now = chrono::Local::now();
+        let now = self.clock.now();
         
         // Determine notification message based on current mode (before switching)
         let notification = match self.mode {
-            PomodoroMode::Work => ("Pomodoro++", "Work session complete! Time for a break."),
-            PomodoroMode::Break => ("Pomodoro++", "Break is over! Back to work."),
+            PomodoroMode::Work => (self.work_urgency, "Work session complete! Time for a break."),
+            PomodoroMode::Break => (self.break_urgency, "Break is over! Back to work."),
         };
         
         if let Some(start) = self.session_start.take() {
-            let duration = match self.mode {
-                PomodoroMode::Work => self.work_duration as i64,
-                PomodoroMode::Break => self.break_duration as i64,
-            };
+            let duration = (self.session_length() + self.overtime_seconds) as i64;
             
-            let tag = self.selected_tag().unwrap_or("Work").to_string();
+            let tags: Vec<String> = self.logged_tags().into_iter().map(String::from).collect();
+            if self.mode == PomodoroMode::Work {
+                self.last_work_tags = tags.clone();
+            }
+            let tags: Vec<&str> = tags.iter().map(String::as_str).collect();
             let session_type = match self.mode {
                 PomodoroMode::Work => "work",
                 PomodoroMode::Break => "break",
             };
             
-            let _ = self.db.save_session(&start, &now, duration, &tag, session_type);
+            self.last_session_id = match self.record_session(&start, &now, duration, &tags, session_type) {
+                Ok(id) => id,
+                Err(err) => {
+                    self.status_message = Some(format!("Could not log the session: {}", err));
+                    None
+                }
+            };
+            if let (Some(id), true) = (self.last_session_id, self.long_break) {
+                let _ = self.db.mark_long_break(id);
+            }
+            if let (Some(id), Some(intention)) = (self.last_session_id, self.intention.take()) {
+                let _ = self.db.set_session_note(id, &intention);
+            }
+            self.last_completion = self.last_session_id.map(|id| (id, self.mode, Instant::now()));
+            self.refresh_recent_sessions();
+            self.refresh_today_summary();
+            self.announce_milestones();
+            if session_type == "work" && self.last_session_id.is_some() {
+                self.announce_pomodoro_goal();
+            }
+            if session_type == "break" {
+                self.break_reminder_sent = false;
+            }
         }
         
-        // Play sound and send notification
-        Self::play_notification_sound();
-        Self::send_notification(notification.0, notification.1);
+        // Play sound and send notification (already done when overtime began)
+        if !self.in_overtime {
+            self.play_notification_sound();
+            self.send_notification_with(notification.0, "Pomodoro++", notification.1);
+        }
+        self.in_overtime = false;
+        self.overtime_seconds = 0;
+        self.incognito = false;
+        self.aligned_length = None;
         
         // Switch mode
         self.mode = match self.mode {
@@ -150,71 +1317,610 @@ impl App {
             PomodoroMode::Break => PomodoroMode::Work,
         };
         
-        self.remaining_seconds = match self.mode {
-            PomodoroMode::Work => self.work_duration,
-            PomodoroMode::Break => self.break_duration,
-        };
+        self.long_break = self.mode == PomodoroMode::Break && self.long_break_due();
+        if self.mode == PomodoroMode::Break {
+            self.break_suggestion = self.pick_break_suggestion();
+        }
+        self.remaining_seconds = self.session_length();
         
         self.timer_running = false;
-    }
-    
-    fn play_notification_sound() {
-        // Play sound using paplay in background
-        let home = std::env::var("HOME").unwrap_or_else(|_| String::from("/home"));
-        let sound_path = format!("{}/Music/sf/vieboom.mp3", home);
+        self.pause_started = None;
+        self.sync_focus_music();
+        if self.completion_flash {
+            self.completion_flash_until = Some(Instant::now() + std::time::Duration::from_secs(2));
+        }
         
-        std::thread::spawn(move || {
-            let _ = Command::new("paplay")
-                .arg(&sound_path)
-                .spawn();
-        });
+        // Ask how the finished work session went, then what it was about
+        if self.mode == PomodoroMode::Break && self.last_session_id.is_some() {
+            self.prompt_session = self.last_session_id;
+            self.queued_prompt = if self.rating_prompt {
+                Some(Screen::RatingInput)
+            } else {
+                self.note_prompt.then_some(Screen::NoteInput)
+            };
+            self.open_queued_prompt();
+        }
     }
     
-    fn send_notification(title: &str, message: &str) {
-        // Send desktop notification using notify-send in background
-        let title = title.to_string();
-        let msg = message.to_string();
-        std::thread::spawn(move || {
-            let _ = Command::new("notify-send")
-                .arg(&title)
-                .arg(&msg)
-                .spawn();
-        });
+    /// Open the prompt queued by a completed session once the user is back on
+    /// Home, so it never covers another screen or popup
+    pub fn open_queued_prompt(&mut self) {
+        if self.current_screen != Screen::Home {
+            return;
+        }
+        let Some(screen) = self.queued_prompt.take() else {
+            return;
+        };
+        if screen == Screen::NoteInput {
+            // Start from the intention, if one was given
+            self.input_buffer = self.recent_sessions
+                .iter()
+                .find(|session| Some(session.id) == self.prompt_session)
+                .and_then(|session| session.note.clone())
+                .unwrap_or_default();
+            self.input_mode = InputMode::Editing;
+        }
+        self.navigate_to(screen);
     }
     
-    pub fn next_tag(&mut self) {
-        if !self.tags.is_empty() {
-            self.selected_tag_index = (self.selected_tag_index + 1) % self.tags.len();
+    /// Store the focus rating (1-5) for the session just finished, or skip it
+    /// with `None`, and move on to the note prompt if that is on too
+    pub fn answer_rating(&mut self, rating: Option<u8>) {
+        if let (Some(id), Some(rating)) = (self.prompt_session, rating) {
+            let _ = self.db.set_session_rating(id, rating);
         }
+        self.current_screen = self.previous_screen;
+        self.queued_prompt = self.note_prompt.then_some(Screen::NoteInput);
+        self.open_queued_prompt();
     }
     
-    pub fn prev_tag(&mut self) {
-        if !self.tags.is_empty() {
-            self.selected_tag_index = if self.selected_tag_index == 0 {
-                self.tags.len() - 1
-            } else {
-                self.selected_tag_index - 1
-            };
+    /// Celebrate each daily milestone crossed by the work logged so far today
+    fn announce_milestones(&mut self) {
+        let today = self.db.today();
+        if self.milestones_reached.0 != today {
+            self.milestones_reached = (today, Vec::new());
+        }
+        
+        let minutes = (self.db.get_total_today() / 60) as u64;
+        let crossed: Vec<u64> = self
+            .milestones
+            .iter()
+            .copied()
+            .filter(|m| *m <= minutes && !self.milestones_reached.1.contains(m))
+            .collect();
+        
+        if let Some(highest) = crossed.last() {
+            let message = format!("{} Milestone reached: {} minutes of focus today!", self.glyphs().milestone, highest);
+            self.send_notification("Pomodoro++", &message);
+            self.status_message = Some(message);
         }
+        self.milestones_reached.1.extend(crossed);
     }
     
-    pub fn add_tag(&mut self, name: String) {
-        if !name.is_empty() && !self.tags.contains(&name) {
-            let _ = self.db.add_tag(&name);
-            self.tags.push(name);
+    /// Notify once a day, when the work session that meets the pomodoro goal
+    /// is logged
+    fn announce_pomodoro_goal(&mut self) {
+        let today = self.today_summary.day;
+        if self.daily_pomodoro_goal == 0 || self.pomodoro_goal_reached == Some(today) {
+            return;
+        }
+        if self.today_summary.sessions as u64 >= self.daily_pomodoro_goal {
+            self.pomodoro_goal_reached = Some(today);
+            let message = format!("{} Daily goal of {} pomodoros reached!", self.glyphs().pomodoro, self.daily_pomodoro_goal);
+            self.send_notification("Pomodoro++", &message);
+            self.status_message = Some(message);
         }
     }
     
-    pub fn delete_selected_tag(&mut self) {
-        if !self.tags.is_empty() && self.selected_tag_index < self.tags.len() {
-            let tag_name = self.tags[self.selected_tag_index].clone();
-            let _ = self.db.delete_tag(&tag_name);
-            self.tags.remove(self.selected_tag_index);
-            
-            // Adjust selected index if needed
-            if self.selected_tag_index >= self.tags.len() && !self.tags.is_empty() {
-                self.selected_tag_index = self.tags.len() - 1;
-            }
+    /// Today's work sessions against the pomodoro goal, if one is set
+    pub fn pomodoro_progress(&self) -> Option<(u64, u64)> {
+        if self.daily_pomodoro_goal == 0 {
+            return None;
+        }
+        Some((self.today_summary.sessions as u64, self.daily_pomodoro_goal))
+    }
+    
+    /// Suggest a break, once per stretch, when the work logged in the last hour
+    /// (plus the session in progress) passes the break reminder threshold
+    pub fn check_break_reminder(&mut self) {
+        if self.break_reminder == 0 || self.break_reminder_sent {
+            return;
+        }
+        let mut seconds = self.db.get_work_since_break_last_hour() as u64;
+        if self.mode == PomodoroMode::Work && self.session_start.is_some() {
+            seconds += self.elapsed_seconds();
+        }
+        
+        if seconds >= self.break_reminder * 60 {
+            self.break_reminder_sent = true;
+            let message = format!("You've worked {} min without a break. Consider resting.", seconds / 60);
+            self.send_notification("Pomodoro++", &message);
+            self.status_message = Some(message);
+        }
+    }
+    
+    /// The next milestone still ahead today, with the minutes left to reach it
+    pub fn next_milestone(&self) -> Option<(u64, u64)> {
+        let minutes = (self.today_summary.seconds / 60) as u64;
+        self.milestones.iter().find(|m| **m > minutes).map(|m| (*m, m - minutes))
+    }
+    
+    /// Remove the session logged by the latest completion, if that was within
+    /// `UNDO_WINDOW_SECONDS`, and return to the mode it was logged in
+    pub fn undo_last_session(&mut self) {
+        match self.last_completion.take() {
+            Some((id, mode, completed_at)) if completed_at.elapsed().as_secs() <= UNDO_WINDOW_SECONDS => {
+                let _ = self.db.delete_session(id);
+                self.last_session_id = None;
+                if self.prompt_session == Some(id) {
+                    self.queued_prompt = None;
+                }
+                self.refresh_recent_sessions();
+                self.refresh_today_summary();
+                
+                // Leave a session that has already started alone
+                if self.session_start.is_none() {
+                    self.mode = mode;
+                    self.reset_timer();
+                }
+                self.status_message = Some("Last session removed".to_string());
+            }
+            _ => self.status_message = Some("Nothing to undo".to_string()),
+        }
+    }
+    
+    /// Log work done away from the timer, typed as "minutes [start]" for the
+    /// selected tag(s). Without a start time the session is taken to end now.
+    pub fn log_manual_session(&mut self, input: &str) -> Result<(), &'static str> {
+        let now = self.clock.now();
+        let (minutes, start) = parse_manual_entry(input, now.naive_local())?;
+        let start = start
+            .and_local_timezone(chrono::Local)
+            .single()
+            .ok_or("That time doesn't exist locally")?;
+        let end = start + chrono::Duration::minutes(minutes);
+        
+        let tags: Vec<String> = self.session_tags().into_iter().map(String::from).collect();
+        let tags: Vec<&str> = tags.iter().map(String::as_str).collect();
+        match self.record_session(&start, &end, minutes * 60, &tags, "work") {
+            Ok(Some(_)) => {}
+            Ok(None) => return Err("Incognito is on, so nothing was logged"),
+            Err(_) => return Err("Could not save the session"),
+        }
+        
+        self.refresh_recent_sessions();
+        self.refresh_today_summary();
+        self.announce_milestones();
+        self.announce_pomodoro_goal();
+        if self.status_message.is_none() {
+            self.status_message = Some(format!("Logged {} min", minutes));
+        }
+        Ok(())
+    }
+    
+    /// Persist a session unless it is too short to be meaningful or incognito
+    /// is on, in which case there is no id. Every code path that logs a
+    /// session goes through here.
+    fn record_session(&self, start: &chrono::DateTime<chrono::Local>, end: &chrono::DateTime<chrono::Local>,
+                      duration: i64, tags: &[&str], session_type: &str) -> Result<Option<i64>, rusqlite::Error> {
+        if duration < MIN_SESSION_SECONDS || self.incognito {
+            return Ok(None);
+        }
+        self.db.save_session(start, end, duration, tags, session_type).map(Some)
+    }
+    
+    pub fn refresh_recent_sessions(&mut self) {
+        if let Ok(sessions) = self.db.get_recent_sessions(RECENT_SESSION_COUNT) {
+            self.recent_sessions = sessions;
+        }
+        if let Ok(last_worked) = self.db.get_last_session_per_tag() {
+            self.last_worked = last_worked;
+        }
+        self.refresh_session_blocks();
+        self.refresh_stats_ratings();
+    }
+    
+    /// Reload the average rating under each bar of the Stats chart, whenever
+    /// the chart or the sessions behind it change
+    fn refresh_stats_ratings(&mut self) {
+        let tag = self.get_stats_tag();
+        self.stats_ratings = self
+            .stats_buckets()
+            .iter()
+            .map(|(label, _)| self.db.get_average_rating(label, tag))
+            .collect();
+    }
+    
+    fn refresh_session_blocks(&mut self) {
+        self.session_blocks = if self.recent_blocks { load_session_blocks(&self.db, self.block_gap) } else { Vec::new() };
+    }
+    
+    pub fn save_note(&mut self) {
+        let note = self.input_buffer.trim();
+        if note.is_empty() {
+            return;
+        }
+        if let Some(id) = self.prompt_session {
+            let _ = self.db.set_session_note(id, note);
+            self.refresh_recent_sessions();
+        }
+    }
+    
+    fn play_notification_sound(&self) {
+        if !self.sound_enabled || self.silent {
+            return;
+        }
+        
+        // Play sound in background: a configured file goes to the external
+        // players, otherwise the bundled chime plays in-process, with the
+        // players and then the terminal bell as fallbacks
+        let players = self.sound_players();
+        let sound_path = self.sound_path.clone();
+        let sound_failed = Arc::clone(&self.sound_failed);
+        std::thread::spawn(move || {
+            let played = if std::path::Path::new(&sound_path).exists() {
+                play_with_fallback(&players, std::path::Path::new(&sound_path))
+            } else {
+                crate::chime::play_bundled_chime()
+                    || crate::chime::default_chime_path()
+                        .is_some_and(|path| play_with_fallback(&players, &path))
+            };
+            if !played {
+                use std::io::Write;
+                let mut stdout = std::io::stdout();
+                let _ = stdout.write_all(b"\x07");
+                let _ = stdout.flush();
+            }
+            sound_failed.store(!played, Ordering::Relaxed);
+        });
+    }
+    
+    /// Whether the last completion sound could not be played by any player
+    pub fn sound_failed(&self) -> bool {
+        self.sound_failed.load(Ordering::Relaxed)
+    }
+    
+    fn sound_players(&self) -> Vec<String> {
+        self.sound_player
+            .split(',')
+            .map(str::trim)
+            .filter(|player| !player.is_empty())
+            .map(String::from)
+            .collect()
+    }
+    
+    /// Whether this second gets a tick, counting overtime seconds once the
+    /// remaining time has run out
+    fn tick_due(&self) -> bool {
+        let counted = if self.in_overtime { self.overtime_seconds } else { self.remaining_seconds };
+        self.timer_running && counted.is_multiple_of(self.tick_interval)
+    }
+    
+    fn play_tick_sound(&mut self) {
+        if !self.sound_enabled || !self.tick_sound || self.silent {
+            return;
+        }
+        self.spawn_tick_player();
+    }
+    
+    /// Play the tick sound unless the previous tick is still playing
+    fn spawn_tick_player(&mut self) {
+        if let Some(child) = self.tick_player.as_mut() {
+            if let Ok(None) = child.try_wait() {
+                return;
+            }
+        }
+        
+        // Ticks are frequent, so they only use the first player
+        let player = match self.sound_players().into_iter().next() {
+            Some(player) => player,
+            None => return,
+        };
+        self.tick_player = Command::new(player)
+            .arg(&self.tick_sound_path)
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .spawn()
+            .ok();
+    }
+    
+    fn send_notification(&self, title: &str, message: &str) {
+        self.send_notification_with(Urgency::Normal, title, message);
+    }
+    
+    fn send_notification_with(&self, urgency: Urgency, title: &str, message: &str) {
+        if self.silent {
+            return;
+        }
+        
+        // Send desktop notification using notify-send in background
+        let args = notify_send_args(urgency, self.notification_timeout, title, message);
+        std::thread::spawn(move || {
+            let _ = Command::new("notify-send")
+                .args(&args)
+                .spawn();
+        });
+    }
+    
+    /// Save the timer state when it changes (started, paused, completed, ...)
+    /// or, while it just counts down, once every `autosave_interval` seconds.
+    /// The status line extrapolates a running countdown, so it stays accurate.
+    pub fn autosave_timer_state(&mut self) {
+        let snapshot = self.timer_snapshot();
+        let due = match self.last_autosave {
+            Some((saved_at, saved)) => {
+                saved != snapshot || saved_at.elapsed().as_secs() >= self.autosave_interval
+            }
+            None => true,
+        };
+        if due {
+            self.save_timer_state();
+            self.last_autosave = Some((Instant::now(), snapshot));
+        }
+    }
+    
+    /// What the saved state depends on, apart from a running countdown
+    fn timer_snapshot(&self) -> TimerSnapshot {
+        let remaining = if self.timer_running { 0 } else { self.remaining_seconds };
+        (self.session_start.is_some(), self.mode, self.timer_running, remaining)
+    }
+    
+    /// Publish the timer state so `pomodoro-pp status` can report it.
+    /// Nothing is published while no session is in progress.
+    pub fn save_timer_state(&self) {
+        let state = self.session_start.map(|_| TimerState {
+            session_type: match self.mode {
+                PomodoroMode::Work => "work",
+                PomodoroMode::Break => "break",
+            }.to_string(),
+            running: self.timer_running,
+            remaining_seconds: self.remaining_seconds,
+            saved_at: self.clock.now().timestamp(),
+            started_at: self.session_start.map_or(0, |start| start.timestamp()),
+            elapsed_seconds: self.session_length().saturating_sub(self.remaining_seconds) + self.overtime_seconds,
+            tags: self.logged_tags().into_iter().map(String::from).collect(),
+        });
+        let _ = self.db.save_timer_state(state.as_ref());
+    }
+    
+    /// Log the time counted before the app last closed unexpectedly, ending
+    /// at its final save
+    pub fn log_abandoned_session(&mut self) {
+        if let Some(state) = self.abandoned_session.take() {
+            let start = Local.timestamp_opt(state.started_at, 0).single();
+            let end = Local.timestamp_opt(state.saved_at, 0).single();
+            if let (Some(start), Some(end)) = (start, end) {
+                let tags: Vec<&str> = state.tags.iter().map(String::as_str).collect();
+                let minutes = state.elapsed_seconds / 60;
+                self.status_message = match self.record_session(&start, &end, state.elapsed_seconds as i64, &tags, &state.session_type) {
+                    Ok(Some(_)) => Some(format!("Logged {} min from the last run", minutes)),
+                    Ok(None) => None,
+                    Err(err) => Some(format!("Could not log the last run: {}", err)),
+                };
+            }
+            self.refresh_recent_sessions();
+            self.refresh_today_summary();
+        }
+        self.navigate_to(Screen::Home);
+    }
+    
+    pub fn discard_abandoned_session(&mut self) {
+        self.abandoned_session = None;
+        self.navigate_to(Screen::Home);
+    }
+    
+    pub fn next_tag(&mut self) {
+        if !self.tags.is_empty() {
+            self.selected_tag_index = (self.selected_tag_index + 1) % self.tags.len();
+            self.remember_selected_tag();
+        }
+    }
+    
+    pub fn prev_tag(&mut self) {
+        if !self.tags.is_empty() {
+            self.selected_tag_index = if self.selected_tag_index == 0 {
+                self.tags.len() - 1
+            } else {
+                self.selected_tag_index - 1
+            };
+            self.remember_selected_tag();
+        }
+    }
+    
+    /// Save the selected tag so the next start picks it again
+    fn remember_selected_tag(&self) {
+        if let Some(tag) = self.selected_tag() {
+            let _ = self.db.set_config("selected_tag", tag);
+        }
+    }
+    
+    /// Add a tag typed by the user, returning a message if it is rejected.
+    /// A name matching an archived tag (ignoring case) restores that tag.
+    pub fn add_tag(&mut self, raw_name: &str) -> Result<(), &'static str> {
+        let mut name = normalize_tag_name(raw_name)?;
+        let lowercase = name.to_lowercase();
+        
+        if self.tags.iter().any(|t| t.to_lowercase() == lowercase) {
+            return Err("A tag with that name already exists");
+        }
+        if let Some(archived) = self.archived_tags.iter().find(|t| t.to_lowercase() == lowercase) {
+            name = archived.clone();
+        }
+        
+        let _ = self.db.add_tag(&name);
+        self.tags.push(name);
+        self.refresh_inactive_tags();
+        Ok(())
+    }
+    
+    /// Whether the session in progress will be logged under the selected tag
+    fn selected_tag_in_use(&self) -> bool {
+        self.session_start.is_some()
+            && self.selected_tag().is_some_and(|tag| self.logged_tags().contains(&tag))
+    }
+    
+    /// Ask to confirm deleting the selected tag, refusing while the session
+    /// in progress will be logged under it
+    pub fn request_tag_deletion(&mut self) {
+        if self.selected_tag_in_use() {
+            self.status_message = Some("Can't delete a tag the current session will be logged under".to_string());
+        } else if self.skip_confirmations {
+            self.delete_selected_tag();
+        } else if self.selected_tag().is_some() {
+            self.navigate_to(Screen::DeleteConfirm);
+        }
+    }
+    
+    pub fn delete_selected_tag(&mut self) {
+        if self.selected_tag_in_use() {
+            return;
+        }
+        if !self.tags.is_empty() && self.selected_tag_index < self.tags.len() {
+            let tag_name = self.tags[self.selected_tag_index].clone();
+            let _ = self.db.delete_tag(&tag_name);
+            self.tags.remove(self.selected_tag_index);
+            
+            // Adjust selected index if needed
+            if self.selected_tag_index >= self.tags.len() && !self.tags.is_empty() {
+                self.selected_tag_index = self.tags.len() - 1;
+            }
+            self.refresh_inactive_tags();
+        }
+    }
+    
+    /// Hide the selected tag from the Home list while keeping its history
+    pub fn archive_selected_tag(&mut self) {
+        if self.selected_tag_index < self.tags.len() {
+            let tag_name = self.tags.remove(self.selected_tag_index);
+            let _ = self.db.set_tag_archived(&tag_name, true);
+            
+            if self.selected_tag_index >= self.tags.len() && !self.tags.is_empty() {
+                self.selected_tag_index = self.tags.len() - 1;
+            }
+            self.refresh_inactive_tags();
+        }
+    }
+    
+    /// Bring the tag highlighted in the archived list back to the Home list
+    pub fn unarchive_selected_tag(&mut self) {
+        if self.archived_index < self.archived_tags.len() {
+            let tag_name = self.archived_tags[self.archived_index].clone();
+            let _ = self.db.set_tag_archived(&tag_name, false);
+            self.tags.push(tag_name);
+            self.refresh_inactive_tags();
+            
+            if self.archived_index >= self.archived_tags.len() && !self.archived_tags.is_empty() {
+                self.archived_index = self.archived_tags.len() - 1;
+            }
+        }
+    }
+    
+    pub fn next_archived_tag(&mut self) {
+        if !self.archived_tags.is_empty() {
+            self.archived_index = (self.archived_index + 1) % self.archived_tags.len();
+        }
+    }
+    
+    pub fn prev_archived_tag(&mut self) {
+        if !self.archived_tags.is_empty() {
+            self.archived_index = if self.archived_index == 0 {
+                self.archived_tags.len() - 1
+            } else {
+                self.archived_index - 1
+            };
+        }
+    }
+    
+    pub fn open_templates(&mut self) {
+        self.template_index = 0;
+        self.navigate_to(Screen::Templates);
+    }
+    
+    pub fn next_template(&mut self) {
+        if !self.templates.is_empty() {
+            self.template_index = (self.template_index + 1) % self.templates.len();
+        }
+    }
+    
+    pub fn prev_template(&mut self) {
+        if !self.templates.is_empty() {
+            self.template_index = if self.template_index == 0 {
+                self.templates.len() - 1
+            } else {
+                self.template_index - 1
+            };
+        }
+    }
+    
+    /// Apply the highlighted template: select its tag (creating or restoring
+    /// it if needed) and set both durations, then optionally start working
+    pub fn apply_selected_template(&mut self, start: bool) {
+        let Some(template) = self.templates.get(self.template_index).cloned() else { return };
+        let lowercase = template.tag.to_lowercase();
+        if !self.tags.iter().any(|tag| tag.to_lowercase() == lowercase) {
+            let _ = self.add_tag(&template.tag);
+        }
+        if let Some(index) = self.tags.iter().position(|tag| tag.to_lowercase() == lowercase) {
+            self.selected_tag_index = index;
+            self.marked_tags.clear();
+            self.remember_selected_tag();
+        }
+        self.set_work_duration(template.work_minutes as i64 * 60);
+        self.set_break_duration(template.break_minutes as i64 * 60);
+        self.navigate_to(Screen::Home);
+        self.status_message = Some(format!("Template \"{}\" applied", template.name));
+        
+        if start {
+            self.quick_start(self.selected_tag_index + 1);
+        }
+    }
+    
+    /// Save the current tag and durations as a template named `name`,
+    /// replacing a template of the same name
+    pub fn create_template(&mut self, name: &str) -> Result<(), &'static str> {
+        let name = name.trim();
+        if name.is_empty() {
+            return Err("Enter a name for the template");
+        }
+        let Some(tag) = self.selected_tag() else {
+            return Err("Add a tag first");
+        };
+        let template = Template {
+            name: name.to_string(),
+            tag: tag.to_string(),
+            work_minutes: self.work_duration / 60,
+            break_minutes: self.break_duration / 60,
+        };
+        let _ = self.db.save_template(&template);
+        self.templates = self.db.get_templates().unwrap_or_default();
+        self.template_index = self.templates.iter().position(|t| t.name == template.name).unwrap_or(0);
+        Ok(())
+    }
+    
+    pub fn delete_selected_template(&mut self) {
+        if let Some(template) = self.templates.get(self.template_index) {
+            let _ = self.db.delete_template(&template.name);
+            self.templates.remove(self.template_index);
+            if self.template_index >= self.templates.len() {
+                self.template_index = self.templates.len().saturating_sub(1);
+            }
+        }
+    }
+    
+    fn refresh_inactive_tags(&mut self) {
+        let tags = &self.tags;
+        self.marked_tags.retain(|tag| tags.contains(tag));
+        if let Ok(all_tags) = self.db.get_all_tags() {
+            self.all_tags = with_categories(all_tags);
+        }
+        if let Ok(archived_tags) = self.db.get_archived_tags() {
+            self.archived_tags = archived_tags;
+        }
+        if self.stats_tag_index > self.all_tags.len() {
+            self.stats_tag_index = 0;
+        }
+        if self.heatmap_tag_index > self.all_tags.len() {
+            self.heatmap_tag_index = 0;
         }
     }
     
@@ -229,61 +1935,2571 @@ impl App {
         }
     }
     
-    pub fn navigate_to(&mut self, screen: Screen) {
-        self.previous_screen = self.current_screen;
-        self.current_screen = screen;
+    /// Move `step` places around `SCREEN_RING` (negative goes backwards)
+    pub fn cycle_screen(&mut self, step: isize) {
+        if let Some(index) = SCREEN_RING.iter().position(|screen| *screen == self.current_screen) {
+            let len = SCREEN_RING.len() as isize;
+            let next = (index as isize + step).rem_euclid(len) as usize;
+            self.navigate_to(SCREEN_RING[next]);
+        }
     }
     
-    pub fn toggle_stats_view(&mut self) {
-        self.stats_view = match self.stats_view {
-            StatsView::Weekly => StatsView::Monthly,
-            StatsView::Monthly => StatsView::Weekly,
+    /// Open the "today at a glance" card with up-to-date figures
+    pub fn show_today_summary(&mut self) {
+        self.refresh_today_summary();
+        self.navigate_to(Screen::TodaySummary);
+    }
+    
+    pub fn refresh_today_summary(&mut self) {
+        self.today_summary = TodaySummary::load(&self.db, self.current_week_start());
+        self.cycle_work = self.work_in_cycle();
+    }
+    
+    /// Show or hide the stats peek over the timer. It reads the cached
+    /// summary, only reloading it once the day has changed.
+    pub fn toggle_stats_peek(&mut self) {
+        if self.current_screen == Screen::StatsPeek {
+            self.current_screen = Screen::Home;
+            return;
+        }
+        if self.today_summary.day != self.db.today() {
+            self.refresh_today_summary();
+        }
+        self.current_screen = Screen::StatsPeek;
+    }
+    
+    /// Mute or unmute sounds and notifications in one go, e.g. for a meeting
+    pub fn toggle_silent(&mut self) {
+        toggle_flag(&self.db, "silent", &mut self.silent);
+        self.sync_focus_music();
+        self.status_message = Some(if self.silent {
+            format!("{} Silent mode on: no sounds or notifications", self.glyphs().silent)
+        } else {
+            format!("{} Silent mode off", self.glyphs().sound)
+        });
+    }
+    
+    /// Switch to the night theme during its hours and back to the color theme
+    /// outside them. Cheap enough to run before every draw.
+    pub fn refresh_theme(&mut self) {
+        let hour = self.clock.now().hour();
+        let (start, end) = self.night_hours;
+        let night = if start <= end {
+            (start..end).contains(&hour)
+        } else {
+            hour >= start || hour < end
+        };
+        self.theme = match self.night_theme {
+            Some(theme) if night => theme,
+            _ => self.day_theme,
         };
     }
     
-    pub fn next_stats_tag(&mut self) {
-        self.stats_tag_index = (self.stats_tag_index + 1) % (self.tags.len() + 1);
+    /// Swap emoji and shading for plain ASCII, or back, for terminals that
+    /// can't show them
+    pub fn toggle_ascii(&mut self) {
+        toggle_flag(&self.db, "ascii_mode", &mut self.ascii);
     }
     
-    pub fn prev_stats_tag(&mut self) {
-        if self.stats_tag_index == 0 {
-            self.stats_tag_index = self.tags.len();
+    pub fn glyphs(&self) -> &'static Glyphs {
+        if self.ascii { &Glyphs::ASCII } else { &Glyphs::UNICODE }
+    }
+    
+    /// Whether the focus lock currently holds: on, with a work session in
+    /// progress, paused or not
+    pub fn focus_locked(&self) -> bool {
+        self.focus_lock && self.session_start.is_some() && self.mode == PomodoroMode::Work
+    }
+    
+    /// Quit from `q`, unless the focus lock holds
+    pub fn request_quit(&mut self) {
+        self.quit(false);
+    }
+    
+    /// Quit from `Q` on Home, even through the focus lock
+    pub fn force_quit(&mut self) {
+        self.quit(true);
+    }
+    
+    fn quit(&mut self, through_lock: bool) {
+        if self.focus_locked() && !through_lock {
+            self.status_message = Some(format!("{} Focus lock: finish this session first (Q quits anyway)", self.glyphs().lock));
         } else {
-            self.stats_tag_index -= 1;
+            self.should_quit = true;
         }
     }
     
-    pub fn get_stats_tag(&self) -> Option<&str> {
-        if self.stats_tag_index == 0 {
-            None
+    /// Keep the current session out of the log, for test or throwaway runs.
+    /// It applies until that session completes.
+    pub fn toggle_incognito(&mut self) {
+        self.incognito = !self.incognito;
+        self.status_message = Some(if self.incognito {
+            format!("{} Incognito: this session won't be logged", self.glyphs().incognito)
+        } else {
+            "This session will be logged".to_string()
+        });
+    }
+    
+    /// Flip the timer between counting down and counting up
+    pub fn toggle_count_up(&mut self) {
+        toggle_flag(&self.db, "count_up", &mut self.count_up);
+    }
+    
+    /// Switch Home between the full layout and the timer on its own
+    pub fn toggle_compact(&mut self) {
+        toggle_flag(&self.db, "compact_mode", &mut self.compact);
+    }
+    
+    /// Pick a tag to merge the Stats tag into, e.g. after creating both
+    /// "Read" and "Reading". Categories and the running session's tags can't be merged.
+    pub fn open_tag_merge(&mut self) {
+        let Some(source) = self.get_stats_tag().map(String::from) else {
+            self.status_message = Some("Pick a tag with ←/→ to merge it into another".to_string());
+            return;
+        };
+        let tags = self.db.get_all_tags().unwrap_or_default();
+        if !tags.contains(&source) {
+            self.status_message = Some("Categories can't be merged; pick one of their tags".to_string());
+        } else if self.session_start.is_some() && self.logged_tags().contains(&source.as_str()) {
+            self.status_message = Some("Can't merge a tag the current session will be logged under".to_string());
         } else {
-            self.tags.get(self.stats_tag_index - 1).map(|s| s.as_str())
+            self.merge_targets = tags.into_iter().filter(|tag| *tag != source).collect();
+            self.merge_index = 0;
+            self.navigate_to(Screen::MergeTags);
         }
     }
     
-    pub fn format_time(&self) -> String {
-        let minutes = self.remaining_seconds / 60;
-        let seconds = self.remaining_seconds % 60;
-        format!("{:02}:{:02}", minutes, seconds)
+    pub fn next_merge_target(&mut self) {
+        if !self.merge_targets.is_empty() {
+            self.merge_index = (self.merge_index + 1) % self.merge_targets.len();
+        }
     }
     
-    pub fn adjust_work_duration(&mut self, delta: i64) {
-        let new_val = (self.work_duration as i64 + delta).max(60).min(7200) as u64;
-        self.work_duration = new_val;
-        let _ = self.db.set_config("work_duration", &new_val.to_string());
-        
-        if self.mode == PomodoroMode::Work && !self.timer_running {
-            self.remaining_seconds = new_val;
+    pub fn prev_merge_target(&mut self) {
+        if !self.merge_targets.is_empty() {
+            self.merge_index = (self.merge_index + self.merge_targets.len() - 1) % self.merge_targets.len();
         }
     }
     
-    pub fn adjust_break_duration(&mut self, delta: i64) {
-        let new_val = (self.break_duration as i64 + delta).max(60).min(3600) as u64;
-        self.break_duration = new_val;
-        let _ = self.db.set_config("break_duration", &new_val.to_string());
-        
-        if self.mode == PomodoroMode::Break && !self.timer_running {
-            self.remaining_seconds = new_val;
+    /// Merge the Stats tag into the highlighted one and show the result
+    pub fn merge_selected_tag(&mut self) {
+        let source = self.get_stats_tag().map(String::from);
+        if let (Some(source), Some(target)) = (source, self.merge_targets.get(self.merge_index).cloned()) {
+            let selected = self.selected_tag().map(String::from);
+            match self.db.merge_tags(&source, &target) {
+                Ok(moved) => {
+                    self.refresh_tags();
+                    // Whatever pointed at the merged tag now points at the one it went into
+                    let selected = if selected.as_deref() == Some(source.as_str()) { Some(target.clone()) } else { selected };
+                    if let Some(index) = selected.and_then(|tag| self.tags.iter().position(|t| *t == tag)) {
+                        self.selected_tag_index = index;
+                    }
+                    if self.selected_tag_index >= self.tags.len() {
+                        self.selected_tag_index = self.tags.len().saturating_sub(1);
+                    }
+                    if self.default_tag == source {
+                        self.default_tag = target.clone();
+                    }
+                    self.refresh_inactive_tags();
+                    self.stats_tag_index = self.all_tags.iter().position(|tag| *tag == target).map_or(0, |i| i + 1);
+                    self.refresh_recent_sessions();
+                    self.status_message = Some(format!(
+                        "Merged {} into {} ({} session{})", source, target, moved, if moved == 1 { "" } else { "s" }
+                    ));
+                }
+                Err(_) => self.status_message = Some("Couldn't merge the tags".to_string()),
+            }
+        }
+        self.navigate_to(Screen::Stats);
+    }
+    
+    /// Open the week review at its first card
+    pub fn open_week_review(&mut self) {
+        let week = WeekData::load(&self.db, self.db.today());
+        self.review_cards = week_review(&week);
+        self.review_index = 0;
+        self.navigate_to(Screen::WeekReview);
+    }
+    
+    pub fn next_review_card(&mut self) {
+        if self.review_index + 1 < self.review_cards.len() {
+            self.review_index += 1;
+        }
+    }
+    
+    pub fn prev_review_card(&mut self) {
+        self.review_index = self.review_index.saturating_sub(1);
+    }
+    
+    pub fn heat_scale(&self) -> HeatScale {
+        match (self.heatmap_absolute, self.heat_metric()) {
+            (false, _) => HeatScale::Relative,
+            (true, HeatMetric::Minutes) => HeatScale::Absolute(self.heatmap_thresholds),
+            (true, HeatMetric::Sessions) => HeatScale::Absolute(SESSION_THRESHOLDS),
         }
     }
+    
+    pub fn toggle_heat_scale(&mut self) {
+        toggle_flag(&self.db, "heatmap_absolute", &mut self.heatmap_absolute);
+    }
+    
+    pub fn heat_metric(&self) -> HeatMetric {
+        if self.heatmap_sessions {
+            HeatMetric::Sessions
+        } else {
+            HeatMetric::Minutes
+        }
+    }
+    
+    pub fn toggle_heat_metric(&mut self) {
+        toggle_flag(&self.db, "heatmap_sessions", &mut self.heatmap_sessions);
+    }
+    
+    pub fn navigate_to(&mut self, screen: Screen) {
+        let browsing = |screen: Screen| matches!(
+            screen,
+            Screen::Stats | Screen::Heatmap | Screen::WeekReview | Screen::BucketSessions | Screen::MergeTags | Screen::ExportDone
+        );
+        
+        if self.auto_pause && browsing(screen) && !browsing(self.current_screen) {
+            // Leaving the timer: pause a running work session
+            if self.timer_running && !self.in_overtime && self.mode == PomodoroMode::Work {
+                self.toggle_timer();
+                self.auto_paused = true;
+                self.sync_focus_music();
+            }
+        } else if self.auto_paused && !browsing(screen) {
+            // Back at the timer: resume what was paused on the way out
+            self.auto_paused = false;
+            if !self.timer_running && self.session_start.is_some() {
+                self.toggle_timer();
+                self.sync_focus_music();
+            }
+        }
+        
+        if screen == Screen::Stats {
+            self.refresh_stats_ratings();
+        }
+        self.previous_screen = self.current_screen;
+        self.current_screen = screen;
+    }
+    
+    pub fn toggle_stats_view(&mut self) {
+        self.stats_view = match self.stats_view {
+            StatsView::Weekly => StatsView::Monthly,
+            StatsView::Monthly => StatsView::Yearly,
+            StatsView::Yearly => StatsView::Weekdays,
+            StatsView::Weekdays => StatsView::Hours,
+            StatsView::Hours => StatsView::Weekly,
+        };
+        self.stats_bucket = usize::MAX;
+        self.refresh_stats_ratings();
+    }
+    
+    /// The bars of the Weekly, Monthly or Yearly chart as (label, seconds)
+    pub fn stats_buckets(&self) -> Vec<(String, i64)> {
+        match self.stats_view {
+            StatsView::Weekly => self.db.get_weekly_stats(self.get_stats_tag()).unwrap_or_default(),
+            StatsView::Monthly => self.db.get_monthly_stats(self.get_stats_tag()).unwrap_or_default(),
+            StatsView::Yearly | StatsView::Weekdays | StatsView::Hours => self.db.get_yearly_stats(self.get_stats_tag()).unwrap_or_default(),
+        }
+    }
+    
+    /// Whether the chart on show has bars that can be highlighted and drilled into
+    pub fn stats_drillable(&self) -> bool {
+        matches!(self.stats_view, StatsView::Weekly | StatsView::Monthly | StatsView::Yearly)
+            && !self.stats_leaderboard
+            && !(self.stats_breakdown && self.get_stats_tag().is_none())
+    }
+    
+    /// Index of the highlighted bar among `bar_count` bars
+    pub fn selected_bucket(&self, bar_count: usize) -> Option<usize> {
+        (bar_count > 0).then(|| self.stats_bucket.min(bar_count - 1))
+    }
+    
+    pub fn next_stats_bucket(&mut self) {
+        let count = self.stats_buckets().len();
+        if let Some(index) = self.selected_bucket(count) {
+            self.stats_bucket = (index + 1) % count;
+        }
+    }
+    
+    pub fn prev_stats_bucket(&mut self) {
+        let count = self.stats_buckets().len();
+        if let Some(index) = self.selected_bucket(count) {
+            self.stats_bucket = if index == 0 { count - 1 } else { index - 1 };
+        }
+    }
+    
+    /// List the sessions behind the highlighted bar in a popup
+    pub fn open_bucket_sessions(&mut self) {
+        if !self.stats_drillable() {
+            return;
+        }
+        let buckets = self.stats_buckets();
+        let Some(index) = self.selected_bucket(buckets.len()) else { return };
+        self.bucket_sessions = self.db.get_sessions_in_bucket(&buckets[index].0, self.get_stats_tag()).unwrap_or_default();
+        self.bucket_session_index = 0;
+        self.navigate_to(Screen::BucketSessions);
+    }
+    
+    pub fn next_bucket_session(&mut self) {
+        if !self.bucket_sessions.is_empty() {
+            self.bucket_session_index = (self.bucket_session_index + 1).min(self.bucket_sessions.len() - 1);
+        }
+    }
+    
+    pub fn prev_bucket_session(&mut self) {
+        self.bucket_session_index = self.bucket_session_index.saturating_sub(1);
+    }
+    
+    pub fn toggle_stats_breakdown(&mut self) {
+        self.stats_breakdown = !self.stats_breakdown;
+    }
+    
+    pub fn toggle_stats_leaderboard(&mut self) {
+        self.stats_leaderboard = !self.stats_leaderboard;
+    }
+    
+    /// First day of the period the current Stats view sums over: the last
+    /// week, the last year, or `None` for all history
+    pub fn stats_period_start(&self) -> Option<chrono::NaiveDate> {
+        let today = self.db.today();
+        match self.stats_view {
+            StatsView::Weekly => Some(today - chrono::Duration::days(7)),
+            StatsView::Monthly => Some(today - chrono::Months::new(12)),
+            StatsView::Yearly | StatsView::Weekdays | StatsView::Hours => None,
+        }
+    }
+    
+    /// Group the split chart by category, turning the split on if needed
+    pub fn toggle_stats_categories(&mut self) {
+        self.stats_by_category = !self.stats_by_category;
+        if self.stats_by_category {
+            self.stats_breakdown = true;
+        }
+    }
+    
+    pub fn next_stats_tag(&mut self) {
+        self.stats_tag_index = (self.stats_tag_index + 1) % (self.all_tags.len() + 1);
+        self.refresh_stats_ratings();
+    }
+    
+    pub fn prev_stats_tag(&mut self) {
+        if self.stats_tag_index == 0 {
+            self.stats_tag_index = self.all_tags.len();
+        } else {
+            self.stats_tag_index -= 1;
+        }
+        self.refresh_stats_ratings();
+    }
+    
+    pub fn get_stats_tag(&self) -> Option<&str> {
+        if self.stats_tag_index == 0 {
+            None
+        } else {
+            self.all_tags.get(self.stats_tag_index - 1).map(|s| s.as_str())
+        }
+    }
+    
+    pub fn next_heatmap_tag(&mut self) {
+        self.heatmap_tag_index = (self.heatmap_tag_index + 1) % (self.all_tags.len() + 1);
+    }
+    
+    pub fn prev_heatmap_tag(&mut self) {
+        if self.heatmap_tag_index == 0 {
+            self.heatmap_tag_index = self.all_tags.len();
+        } else {
+            self.heatmap_tag_index -= 1;
+        }
+    }
+    
+    /// Last day shown on the heatmap: today, moved back by the scroll offset
+    pub fn heatmap_end(&self) -> chrono::NaiveDate {
+        let today = self.db.today();
+        today.checked_sub_months(chrono::Months::new(self.heatmap_offset)).unwrap_or(today)
+    }
+    
+    /// Scroll the heatmap a month back, until its window reaches the first logged work
+    pub fn scroll_heatmap_back(&mut self) {
+        let window_start = self.heatmap_end() - chrono::Duration::days(HEATMAP_DAYS);
+        let first_day = self.db.get_weekday_totals(self.get_heatmap_tag()).ok().and_then(|(_, first)| first);
+        match first_day {
+            Some(first) if first < window_start => self.heatmap_offset += 1,
+            _ => self.status_message = Some("No earlier activity".to_string()),
+        }
+    }
+    
+    /// Each day's value in the heatmap's visible window, ending at `heatmap_end`:
+    /// minutes of work, or completed work sessions with the session metric
+    pub fn heatmap_window_data(&self) -> Vec<(chrono::NaiveDate, i64)> {
+        let end = self.heatmap_end();
+        let days = (self.db.today() - end).num_days() + HEATMAP_DAYS;
+        let data = match self.heat_metric() {
+            HeatMetric::Minutes => self.db.get_heatmap_data_for_tag(self.get_heatmap_tag(), days)
+                .map(|data| data.into_iter().map(|(day, seconds)| (day, seconds / 60)).collect()),
+            HeatMetric::Sessions => self.db.get_heatmap_counts_for_tag(self.get_heatmap_tag(), days),
+        };
+        data.unwrap_or_default()
+            .into_iter()
+            .filter(|(day, _)| *day <= end)
+            .collect()
+    }
+    
+    pub fn scroll_heatmap_forward(&mut self) {
+        self.heatmap_offset = self.heatmap_offset.saturating_sub(1);
+    }
+    
+    pub fn heatmap_to_today(&mut self) {
+        self.heatmap_offset = 0;
+    }
+    
+    pub fn get_heatmap_tag(&self) -> Option<&str> {
+        if self.heatmap_tag_index == 0 {
+            None
+        } else {
+            self.all_tags.get(self.heatmap_tag_index - 1).map(|s| s.as_str())
+        }
+    }
+    
+    /// Change the weekly target of the tag selected on the Stats screen.
+    /// Dropping to zero clears the target.
+    pub fn adjust_stats_tag_target(&mut self, delta_minutes: i64) {
+        if self.stats_by_category {
+            self.status_message = Some("Weekly targets are set per tag: press [c] to leave the category view".to_string());
+            return;
+        }
+        if let Some(tag) = self.get_stats_tag() {
+            let current = self.db.get_tag_weekly_target(tag).unwrap_or(0);
+            let new_val = (current + delta_minutes).clamp(0, 100 * 60);
+            let target = if new_val == 0 { None } else { Some(new_val) };
+            let _ = self.db.set_tag_weekly_target(tag, target);
+        }
+    }
+    
+    /// Write the heatmap as an SVG file in the data directory
+    pub fn export_heatmap_svg(&mut self) {
+        let today = self.db.today();
+        let svg = crate::export::heatmap_svg(&self.heatmap_window_data(), self.heatmap_end(), self.week_start, self.heat_scale(), self.heat_metric());
+        let path = Database::data_dir().join(format!("heatmap-{}.svg", today.format("%Y-%m-%d")));
+        
+        self.status_message = Some(match std::fs::write(&path, svg) {
+            Ok(()) => format!("Heatmap saved to {}", path.display()),
+            Err(err) => format!("Could not save heatmap: {}", err),
+        });
+    }
+    
+    /// Switch the format the Stats screen exports sessions in
+    pub fn cycle_export_format(&mut self) {
+        self.export_format = self.export_format.next();
+        let _ = self.db.set_config("export_format", self.export_format.extension());
+        self.status_message = Some(format!("Sessions will be exported as {}", self.export_format.name()));
+    }
+    
+    /// Export every session in the chosen format to a dated file in the data
+    /// directory and show the outcome in a popup
+    pub fn export_sessions(&mut self) {
+        let today = self.db.today();
+        let path = Database::data_dir().join(format!("sessions-{}.{}", today.format("%Y-%m-%d"), self.export_format.extension()));
+        
+        self.export_message = match self.db.export(self.export_format, &path) {
+            Ok(count) => format!("Exported {} sessions as {} to {}", count, self.export_format.name(), path.display()),
+            Err(err) => format!("Could not export sessions: {}", err),
+        };
+        self.navigate_to(Screen::ExportDone);
+    }
+    
+    /// Back up the database to a timestamped file in the data directory and
+    /// show the outcome in a popup
+    pub fn backup_database(&mut self) {
+        let stamp = self.clock.now().format("%Y-%m-%d_%H%M%S");
+        let path = Database::data_dir().join(format!("pomodoro-backup-{}.db", stamp));
+        
+        self.backup_message = match self.db.backup_to(&path) {
+            Ok(()) => format!("Database backed up to {}", path.display()),
+            Err(err) => format!("Could not back up the database: {}", err),
+        };
+        self.navigate_to(Screen::BackupDone);
+    }
+    
+    /// First day kept when purging sessions past the retention window
+    pub fn purge_cutoff(&self) -> Option<chrono::NaiveDate> {
+        self.db.retention_cutoff(self.db.retention_days())
+    }
+    
+    /// Ask before purging sessions older than the retention window, if there are any
+    pub fn request_purge(&mut self) {
+        let cutoff = self.purge_cutoff();
+        if self.db.retention_days() == 0 {
+            self.status_message = Some("Set how many days of sessions to keep first".to_string());
+        } else if cutoff.is_none() {
+            self.status_message = Some("The retention window reaches too far back".to_string());
+        } else if cutoff.is_some_and(|cutoff| self.db.count_sessions_before(cutoff) == 0) {
+            self.status_message = Some(format!("No sessions older than {} days", self.db.retention_days()));
+        } else if self.skip_confirmations {
+            self.purge_old_sessions();
+        } else {
+            self.navigate_to(Screen::PurgeConfirm);
+        }
+    }
+    
+    /// Delete the sessions older than the retention window and reclaim their space
+    pub fn purge_old_sessions(&mut self) {
+        let Some(cutoff) = self.purge_cutoff() else {
+            self.status_message = Some("The retention window reaches too far back".to_string());
+            self.current_screen = Screen::Settings;
+            return;
+        };
+        self.status_message = Some(match self.db.purge_sessions_before(cutoff) {
+            Ok(count) => {
+                let _ = self.db.vacuum();
+                self.refresh_recent_sessions();
+                self.refresh_today_summary();
+                format!("Deleted {} sessions from before {}", count, cutoff)
+            }
+            Err(err) => format!("Could not purge sessions: {}", err),
+        });
+        self.current_screen = Screen::Settings;
+    }
+    
+    /// Work sessions of the current length still needed to reach today's goal,
+    /// or `None` when no daily goal is set
+    pub fn sessions_to_goal(&self) -> Option<u64> {
+        if self.daily_goal == 0 {
+            return None;
+        }
+        Some(sessions_needed(self.today_summary.seconds, self.daily_goal, self.work_duration))
+    }
+    
+    pub fn format_time(&self) -> String {
+        let minutes = self.remaining_seconds / 60;
+        let seconds = self.remaining_seconds % 60;
+        format!("{:02}:{:02}", minutes, seconds)
+    }
+    
+    /// How long the timer has been paused mid-session, as "MM:SS"
+    pub fn format_paused_for(&self) -> Option<String> {
+        let paused = self.pause_started?.elapsed().as_secs();
+        Some(format!("{:02}:{:02}", paused / 60, paused % 60))
+    }
+    
+    /// How long the event loop waits for input before redrawing: short while
+    /// the countdown or the completion flash needs it, longer when idle.
+    /// Key presses end the wait early either way.
+    pub fn poll_interval(&self) -> std::time::Duration {
+        let flashing = self.completion_flash_until.is_some_and(|until| until > Instant::now());
+        let millis = if self.timer_running || flashing { ACTIVE_POLL_MS } else { self.idle_poll_ms };
+        std::time::Duration::from_millis(millis)
+    }
+    
+    /// Whether a running session is within its last `final_countdown` seconds
+    pub fn in_final_countdown(&self) -> bool {
+        self.timer_running
+            && !self.in_overtime
+            && self.remaining_seconds > 0
+            && self.remaining_seconds <= self.final_countdown
+    }
+    
+    /// Whether the completion flash is in its lit phase (blinking every 250ms)
+    pub fn flash_on(&self) -> bool {
+        match self.completion_flash_until {
+            Some(until) => until
+                .checked_duration_since(Instant::now())
+                .is_some_and(|left| (left.as_millis() / 250) % 2 == 1),
+            None => false,
+        }
+    }
+    
+    /// Current wall-clock time in the configured 12h/24h format
+    pub fn format_clock(&self) -> String {
+        self.clock.now().format(self.clock_format()).to_string()
+    }
+    
+    /// Wall-clock time the running session will end, or `None` while it's
+    /// paused, idle or already in overtime
+    pub fn format_end_time(&self) -> Option<String> {
+        if !self.timer_running || self.in_overtime {
+            return None;
+        }
+        let end = self.clock.now() + chrono::Duration::seconds(self.remaining_seconds as i64);
+        Some(end.format(self.clock_format()).to_string())
+    }
+    
+    fn clock_format(&self) -> &'static str {
+        if self.clock_24h { "%H:%M" } else { "%-I:%M %p" }
+    }
+    
+    /// Seconds spent in the session so far, overtime included
+    pub fn elapsed_seconds(&self) -> u64 {
+        self.session_length().saturating_sub(self.remaining_seconds) + self.overtime_seconds
+    }
+    
+    /// Time spent in the session so far, overtime included, as "MM:SS"
+    pub fn format_elapsed(&self) -> String {
+        let elapsed = self.elapsed_seconds();
+        format!("{:02}:{:02}", elapsed / 60, elapsed % 60)
+    }
+    
+    /// What the timer shows: elapsed time when counting up, otherwise time
+    /// left or, past zero, the overtime
+    pub fn timer_text(&self) -> String {
+        if self.count_up {
+            self.format_elapsed()
+        } else if self.in_overtime {
+            self.format_overtime()
+        } else {
+            self.format_time()
+        }
+    }
+    
+    pub fn format_overtime(&self) -> String {
+        let minutes = self.overtime_seconds / 60;
+        let seconds = self.overtime_seconds % 60;
+        format!("{:02}:{:02}", minutes, seconds)
+    }
+    
+    /// Nudge the work duration, returning whether it changed. At a limit
+    /// a short hint says so instead.
+    pub fn adjust_work_duration(&mut self, delta: i64) -> bool {
+        let before = self.work_duration;
+        self.set_work_duration(before as i64 + delta);
+        let changed = self.work_duration != before;
+        if !changed {
+            self.show_limit_hint("work", delta, self.max_work_duration);
+        }
+        changed
+    }
+    
+    pub fn adjust_break_duration(&mut self, delta: i64) -> bool {
+        let before = self.break_duration;
+        self.set_break_duration(before as i64 + delta);
+        let changed = self.break_duration != before;
+        if !changed {
+            self.show_limit_hint("break", delta, self.max_break_duration);
+        }
+        changed
+    }
+    
+    fn show_limit_hint(&mut self, what: &str, delta: i64, max: u64) {
+        let hint = if delta < 0 {
+            format!("{} minimum {} min", what, MIN_DURATION_SECONDS / 60)
+        } else {
+            format!("{} maximum {} min (raise it in Settings)", what, max / 60)
+        };
+        self.limit_hint = Some((hint, Instant::now() + std::time::Duration::from_secs(2)));
+    }
+    
+    /// The duration limit just hit, for two seconds after the key press
+    pub fn limit_hint(&self) -> Option<&str> {
+        self.limit_hint.as_ref()
+            .filter(|(_, until)| *until > Instant::now())
+            .map(|(hint, _)| hint.as_str())
+    }
+    
+    pub fn set_work_duration(&mut self, seconds: i64) {
+        let new_val = (seconds.max(0) as u64).clamp(MIN_DURATION_SECONDS, self.max_work_duration);
+        self.work_duration = new_val;
+        let _ = self.db.set_config("work_duration", &new_val.to_string());
+        
+        if self.mode == PomodoroMode::Work && !self.timer_running {
+            self.remaining_seconds = new_val;
+        }
+    }
+    
+    pub fn set_break_duration(&mut self, seconds: i64) {
+        let new_val = (seconds.max(0) as u64).clamp(MIN_DURATION_SECONDS, self.max_break_duration);
+        self.break_duration = new_val;
+        let _ = self.db.set_config("break_duration", &new_val.to_string());
+        
+        if self.mode == PomodoroMode::Break && !self.long_break && !self.timer_running {
+            self.remaining_seconds = new_val;
+        }
+    }
+    
+    /// Apply a typed "work/break" preset such as "50/10" (in minutes)
+    pub fn apply_duration_preset(&mut self, input: &str) -> Result<(), &'static str> {
+        let (work_minutes, break_minutes) = parse_duration_preset(input)?;
+        self.set_work_duration(work_minutes * 60);
+        self.set_break_duration(break_minutes * 60);
+        Ok(())
+    }
+    
+    pub fn selected_setting(&self) -> Setting {
+        Setting::ALL[self.settings_index]
+    }
+    
+    pub fn next_setting(&mut self) {
+        self.settings_index = (self.settings_index + 1) % Setting::ALL.len();
+    }
+    
+    pub fn prev_setting(&mut self) {
+        self.settings_index = if self.settings_index == 0 {
+            Setting::ALL.len() - 1
+        } else {
+            self.settings_index - 1
+        };
+    }
+    
+    pub fn setting_value(&self, setting: Setting) -> String {
+        match setting {
+            Setting::Theme => self.day_theme.name.to_string(),
+            Setting::NightTheme => self.night_theme.map_or("off", |theme| theme.name).to_string(),
+            Setting::NightHours => format!("{}-{}", self.night_hours.0, self.night_hours.1),
+            Setting::ClockFormat => if self.clock_24h { "24h" } else { "12h" }.to_string(),
+            Setting::ProgressStyle => self.progress_style.name().to_string(),
+            Setting::TagLayout => self.tag_layout.name().to_string(),
+            Setting::BarColoring => self.bar_coloring.name().to_string(),
+            Setting::DefaultTag => self.default_tag.clone(),
+            Setting::IntentionPrompt => on_off(self.intention_prompt),
+            Setting::NotePrompt => on_off(self.note_prompt),
+            Setting::RatingPrompt => on_off(self.rating_prompt),
+            Setting::CompletionFlash => on_off(self.completion_flash),
+            Setting::Overtime => on_off(self.overtime_mode),
+            Setting::CountUp => on_off(self.count_up),
+            Setting::AutoPause => on_off(self.auto_pause),
+            Setting::Silent => on_off(self.silent),
+            Setting::WorkUrgency => self.work_urgency.name().to_string(),
+            Setting::BreakUrgency => self.break_urgency.name().to_string(),
+            Setting::NotificationTimeout => self.notification_timeout.to_string(),
+            Setting::Sound => on_off(self.sound_enabled),
+            Setting::SoundPlayer => self.sound_player.clone(),
+            Setting::SoundPath => self.sound_path.clone(),
+            Setting::TickSound => on_off(self.tick_sound),
+            Setting::TickSoundPath => self.tick_sound_path.clone(),
+            Setting::TickInterval => self.tick_interval.to_string(),
+            Setting::FinalCountdown => self.final_countdown.to_string(),
+            Setting::FocusMusic => on_off(self.focus_music),
+            Setting::FocusMusicStart => self.focus_music_start.clone(),
+            Setting::FocusMusicStop => self.focus_music_stop.clone(),
+            Setting::AutosaveInterval => self.autosave_interval.to_string(),
+            Setting::IdlePoll => self.idle_poll_ms.to_string(),
+            Setting::DayRollover => format!("{}:00", self.drafted("day_rollover").unwrap_or_else(|| self.db.rollover_hour().to_string())),
+            Setting::Retention => self.drafted("retention_days").unwrap_or_else(|| self.db.retention_days().to_string()),
+            Setting::DailyGoal => self.daily_goal.to_string(),
+            Setting::WeeklyGoal => self.weekly_goal.to_string(),
+            Setting::PomodoroGoal => self.daily_pomodoro_goal.to_string(),
+            Setting::BreakReminder => self.break_reminder.to_string(),
+            Setting::BreakLock => self.break_lock.to_string(),
+            Setting::BreakTag => on_off(self.break_follows_work),
+            Setting::BreakSuggestions => on_off(self.break_suggestions),
+            Setting::BreakSuggestionList => self.break_suggestion_list.join("; "),
+            Setting::FocusLock => on_off(self.focus_lock),
+            Setting::SkipConfirmations => on_off(self.skip_confirmations),
+            Setting::RecentBlocks => on_off(self.recent_blocks),
+            Setting::BlockGap => self.block_gap.to_string(),
+            Setting::LongBreakEvery => self.long_break_every.to_string(),
+            Setting::LongBreakLength => (self.long_break_duration / 60).to_string(),
+            Setting::CycleReset => self.cycle_reset.name().to_string(),
+            Setting::MaxWork => (self.max_work_duration / 60).to_string(),
+            Setting::MaxBreak => (self.max_break_duration / 60).to_string(),
+            Setting::Milestones => self.milestones.iter().map(|m| m.to_string()).collect::<Vec<_>>().join(","),
+            Setting::HeatmapThresholds => self.heatmap_thresholds.map(|t| t.to_string()).join(","),
+            Setting::RestDays => match self.drafted("rest_days") {
+                Some(days) => parse_rest_days(&days).unwrap_or_default(),
+                None => RestDays::load(&self.db).weekdays,
+            }.iter().map(|d| d.to_string()).collect::<Vec<_>>().join(","),
+            Setting::StreakFreezes => self.drafted("streak_freezes").unwrap_or_else(|| RestDays::load(&self.db).freezes.to_string()),
+            Setting::WeekStart => match self.week_start {
+                Weekday::Sun => "Sunday".to_string(),
+                _ => "Monday".to_string(),
+            },
+        }
+    }
+    
+    /// Open the Settings screen, remembering the current values so changes
+    /// can be told apart and discarded
+    pub fn open_settings(&mut self) {
+        self.settings_draft.clear();
+        self.refresh_setting_values();
+        self.settings_saved = self.settings_values.clone();
+        self.navigate_to(Screen::Settings);
+    }
+    
+    /// Recompute the values shown on the Settings screen after a change
+    fn refresh_setting_values(&mut self) {
+        self.settings_values = Setting::ALL.iter().map(|setting| self.setting_value(*setting)).collect();
+    }
+    
+    /// A setting's value as shown on the Settings screen, unsaved changes included
+    pub fn shown_setting_value(&self, setting: Setting) -> &str {
+        Setting::ALL.iter().position(|s| *s == setting)
+            .and_then(|i| self.settings_values.get(i))
+            .map_or("", String::as_str)
+    }
+    
+    /// Whether a setting differs from its saved value. Changes take effect
+    /// right away as a preview, but only reach the database when saved.
+    pub fn setting_modified(&self, setting: Setting) -> bool {
+        Setting::ALL.iter().position(|s| *s == setting)
+            .and_then(|i| self.settings_saved.get(i).zip(self.settings_values.get(i)))
+            .is_some_and(|(saved, shown)| saved != shown)
+    }
+    
+    pub fn has_unsaved_settings(&self) -> bool {
+        self.settings_saved != self.settings_values
+    }
+    
+    /// Hold a changed setting's config value until Settings are saved
+    fn stage_config(&mut self, key: &'static str, value: String) {
+        self.settings_draft.insert(key, value);
+    }
+    
+    /// A config value changed on the Settings screen but not saved yet
+    fn drafted(&self, key: &str) -> Option<String> {
+        self.settings_draft.get(key).cloned()
+    }
+    
+    /// Write the changed settings to the database
+    pub fn save_settings(&mut self) {
+        if self.settings_draft.is_empty() {
+            return;
+        }
+        // A long break cycle counts from when it's turned on or its reset changes
+        let restart_cycle = self.db.get_config("cycle_reset", "never") != self.cycle_reset.name()
+            || (self.db.get_config("long_break_every", "0") == "0" && self.long_break_every > 0);
+        let draft: Vec<(&str, String)> = self.settings_draft.iter().map(|(key, value)| (*key, value.clone())).collect();
+        if let Err(err) = self.db.set_configs(&draft) {
+            self.status_message = Some(format!("Could not save settings: {}", err));
+            return;
+        }
+        
+        if restart_cycle {
+            let _ = self.db.restart_long_break_cycle(&self.clock.now());
+        }
+        if let Some(hour) = self.drafted("day_rollover").and_then(|hour| hour.parse().ok()) {
+            let _ = self.db.set_rollover_hour(hour);
+        }
+        if let Some(tag) = self.drafted("default_tag") {
+            let lowercase = tag.to_lowercase();
+            if !self.tags.iter().any(|t| t.to_lowercase() == lowercase) {
+                let _ = self.add_tag(&tag);
+            }
+        }
+        // Durations above a lowered maximum come down to it
+        self.set_work_duration(self.work_duration as i64);
+        self.set_break_duration(self.break_duration as i64);
+        self.refresh_today_summary();
+        
+        self.settings_draft.clear();
+        self.refresh_setting_values();
+        self.settings_saved = self.settings_values.clone();
+        self.status_message = Some("Settings saved".to_string());
+    }
+    
+    /// Put every modified setting back to its saved value. Nothing unsaved has
+    /// reached the database, so only the values in memory need restoring.
+    pub fn discard_settings(&mut self) {
+        if !self.has_unsaved_settings() {
+            self.settings_draft.clear();
+            return;
+        }
+        for (setting, saved) in Setting::ALL.iter().zip(self.settings_saved.clone()) {
+            if self.setting_modified(*setting) {
+                self.restore_setting(*setting, &saved);
+            }
+        }
+        self.settings_draft.clear();
+        self.refresh_theme();
+        self.sync_focus_music();
+        self.refresh_session_blocks();
+        self.refresh_today_summary();
+        self.refresh_setting_values();
+        self.status_message = Some("Changes discarded".to_string());
+    }
+    
+    /// Set a setting back to a value in the form `setting_value` shows
+    fn restore_setting(&mut self, setting: Setting, value: &str) {
+        if setting.is_text() {
+            let _ = self.set_text_setting(setting, value.to_string());
+            return;
+        }
+        let on = value == "On";
+        match setting {
+            Setting::Theme => self.day_theme = Theme::by_name(value),
+            Setting::NightTheme => self.night_theme = (value != "off").then(|| Theme::by_name(value)),
+            Setting::ClockFormat => self.clock_24h = value == "24h",
+            Setting::ProgressStyle => self.progress_style = ProgressStyle::from_name(value),
+            Setting::TagLayout => self.tag_layout = TagLayout::from_name(value),
+            Setting::BarColoring => self.bar_coloring = BarColoring::from_name(value),
+            Setting::CycleReset => self.cycle_reset = CycleReset::from_name(value),
+            Setting::WorkUrgency => self.work_urgency = Urgency::from_name(value),
+            Setting::BreakUrgency => self.break_urgency = Urgency::from_name(value),
+            Setting::WeekStart => self.week_start = if value == "Sunday" { Weekday::Sun } else { Weekday::Mon },
+            Setting::IntentionPrompt => self.intention_prompt = on,
+            Setting::NotePrompt => self.note_prompt = on,
+            Setting::RatingPrompt => self.rating_prompt = on,
+            Setting::CompletionFlash => self.completion_flash = on,
+            Setting::Overtime => self.overtime_mode = on,
+            Setting::CountUp => self.count_up = on,
+            Setting::AutoPause => self.auto_pause = on,
+            Setting::BreakTag => self.break_follows_work = on,
+            Setting::BreakSuggestions => self.break_suggestions = on,
+            Setting::FocusLock => self.focus_lock = on,
+            Setting::SkipConfirmations => self.skip_confirmations = on,
+            Setting::RecentBlocks => self.recent_blocks = on,
+            Setting::Silent => self.silent = on,
+            Setting::FocusMusic => self.focus_music = on,
+            Setting::Sound => self.sound_enabled = on,
+            Setting::TickSound => self.tick_sound = on,
+            _ => {}
+        }
+    }
+    
+    /// Go back Home, unless there are changes to save or discard first
+    pub fn leave_settings(&mut self) {
+        if self.has_unsaved_settings() {
+            self.status_message = Some("Unsaved changes: [s] Save or [x] Discard".to_string());
+        } else {
+            self.navigate_to(Screen::Home);
+        }
+    }
+    
+    /// Toggle or cycle a setting; text settings open an input popup instead
+    pub fn change_setting(&mut self, setting: Setting) {
+        if setting.is_text() {
+            self.input_buffer = self.setting_value(setting);
+            self.input_mode = InputMode::Editing;
+            self.navigate_to(Screen::SettingInput);
+            return;
+        }
+        
+        match setting {
+            Setting::Theme => {
+                self.day_theme = self.day_theme.next();
+                self.stage_config("theme", self.day_theme.name.to_string());
+                self.refresh_theme();
+            }
+            Setting::NightTheme => {
+                // Off, then each theme starting with the night one, then off again
+                self.night_theme = match self.night_theme {
+                    None => Some(Theme::NIGHT),
+                    Some(theme) if theme.next() == Theme::NIGHT => None,
+                    Some(theme) => Some(theme.next()),
+                };
+                self.stage_config("night_theme", self.setting_value(Setting::NightTheme));
+                self.refresh_theme();
+            }
+            Setting::ClockFormat => {
+                self.clock_24h = !self.clock_24h;
+                self.stage_config("clock_format", self.setting_value(Setting::ClockFormat));
+            }
+            Setting::ProgressStyle => {
+                self.progress_style = self.progress_style.next();
+                self.stage_config("progress_style", self.progress_style.name().to_string());
+            }
+            Setting::TagLayout => {
+                self.tag_layout = self.tag_layout.next();
+                self.stage_config("tag_layout", self.tag_layout.name().to_string());
+            }
+            Setting::BarColoring => {
+                self.bar_coloring = self.bar_coloring.next();
+                self.stage_config("bar_coloring", self.bar_coloring.name().to_string());
+            }
+            Setting::IntentionPrompt => stage_flag(&mut self.settings_draft, "intention_prompt", &mut self.intention_prompt),
+            Setting::NotePrompt => stage_flag(&mut self.settings_draft, "note_prompt", &mut self.note_prompt),
+            Setting::RatingPrompt => stage_flag(&mut self.settings_draft, "rating_prompt", &mut self.rating_prompt),
+            Setting::CompletionFlash => stage_flag(&mut self.settings_draft, "completion_flash", &mut self.completion_flash),
+            Setting::Overtime => stage_flag(&mut self.settings_draft, "overtime_mode", &mut self.overtime_mode),
+            Setting::CountUp => stage_flag(&mut self.settings_draft, "count_up", &mut self.count_up),
+            Setting::AutoPause => stage_flag(&mut self.settings_draft, "auto_pause", &mut self.auto_pause),
+            Setting::BreakTag => stage_flag(&mut self.settings_draft, "break_follows_work", &mut self.break_follows_work),
+            Setting::BreakSuggestions => stage_flag(&mut self.settings_draft, "break_suggestions", &mut self.break_suggestions),
+            Setting::FocusLock => stage_flag(&mut self.settings_draft, "focus_lock", &mut self.focus_lock),
+            Setting::SkipConfirmations => stage_flag(&mut self.settings_draft, "skip_confirmations", &mut self.skip_confirmations),
+            Setting::RecentBlocks => {
+                stage_flag(&mut self.settings_draft, "recent_blocks", &mut self.recent_blocks);
+                self.refresh_session_blocks();
+            }
+            Setting::Silent => {
+                stage_flag(&mut self.settings_draft, "silent", &mut self.silent);
+                self.sync_focus_music();
+            }
+            Setting::FocusMusic => {
+                stage_flag(&mut self.settings_draft, "focus_music", &mut self.focus_music);
+                self.sync_focus_music();
+            }
+            Setting::CycleReset => {
+                self.cycle_reset = self.cycle_reset.next();
+                self.stage_config("cycle_reset", self.cycle_reset.name().to_string());
+            }
+            Setting::WorkUrgency => {
+                self.work_urgency = self.work_urgency.next();
+                self.stage_config("work_urgency", self.work_urgency.name().to_string());
+            }
+            Setting::BreakUrgency => {
+                self.break_urgency = self.break_urgency.next();
+                self.stage_config("break_urgency", self.break_urgency.name().to_string());
+            }
+            Setting::Sound => stage_flag(&mut self.settings_draft, "sound_enabled", &mut self.sound_enabled),
+            Setting::TickSound => stage_flag(&mut self.settings_draft, "tick_sound", &mut self.tick_sound),
+            Setting::WeekStart => {
+                let (week_start, value) = match self.week_start {
+                    Weekday::Sun => (Weekday::Mon, "monday"),
+                    _ => (Weekday::Sun, "sunday"),
+                };
+                self.week_start = week_start;
+                self.stage_config("week_start", value.to_string());
+                self.refresh_today_summary();
+            }
+            _ => {}
+        }
+        self.refresh_setting_values();
+    }
+    
+    /// Save the value typed for the selected text setting
+    pub fn apply_setting_input(&mut self) -> Result<(), &'static str> {
+        let value = self.input_buffer.trim().to_string();
+        self.set_text_setting(self.selected_setting(), value)?;
+        self.refresh_setting_values();
+        Ok(())
+    }
+    
+    /// Set a text setting from its typed form, the same form `setting_value` shows
+    fn set_text_setting(&mut self, setting: Setting, value: String) -> Result<(), &'static str> {
+        match setting {
+            Setting::SoundPlayer => {
+                if value.split(',').all(|player| player.trim().is_empty()) {
+                    return Err("Enter at least one player command");
+                }
+                self.sound_player = value;
+                self.sound_failed.store(false, Ordering::Relaxed);
+                self.stage_config("sound_player", self.sound_player.clone());
+            }
+            Setting::SoundPath => {
+                self.sound_path = value;
+                self.stage_config("sound_path", self.sound_path.clone());
+            }
+            Setting::FocusMusicStart => {
+                self.focus_music_start = value;
+                self.stage_config("focus_music_start", self.focus_music_start.clone());
+            }
+            Setting::FocusMusicStop => {
+                self.focus_music_stop = value;
+                self.stage_config("focus_music_stop", self.focus_music_stop.clone());
+            }
+            Setting::TickSoundPath => {
+                self.tick_sound_path = value;
+                self.stage_config("tick_sound_path", self.tick_sound_path.clone());
+            }
+            Setting::TickInterval => {
+                let seconds: u64 = value.parse().map_err(|_| "Enter a whole number of seconds")?;
+                if !(1..=60).contains(&seconds) {
+                    return Err("Interval must be between 1 and 60 seconds");
+                }
+                self.tick_interval = seconds;
+                self.stage_config("tick_interval", seconds.to_string());
+            }
+            Setting::FinalCountdown => {
+                let seconds: u64 = value.parse().map_err(|_| "Enter a whole number of seconds")?;
+                if seconds > MAX_FINAL_COUNTDOWN {
+                    return Err("Countdown must be between 0 and 30 seconds");
+                }
+                self.final_countdown = seconds;
+                self.stage_config("final_countdown", seconds.to_string());
+            }
+            Setting::NotificationTimeout => {
+                let seconds: u64 = value.parse().map_err(|_| "Enter a whole number of seconds")?;
+                if seconds > 3600 {
+                    return Err("Keep the timeout under an hour");
+                }
+                self.notification_timeout = seconds;
+                self.stage_config("notification_timeout", seconds.to_string());
+            }
+            Setting::NightHours => {
+                const ERROR: &str = "Enter two hours from 0 to 23, e.g. 21-7";
+                let (start, end) = value.split_once('-').ok_or(ERROR)?;
+                let start: u32 = start.trim().parse().map_err(|_| ERROR)?;
+                let end: u32 = end.trim().parse().map_err(|_| ERROR)?;
+                if start > 23 || end > 23 {
+                    return Err(ERROR);
+                }
+                self.night_hours = (start, end);
+                self.stage_config("night_theme_start", start.to_string());
+                self.stage_config("night_theme_end", end.to_string());
+                self.refresh_theme();
+            }
+            Setting::Retention => {
+                const ERROR: &str = "Enter a whole number of days up to 36500";
+                let days: i64 = value.parse().map_err(|_| ERROR)?;
+                if !(0..=crate::db::MAX_RETENTION_DAYS).contains(&days) {
+                    return Err(ERROR);
+                }
+                self.stage_config("retention_days", days.to_string());
+            }
+            Setting::DayRollover => {
+                let hour: u32 = value.trim_end_matches(":00").parse().map_err(|_| "Enter an hour from 0 to 23")?;
+                if hour > 23 {
+                    return Err("Enter an hour from 0 to 23");
+                }
+                self.stage_config("day_rollover", hour.to_string());
+            }
+            Setting::AutosaveInterval => {
+                let seconds: u64 = value.parse().map_err(|_| "Enter a whole number of seconds")?;
+                if !(1..=300).contains(&seconds) {
+                    return Err("Interval must be between 1 and 300 seconds");
+                }
+                self.autosave_interval = seconds;
+                self.stage_config("autosave_interval", seconds.to_string());
+            }
+            Setting::IdlePoll => {
+                let millis: u64 = value.parse().map_err(|_| "Enter a whole number of milliseconds")?;
+                if !(ACTIVE_POLL_MS..=1000).contains(&millis) {
+                    return Err("Interval must be between 100 and 1000 ms");
+                }
+                self.idle_poll_ms = millis;
+                self.stage_config("idle_poll_ms", millis.to_string());
+            }
+            Setting::BreakReminder => {
+                let minutes: u64 = value.parse().map_err(|_| "Enter a whole number of minutes")?;
+                if minutes > 60 {
+                    return Err("The reminder looks at one hour, so use 60 minutes or less");
+                }
+                self.break_reminder = minutes;
+                self.stage_config("break_reminder", minutes.to_string());
+            }
+            Setting::BreakLock => {
+                let percent: u64 = value.trim_end_matches('%').parse().map_err(|_| "Enter a percentage from 0 to 100")?;
+                if percent > 100 {
+                    return Err("Enter a percentage from 0 to 100");
+                }
+                self.break_lock = percent;
+                self.stage_config("break_lock", percent.to_string());
+            }
+            Setting::MaxWork => {
+                self.max_work_duration = parse_max_minutes(&value)? * 60;
+                self.stage_config("max_work_duration", self.max_work_duration.to_string());
+            }
+            Setting::MaxBreak => {
+                self.max_break_duration = parse_max_minutes(&value)? * 60;
+                self.stage_config("max_break_duration", self.max_break_duration.to_string());
+            }
+            Setting::BlockGap => {
+                let minutes: u64 = value.parse().map_err(|_| "Enter a whole number of minutes")?;
+                if minutes > MAX_BLOCK_GAP_MINUTES {
+                    return Err("Gap must be between 0 and 240 minutes");
+                }
+                self.block_gap = minutes;
+                self.stage_config("block_gap_minutes", minutes.to_string());
+                self.refresh_session_blocks();
+            }
+            Setting::BreakSuggestionList => {
+                self.break_suggestion_list = parse_break_suggestions(&value)?;
+                self.stage_config("break_suggestion_list", self.setting_value(Setting::BreakSuggestionList));
+            }
+            Setting::Milestones => {
+                self.milestones = parse_milestones(&value)?;
+                self.stage_config("milestones", self.setting_value(Setting::Milestones));
+            }
+            Setting::HeatmapThresholds => {
+                self.heatmap_thresholds = parse_thresholds(&value)?;
+                self.stage_config("heatmap_thresholds", self.setting_value(Setting::HeatmapThresholds));
+            }
+            Setting::DefaultTag => {
+                // Pick up an existing tag whatever its case; a new one is created on save
+                let name = normalize_tag_name(&value)?;
+                let lowercase = name.to_lowercase();
+                self.default_tag = self.tags.iter().chain(&self.archived_tags)
+                    .find(|tag| tag.to_lowercase() == lowercase)
+                    .cloned()
+                    .unwrap_or(name);
+                self.stage_config("default_tag", self.default_tag.clone());
+            }
+            Setting::RestDays => {
+                let weekdays = parse_rest_days(&value)?;
+                if weekdays.len() == 7 {
+                    return Err("Leave at least one day to work on");
+                }
+                let value = weekdays.iter().map(|d| d.to_string().to_lowercase()).collect::<Vec<_>>().join(",");
+                self.stage_config("rest_days", value);
+            }
+            Setting::StreakFreezes => {
+                let freezes: u32 = value.parse().map_err(|_| "Enter a whole number of days")?;
+                if freezes > 30 {
+                    return Err("Use 30 days or fewer");
+                }
+                self.stage_config("streak_freezes", freezes.to_string());
+            }
+            Setting::LongBreakEvery => {
+                let sessions: u64 = value.parse().map_err(|_| "Enter a whole number of sessions")?;
+                if sessions > 12 {
+                    return Err("Use 12 sessions or fewer");
+                }
+                self.long_break_every = sessions;
+                self.stage_config("long_break_every", sessions.to_string());
+            }
+            Setting::LongBreakLength => {
+                let minutes: u64 = value.parse().map_err(|_| "Enter a whole number of minutes")?;
+                if !(MIN_DURATION_SECONDS / 60..=self.max_break_duration / 60).contains(&minutes) {
+                    return Err("Long break must be between 1 minute and the longest break");
+                }
+                self.long_break_duration = minutes * 60;
+                self.stage_config("long_break_duration", self.long_break_duration.to_string());
+                if self.long_break && !self.timer_running {
+                    self.remaining_seconds = self.long_break_duration;
+                }
+            }
+            Setting::DailyGoal => {
+                let minutes: u64 = value.parse().map_err(|_| "Enter a whole number of minutes")?;
+                if minutes > 1440 {
+                    return Err("A day only has 1440 minutes");
+                }
+                self.daily_goal = minutes;
+                self.stage_config("daily_goal", minutes.to_string());
+            }
+            Setting::WeeklyGoal => {
+                let minutes: u64 = value.parse().map_err(|_| "Enter a whole number of minutes")?;
+                if minutes > 7 * 1440 {
+                    return Err("A week only has 10080 minutes");
+                }
+                self.weekly_goal = minutes;
+                self.stage_config("weekly_goal_minutes", minutes.to_string());
+            }
+            Setting::PomodoroGoal => {
+                let count: u64 = value.parse().map_err(|_| "Enter a whole number of pomodoros")?;
+                if count > 48 {
+                    return Err("Pick at most 48 pomodoros a day");
+                }
+                self.daily_pomodoro_goal = count;
+                self.stage_config("daily_pomodoro_goal", count.to_string());
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+}
+
+/// Trims and collapses whitespace in a tag name, rejecting empty or overlong names
+pub fn normalize_tag_name(raw: &str) -> Result<String, &'static str> {
+    let name = raw.split_whitespace().collect::<Vec<_>>().join(" ");
+    if name.is_empty() {
+        Err("Tag name cannot be empty")
+    } else if name.chars().count() > MAX_TAG_LENGTH {
+        Err("Tag name is too long (max 30 characters)")
+    } else {
+        Ok(name)
+    }
+}
+
+/// The category of a sub-tag: "Study" for "Study/Math", `None` for a flat tag
+pub fn tag_category(tag: &str) -> Option<&str> {
+    match tag.split_once('/') {
+        Some((category, _)) if !category.is_empty() => Some(category),
+        _ => None,
+    }
+}
+
+/// Tags plus the categories their sub-tags belong to, sorted, for the stats
+/// and heatmap filters
+fn with_categories(mut tags: Vec<String>) -> Vec<String> {
+    let categories: Vec<String> = tags.iter().filter_map(|tag| tag_category(tag)).map(String::from).collect();
+    tags.extend(categories);
+    tags.sort();
+    tags.dedup();
+    tags
+}
+
+/// Parses "work/break" minutes such as "50/10" or " 25 / 5 "
+pub fn parse_duration_preset(input: &str) -> Result<(i64, i64), &'static str> {
+    let (work, brk) = input.split_once('/').ok_or("Use the format work/break, e.g. 50/10")?;
+    let work: i64 = work.trim().parse().map_err(|_| "Work minutes must be a number")?;
+    let brk: i64 = brk.trim().parse().map_err(|_| "Break minutes must be a number")?;
+    Ok((work, brk))
+}
+
+/// Sessions of `work_seconds` needed to bring `done_seconds` up to `goal_minutes`
+pub fn sessions_needed(done_seconds: i64, goal_minutes: u64, work_seconds: u64) -> u64 {
+    let missing = (goal_minutes * 60).saturating_sub(done_seconds.max(0) as u64);
+    missing.div_ceil(work_seconds.max(1))
+}
+
+/// Parses a maximum duration in minutes, from the minimum duration up to the ceiling
+fn parse_max_minutes(input: &str) -> Result<u64, &'static str> {
+    let minutes: u64 = input.parse().map_err(|_| "Enter a whole number of minutes")?;
+    if !(MIN_DURATION_SECONDS / 60..=DURATION_CEILING_MINUTES).contains(&minutes) {
+        return Err("Maximum must be between 1 and 720 minutes");
+    }
+    Ok(minutes)
+}
+
+/// Parses a comma-separated list of minutes such as "30,60,120", sorted and
+/// without duplicates. An empty list turns milestones off.
+pub fn parse_milestones(input: &str) -> Result<Vec<u64>, &'static str> {
+    let mut milestones: Vec<u64> = input
+        .split(',')
+        .map(str::trim)
+        .filter(|part| !part.is_empty())
+        .map(|part| part.parse::<u64>().ok().filter(|m| (1..=1440).contains(m)))
+        .collect::<Option<_>>()
+        .ok_or("Enter minutes between 1 and 1440, e.g. 30,60,120")?;
+    milestones.sort_unstable();
+    milestones.dedup();
+    Ok(milestones)
+}
+
+/// Parses a list of break suggestions separated by semicolons, such as
+/// "Stretch; Get water", dropping blanks and repeats
+pub fn parse_break_suggestions(input: &str) -> Result<Vec<String>, &'static str> {
+    let mut suggestions: Vec<String> = Vec::new();
+    for suggestion in input.split(';').map(str::trim).filter(|s| !s.is_empty()) {
+        if !suggestions.iter().any(|s| s == suggestion) {
+            suggestions.push(suggestion.to_string());
+        }
+    }
+    if suggestions.is_empty() {
+        return Err("Enter at least one suggestion, e.g. Stretch; Get water");
+    }
+    Ok(suggestions)
+}
+
+/// Parses a manual entry such as "45", "45 14:00" or "45 2024-06-10 14:00"
+/// into minutes and a start time. Sessions may not end after `now`.
+pub fn parse_manual_entry(input: &str, now: chrono::NaiveDateTime) -> Result<(i64, chrono::NaiveDateTime), &'static str> {
+    let input = input.trim();
+    let (minutes, start) = input.split_once(' ').unwrap_or((input, ""));
+    let minutes: i64 = minutes.parse().map_err(|_| "Start with the minutes worked, e.g. 45")?;
+    if !(1..=720).contains(&minutes) {
+        return Err("Minutes must be between 1 and 720");
+    }
+    
+    let duration = chrono::Duration::minutes(minutes);
+    let start = match start.trim() {
+        "" => now - duration,
+        time => chrono::NaiveDateTime::parse_from_str(time, "%Y-%m-%d %H:%M")
+            .or_else(|_| chrono::NaiveTime::parse_from_str(time, "%H:%M").map(|t| now.date().and_time(t)))
+            .map_err(|_| "Use HH:MM or YYYY-MM-DD HH:MM for the start time")?,
+    };
+    if start + duration > now {
+        return Err("The session can't end in the future");
+    }
+    Ok((minutes, start))
+}
+
+/// Parses three increasing minute thresholds such as "15,30,60"
+pub fn parse_thresholds(input: &str) -> Result<[i64; 3], &'static str> {
+    let values: Vec<i64> = input
+        .split(',')
+        .map(|part| part.trim().parse::<i64>())
+        .collect::<Result<_, _>>()
+        .map_err(|_| "Enter three numbers, e.g. 15,30,60")?;
+    match values[..] {
+        [low, mid, high] if 0 < low && low < mid && mid < high => Ok([low, mid, high]),
+        [_, _, _] => Err("Thresholds must be positive and increasing"),
+        _ => Err("Enter three numbers, e.g. 15,30,60"),
+    }
+}
+
+/// Play `path` with the first of `players` that runs and exits successfully,
+/// returning whether any did
+fn play_with_fallback(players: &[String], path: &std::path::Path) -> bool {
+    players.iter().any(|player| {
+        Command::new(player)
+            .arg(path)
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .status()
+            .is_ok_and(|status| status.success())
+    })
+}
+
+/// Arguments for `notify-send`; a timeout of 0 leaves it to the daemon
+fn notify_send_args(urgency: Urgency, timeout_seconds: u64, title: &str, message: &str) -> Vec<String> {
+    let mut args = vec!["-u".to_string(), urgency.name().to_string()];
+    if timeout_seconds > 0 {
+        args.push("-t".to_string());
+        args.push((timeout_seconds * 1000).to_string());
+    }
+    args.push(title.to_string());
+    args.push(message.to_string());
+    args
+}
+
+/// The newest work blocks for the Recent panel, grouped from the latest sessions
+fn load_session_blocks(db: &Database, gap_minutes: u64) -> Vec<SessionBlock> {
+    let sessions = db.get_recent_sessions(BLOCK_SOURCE_SESSIONS).unwrap_or_default();
+    let mut blocks = group_into_blocks(&sessions, gap_minutes as i64);
+    blocks.truncate(RECENT_SESSION_COUNT);
+    blocks
+}
+
+/// The latest `week_start` weekday on or before `day`
+fn week_start_on_or_before(day: chrono::NaiveDate, week_start: Weekday) -> chrono::NaiveDate {
+    let days_in = (7 + day.weekday().num_days_from_monday() - week_start.num_days_from_monday()) % 7;
+    day - chrono::Duration::days(days_in as i64)
+}
+
+/// Work sessions logged since the last long break, within the current day or
+/// week when the cycle resets
+fn count_work_in_cycle(db: &Database, cycle_reset: CycleReset, week_start: chrono::NaiveDate) -> u64 {
+    let since = match cycle_reset {
+        CycleReset::Never => None,
+        CycleReset::Daily => Some(db.today()),
+        CycleReset::Weekly => Some(week_start),
+    };
+    db.count_work_since_long_break(since) as u64
+}
+
+fn toggle_flag(db: &Database, key: &str, flag: &mut bool) {
+    *flag = !*flag;
+    let _ = db.set_config(key, &flag.to_string());
+}
+
+/// Flip a boolean setting, holding the new value until Settings are saved
+fn stage_flag(draft: &mut HashMap<&'static str, String>, key: &'static str, flag: &mut bool) {
+    *flag = !*flag;
+    draft.insert(key, flag.to_string());
+}
+
+fn on_off(value: bool) -> String {
+    if value { "On" } else { "Off" }.to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::Cell;
+    use std::rc::Rc;
+
+    fn test_app() -> App {
+        let mut app = App::with_database(Database::open_in_memory().unwrap()).unwrap();
+        app.ascii = false;
+        app.sound_enabled = false;
+        app.silent = true;
+        app
+    }
+
+    #[test]
+    fn test_startup_repairs_out_of_range_durations() {
+        let db = Database::open_in_memory().unwrap();
+        db.set_config("work_duration", "-1500").unwrap();
+        db.set_config("break_duration", "86400").unwrap();
+        db.set_config("long_break_duration", "0").unwrap();
+        let app = App::with_database(db).unwrap();
+        
+        assert_eq!(app.work_duration, 1500);
+        assert_eq!(app.break_duration, DEFAULT_MAX_BREAK_SECONDS);
+        assert_eq!(app.long_break_duration, MIN_DURATION_SECONDS);
+        assert_eq!(app.db.get_config("break_duration", ""), DEFAULT_MAX_BREAK_SECONDS.to_string());
+    }
+
+    #[test]
+    fn test_tick_advances_by_elapsed_seconds() {
+        let mut app = test_app();
+        app.toggle_timer();
+        app.tick(90);
+        assert_eq!(app.remaining_seconds, app.work_duration - 90);
+        assert_eq!(app.mode, PomodoroMode::Work);
+    }
+
+    #[test]
+    fn test_final_countdown_covers_last_seconds() {
+        let mut app = test_app();
+        app.final_countdown = 5;
+        app.toggle_timer();
+        app.tick(app.work_duration - 6);
+        assert!(!app.in_final_countdown());
+        
+        app.tick(1);
+        assert!(app.in_final_countdown());
+        assert_eq!(app.last_countdown_second, Some(5));
+        app.tick(2);
+        assert_eq!(app.last_countdown_second, Some(3));
+        
+        app.toggle_timer();
+        assert!(!app.in_final_countdown());
+    }
+
+    #[test]
+    fn test_stats_peek_reads_summary_kept_current() {
+        let mut app = test_app();
+        app.toggle_timer();
+        app.tick(app.work_duration);
+        assert_eq!(app.today_summary.sessions, 1);
+        assert_eq!(app.today_summary.week_seconds, app.work_duration as i64);
+        
+        app.toggle_stats_peek();
+        assert_eq!(app.current_screen, Screen::StatsPeek);
+        app.toggle_stats_peek();
+        assert_eq!(app.current_screen, Screen::Home);
+        
+        // Moving the week start moves the week total with it
+        let sunday = week_start_on_or_before(app.db.today(), Weekday::Sun);
+        let at = Local.from_local_datetime(&sunday.and_hms_opt(12, 0, 0).unwrap()).unwrap();
+        app.db.save_session(&at, &(at + chrono::Duration::minutes(25)), 1500, &["Work"], "work").unwrap();
+        app.change_setting(Setting::WeekStart);
+        assert_eq!(app.week_start, Weekday::Sun);
+        assert_eq!(app.today_summary.week_seconds, app.work_duration as i64 + 1500);
+    }
+
+    #[test]
+    fn test_count_up_shows_elapsed() {
+        let mut app = test_app();
+        app.toggle_timer();
+        app.tick(90);
+        assert_eq!(app.timer_text(), "23:30");
+        app.count_up = true;
+        assert_eq!(app.timer_text(), "01:30");
+    }
+
+    #[test]
+    fn test_polls_slower_while_idle() {
+        let mut app = test_app();
+        app.completion_flash = false;
+        assert_eq!(app.poll_interval().as_millis(), 500);
+        app.toggle_timer();
+        assert_eq!(app.poll_interval().as_millis(), 100);
+    }
+
+    #[test]
+    fn test_tick_completes_after_large_jump() {
+        let mut app = test_app();
+        app.toggle_timer();
+        // e.g. the laptop was suspended for an hour mid-session
+        app.tick(3600);
+        assert_eq!(app.mode, PomodoroMode::Break);
+        assert_eq!(app.remaining_seconds, app.break_duration);
+        assert!(!app.timer_running);
+    }
+
+    #[test]
+    fn test_full_cycle_logs_work_then_break() {
+        let mut app = test_app();
+        app.toggle_timer();
+        app.tick(app.work_duration);
+        assert_eq!(app.mode, PomodoroMode::Break);
+        
+        app.toggle_timer();
+        app.tick(app.break_duration);
+        assert_eq!(app.mode, PomodoroMode::Work);
+        assert_eq!(app.remaining_seconds, app.work_duration);
+        
+        let types: Vec<&str> = app.recent_sessions.iter().map(|s| s.session_type.as_str()).collect();
+        assert_eq!(types, ["break", "work"]);
+        assert_eq!(app.db.get_session_count_today(), 1);
+    }
+
+    /// A clock that only moves when the test moves it
+    struct FixedClock(Rc<Cell<DateTime<Local>>>);
+
+    impl Clock for FixedClock {
+        fn now(&self) -> DateTime<Local> {
+            self.0.get()
+        }
+    }
+
+    #[test]
+    fn test_completed_session_logs_clock_times() {
+        let mut app = test_app();
+        let time = Rc::new(Cell::new(Local.with_ymd_and_hms(2024, 6, 10, 9, 0, 0).unwrap()));
+        app.clock = Box::new(FixedClock(time.clone()));
+        
+        app.toggle_timer();
+        time.set(time.get() + chrono::Duration::seconds(app.work_duration as i64));
+        app.tick(app.work_duration);
+        
+        let session = &app.recent_sessions[0];
+        assert_eq!(session.start_time, "2024-06-10 09:00:00");
+        assert_eq!(session.end_time, "2024-06-10 09:25:00");
+        assert_eq!(session.duration, 1500);
+    }
+
+    #[test]
+    fn test_breaks_get_a_fresh_suggestion() {
+        let mut app = test_app();
+        app.clock = Box::new(FixedClock(Rc::new(Cell::new(Local.with_ymd_and_hms(2024, 6, 10, 9, 0, 0).unwrap()))));
+        app.toggle_timer();
+        app.tick(app.work_duration);
+        assert_eq!(app.break_suggestion, None);
+        
+        app.change_setting(Setting::BreakSuggestions);
+        assert_eq!(app.set_text_setting(Setting::BreakSuggestionList, " Stretch;;Get water; Stretch ".to_string()), Ok(()));
+        app.save_settings();
+        assert_eq!(app.db.get_config("break_suggestion_list", ""), "Stretch; Get water");
+        assert!(app.set_text_setting(Setting::BreakSuggestionList, " ; ".to_string()).is_err());
+        
+        // The pinned clock always lands on the same pick, so the next one is moved along
+        for expected in ["Stretch", "Get water"] {
+            app.toggle_timer();
+            app.tick(app.break_duration);
+            app.toggle_timer();
+            app.tick(app.work_duration);
+            assert_eq!(app.break_suggestion.as_deref(), Some(expected));
+        }
+    }
+
+    #[test]
+    fn test_night_theme_follows_the_hour() {
+        let mut app = test_app();
+        let time = Rc::new(Cell::new(Local.with_ymd_and_hms(2024, 6, 10, 22, 0, 0).unwrap()));
+        app.clock = Box::new(FixedClock(time.clone()));
+        
+        app.change_setting(Setting::NightTheme);
+        assert_eq!(app.theme, Theme::NIGHT);
+        time.set(Local.with_ymd_and_hms(2024, 6, 11, 7, 0, 0).unwrap());
+        app.refresh_theme();
+        assert_eq!(app.theme, Theme::DEFAULT);
+        
+        // Hours that don't wrap past midnight, and bad input
+        assert_eq!(app.set_text_setting(Setting::NightHours, "6-8".to_string()), Ok(()));
+        assert_eq!(app.theme, Theme::NIGHT);
+        assert!(app.set_text_setting(Setting::NightHours, "6-24".to_string()).is_err());
+        app.save_settings();
+        assert_eq!(app.db.get_config("night_theme_end", "7"), "8");
+        
+        // Turning it off pins the color theme
+        for _ in 0..4 {
+            app.change_setting(Setting::NightTheme);
+        }
+        assert_eq!(app.night_theme, None);
+        assert_eq!(app.theme, Theme::DEFAULT);
+    }
+
+    #[test]
+    fn test_purge_needs_a_retention_window() {
+        let mut app = test_app();
+        let old = Local::now() - chrono::Duration::days(40);
+        app.db.save_session(&old, &(old + chrono::Duration::minutes(25)), 1500, &["Work"], "work").unwrap();
+        
+        app.request_purge();
+        assert_eq!(app.current_screen, Screen::Home);
+        assert!(app.status_message.is_some());
+        
+        assert_eq!(app.set_text_setting(Setting::Retention, "30".to_string()), Ok(()));
+        app.save_settings();
+        app.request_purge();
+        assert_eq!(app.current_screen, Screen::PurgeConfirm);
+        app.purge_old_sessions();
+        assert_eq!(app.db.get_all_sessions().unwrap().len(), 0);
+        assert_eq!(app.status_message.as_deref(), Some(format!("Deleted 1 sessions from before {}", app.purge_cutoff().unwrap()).as_str()));
+        
+        assert!(app.set_text_setting(Setting::Retention, "36501".to_string()).is_err());
+        assert!(app.set_text_setting(Setting::Retention, i64::MAX.to_string()).is_err());
+        assert!(app.db.retention_cutoff(i64::MAX).is_none());
+    }
+
+    #[test]
+    fn test_align_session_end_to_half_hour() {
+        let mut app = test_app();
+        let time = Rc::new(Cell::new(Local.with_ymd_and_hms(2024, 6, 10, 9, 7, 0).unwrap()));
+        app.clock = Box::new(FixedClock(time.clone()));
+        
+        // 25 minutes from 09:07 rounds to 09:30
+        app.align_session_end(false);
+        assert!(app.timer_running);
+        assert_eq!(app.remaining_seconds, 23 * 60);
+        assert_eq!(app.status_message.as_deref(), Some("Aligned: this session ends at 09:30"));
+        
+        // Counting the 5 minute break that follows, work ends at 09:25
+        app.align_session_end(true);
+        assert_eq!(app.remaining_seconds, 18 * 60);
+        
+        // The logged session is as long as it actually ran
+        time.set(time.get() + chrono::Duration::minutes(18));
+        app.tick(app.remaining_seconds);
+        assert_eq!(app.recent_sessions[0].duration, 18 * 60);
+        assert_eq!(app.remaining_seconds, app.break_duration);
+        
+        // A boundary too close is skipped for the next one
+        time.set(Local.with_ymd_and_hms(2024, 6, 10, 11, 58, 0).unwrap());
+        app.reset_timer();
+        app.remaining_seconds = 60;
+        app.align_session_end(false);
+        assert_eq!(app.remaining_seconds, 32 * 60);
+    }
+
+    #[test]
+    fn test_weekly_goal_counts_the_current_week() {
+        let mut app = test_app();
+        assert_eq!(app.weekly_goal_progress(), None);
+        assert!(app.set_text_setting(Setting::WeeklyGoal, "20000".to_string()).is_err());
+        assert_eq!(app.set_text_setting(Setting::WeeklyGoal, "600".to_string()), Ok(()));
+        
+        let now = Local::now();
+        let last_week = now - chrono::Duration::days(8);
+        for start in [now - chrono::Duration::minutes(30), last_week] {
+            app.db.save_session(&start, &(start + chrono::Duration::minutes(25)), 1500, &["Work"], "work").unwrap();
+        }
+        assert_eq!(app.weekly_goal_progress(), Some((25, 600)));
+        
+        // The week starts on the configured day
+        for week_start in [Weekday::Mon, Weekday::Sun] {
+            app.week_start = week_start;
+            assert_eq!(app.current_week_start().weekday(), week_start);
+            assert!((app.db.today() - app.current_week_start()).num_days() < 7);
+        }
+    }
+
+    #[test]
+    fn test_incognito_session_is_not_logged() {
+        let mut app = test_app();
+        app.toggle_incognito();
+        app.toggle_timer();
+        app.tick(app.work_duration);
+        assert_eq!(app.mode, PomodoroMode::Break);
+        assert!(app.recent_sessions.is_empty());
+        
+        // Only the one session was incognito
+        assert!(!app.incognito);
+        app.toggle_timer();
+        app.tick(app.break_duration);
+        assert_eq!(app.recent_sessions.len(), 1);
+        
+        // Manual entries are kept out too, and say so
+        app.toggle_incognito();
+        assert!(app.log_manual_session("25").is_err());
+        assert_eq!(app.recent_sessions.len(), 1);
+        
+        // Resetting throws the incognito session away along with the flag
+        app.toggle_timer();
+        app.reset_timer();
+        assert!(!app.incognito);
+    }
+
+    #[test]
+    fn test_abandoned_session_can_be_logged() {
+        let mut app = test_app();
+        app.toggle_timer();
+        app.tick(600);
+        app.save_timer_state();
+        
+        // The next start finds the state a crash left behind
+        let mut app = App::with_database(app.db).unwrap();
+        let state = app.abandoned_session.clone().unwrap();
+        assert_eq!((state.session_type.as_str(), state.elapsed_seconds), ("work", 600));
+        
+        app.log_abandoned_session();
+        assert_eq!(app.recent_sessions[0].duration, 600);
+        assert_eq!(Some(app.recent_sessions[0].tag.as_str()), app.selected_tag());
+        assert!(app.abandoned_session.is_none());
+    }
+
+    #[test]
+    fn test_quick_start_leaves_a_running_session_alone() {
+        let mut app = test_app();
+        app.add_tag("Reading").unwrap();
+        app.quick_start(1);
+        assert!(app.timer_running);
+        let tag = app.selected_tag().unwrap().to_string();
+        
+        app.quick_start(2);
+        assert_eq!(app.selected_tag(), Some(tag.as_str()));
+        assert!(app.status_message.is_some());
+        
+        // Paused still counts as in progress
+        app.toggle_timer();
+        app.quick_start(2);
+        assert_eq!(app.selected_tag(), Some(tag.as_str()));
+    }
+
+    #[test]
+    fn test_locked_break_blocks_quick_start() {
+        let mut app = test_app();
+        app.break_lock = 50;
+        app.toggle_timer();
+        app.tick(app.work_duration);
+        
+        app.quick_start(1);
+        assert_eq!(app.mode, PomodoroMode::Break);
+        assert_eq!(app.break_unlocks_in(), Some(app.break_duration / 2));
+        
+        app.toggle_timer();
+        app.tick(app.break_duration / 2);
+        assert_eq!(app.break_unlocks_in(), None);
+        app.toggle_timer();
+        app.reset_timer();
+        assert_eq!(app.break_unlocks_in(), Some(app.break_duration / 2));
+    }
+
+    #[test]
+    fn test_pause_holds_and_reset_discards() {
+        let mut app = test_app();
+        app.toggle_timer();
+        app.tick(60);
+        app.toggle_timer();
+        assert!(!app.timer_running);
+        app.tick(60);
+        assert_eq!(app.remaining_seconds, app.work_duration - 60);
+        
+        app.reset_timer();
+        assert_eq!(app.remaining_seconds, app.work_duration);
+        assert!(app.session_start.is_none());
+        assert!(app.recent_sessions.is_empty());
+    }
+
+    #[test]
+    fn test_overtime_counts_past_zero() {
+        let mut app = test_app();
+        app.overtime_mode = true;
+        app.toggle_timer();
+        app.tick(app.work_duration + 30);
+        assert!(app.in_overtime);
+        assert_eq!(app.overtime_seconds, 30);
+        app.tick(15);
+        assert_eq!(app.overtime_seconds, 45);
+        
+        // Ticks keep their interval in overtime
+        app.tick_interval = 10;
+        assert!(!app.tick_due());
+        app.tick(5);
+        assert!(app.tick_due());
+
+        // Stopping logs the session and moves on to the break
+        app.toggle_timer();
+        assert!(!app.in_overtime);
+        assert_eq!(app.mode, PomodoroMode::Break);
+    }
+
+    #[test]
+    fn test_short_sessions_are_not_logged() {
+        let mut app = test_app();
+        app.work_duration = (MIN_SESSION_SECONDS - 1) as u64;
+        app.reset_timer();
+        app.toggle_timer();
+        app.tick(app.work_duration);
+        assert_eq!(app.mode, PomodoroMode::Break);
+        assert_eq!(app.db.count_sessions(), 0);
+        assert_eq!(app.last_session_id, None);
+    }
+
+    #[test]
+    fn test_multi_tag_session_counts_once_in_totals() {
+        let mut app = test_app();
+        for tag in ["Study", "Work"] {
+            app.selected_tag_index = app.tags.iter().position(|t| t == tag).unwrap();
+            app.toggle_tag_mark();
+        }
+        app.toggle_timer();
+        app.tick(app.work_duration);
+
+        let total = |tag| app.db.get_yearly_stats(tag).unwrap().iter().map(|(_, s)| s).sum::<i64>();
+        let seconds = app.work_duration as i64;
+        assert_eq!(total(None), seconds);
+        assert_eq!(total(Some("Study")), seconds);
+        assert_eq!(total(Some("Work")), seconds);
+        assert_eq!(app.recent_sessions[0].tag, "Study + Work");
+    }
+
+    #[test]
+    fn test_undo_last_session() {
+        let mut app = test_app();
+        app.toggle_timer();
+        app.tick(app.work_duration);
+        assert_eq!(app.db.count_sessions(), 1);
+        
+        app.undo_last_session();
+        assert_eq!(app.db.count_sessions(), 0);
+        assert_eq!(app.mode, PomodoroMode::Work);
+        assert_eq!(app.remaining_seconds, app.work_duration);
+        
+        // Only the latest completion can be undone, and only once
+        app.undo_last_session();
+        assert_eq!(app.status_message.as_deref(), Some("Nothing to undo"));
+    }
+
+    #[test]
+    fn test_auto_pause_while_browsing() {
+        let mut app = test_app();
+        app.auto_pause = true;
+        app.toggle_timer();
+        
+        app.navigate_to(Screen::Stats);
+        assert!(!app.timer_running);
+        app.navigate_to(Screen::Heatmap);
+        assert!(!app.timer_running);
+        app.navigate_to(Screen::Home);
+        assert!(app.timer_running);
+        
+        // Popups opened from Stats are still browsing
+        app.navigate_to(Screen::Stats);
+        for popup in [Screen::BucketSessions, Screen::MergeTags, Screen::ExportDone] {
+            app.navigate_to(popup);
+            assert!(!app.timer_running);
+            app.navigate_to(Screen::Stats);
+            assert!(!app.timer_running);
+        }
+        app.navigate_to(Screen::Home);
+        assert!(app.timer_running);
+        
+        // A timer paused by hand stays paused
+        app.toggle_timer();
+        app.navigate_to(Screen::Stats);
+        app.navigate_to(Screen::Home);
+        assert!(!app.timer_running);
+    }
+
+    #[test]
+    fn test_sessions_needed() {
+        assert_eq!(sessions_needed(0, 100, 1500), 4);
+        assert_eq!(sessions_needed(3000, 100, 1500), 2);
+        assert_eq!(sessions_needed(6000, 100, 1500), 0);
+        assert_eq!(sessions_needed(7000, 100, 1500), 0);
+    }
+
+    #[test]
+    fn test_sessions_to_goal_follows_logged_work() {
+        let mut app = test_app();
+        app.daily_goal = 50;
+        assert_eq!(app.sessions_to_goal(), Some(2));
+        
+        app.toggle_timer();
+        app.tick(app.work_duration);
+        assert_eq!(app.sessions_to_goal(), Some(1));
+    }
+
+    #[test]
+    fn test_last_worked_kept_with_recent_sessions() {
+        let mut app = test_app();
+        let tag = app.selected_tag().unwrap().to_string();
+        assert!(!app.last_worked.contains_key(&tag));
+        
+        app.toggle_timer();
+        app.tick(app.work_duration);
+        assert!(app.last_worked.contains_key(&tag));
+    }
+
+    #[test]
+    fn test_milestones_fire_once() {
+        let mut app = test_app();
+        app.milestones = parse_milestones("120, 25,50,25").unwrap();
+        assert_eq!(app.milestones, [25, 50, 120]);
+        
+        app.toggle_timer();
+        app.tick(app.work_duration);
+        assert!(app.status_message.as_deref().unwrap().contains("25 minutes"));
+        assert_eq!(app.next_milestone(), Some((50, 25)));
+        
+        // A second announcement only comes with the next milestone
+        app.status_message = None;
+        app.announce_milestones();
+        assert_eq!(app.status_message, None);
+    }
+
+    #[test]
+    fn test_pomodoro_goal_announced_when_reached() {
+        let mut app = test_app();
+        app.daily_pomodoro_goal = 2;
+        
+        app.toggle_timer();
+        app.tick(app.work_duration);
+        assert_eq!(app.pomodoro_progress(), Some((1, 2)));
+        assert_eq!(app.status_message, None);
+        
+        app.log_manual_session("25").unwrap();
+        assert_eq!(app.pomodoro_progress(), Some((2, 2)));
+        assert_eq!(app.status_message.as_deref(), Some("🍅 Daily goal of 2 pomodoros reached!"));
+        
+        // Neither the following break nor undoing and re-logging announce it again
+        app.status_message = None;
+        app.toggle_timer();
+        app.tick(app.break_duration);
+        assert_eq!(app.status_message, None);
+        app.undo_last_session();
+        app.status_message = None;
+        app.log_manual_session("25").unwrap();
+        assert_eq!(app.status_message.as_deref(), Some("Logged 25 min"));
+    }
+
+    #[test]
+    fn test_intention_is_saved_as_note() {
+        let mut app = test_app();
+        app.intention_prompt = true;
+        app.toggle_timer();
+        assert_eq!(app.current_screen, Screen::IntentionInput);
+        assert!(!app.timer_running);
+        
+        app.input_buffer = " Write the report ".to_string();
+        app.start_with_intention();
+        assert!(app.timer_running);
+        app.tick(app.work_duration);
+        assert_eq!(app.recent_sessions[0].note.as_deref(), Some("Write the report"));
+    }
+
+    #[test]
+    fn test_note_prompt_waits_until_home() {
+        let mut app = test_app();
+        app.note_prompt = true;
+        app.toggle_timer();
+        app.navigate_to(Screen::Stats);
+        app.tick(app.work_duration);
+        app.open_queued_prompt();
+        assert_eq!(app.current_screen, Screen::Stats);
+        
+        app.navigate_to(Screen::Home);
+        app.open_queued_prompt();
+        assert_eq!(app.current_screen, Screen::NoteInput);
+        app.input_buffer = " Drafted the intro ".to_string();
+        app.save_note();
+        assert_eq!(app.recent_sessions[0].note.as_deref(), Some("Drafted the intro"));
+    }
+
+    #[test]
+    fn test_rating_prompt_precedes_note_prompt() {
+        let mut app = test_app();
+        app.rating_prompt = true;
+        app.note_prompt = true;
+        app.toggle_timer();
+        app.open_settings();
+        app.tick(app.work_duration);
+        assert_eq!(app.current_screen, Screen::Settings);
+        app.navigate_to(Screen::Home);
+        app.open_queued_prompt();
+        assert_eq!(app.current_screen, Screen::RatingInput);
+        
+        app.answer_rating(Some(4));
+        assert_eq!(app.current_screen, Screen::NoteInput);
+        let bucket = app.db.today().to_string();
+        assert_eq!(app.db.get_average_rating(&bucket, None), Some(4.0));
+        
+        // Skipping leaves the next session unrated
+        app.current_screen = Screen::Home;
+        app.note_prompt = false;
+        app.toggle_timer();
+        app.tick(app.break_duration);
+        app.toggle_timer();
+        app.tick(app.work_duration);
+        app.answer_rating(None);
+        assert_eq!(app.current_screen, Screen::Home);
+        assert_eq!(app.db.get_average_rating(&bucket, None), Some(4.0));
+        
+        // The chart reads the averages loaded on the way in
+        app.navigate_to(Screen::Stats);
+        assert!(app.stats_ratings.contains(&Some(4.0)));
+    }
+
+    #[test]
+    fn test_tag_of_running_session_cannot_be_deleted() {
+        let mut app = test_app();
+        app.toggle_timer();
+        app.request_tag_deletion();
+        assert_eq!(app.current_screen, Screen::Home);
+        assert!(app.status_message.is_some());
+        
+        // Tags outside the session's combination can still go
+        app.toggle_tag_mark();
+        app.next_tag();
+        app.request_tag_deletion();
+        assert_eq!(app.current_screen, Screen::DeleteConfirm);
+    }
+
+    #[test]
+    fn test_break_reminder_fires_once_per_stretch() {
+        let mut app = test_app();
+        app.break_reminder = 40;
+        assert_eq!(app.log_manual_session("30"), Ok(()));
+        app.status_message = None;
+        app.check_break_reminder();
+        assert_eq!(app.status_message, None);
+        
+        // Ten more minutes into a running session crosses the threshold
+        app.toggle_timer();
+        app.tick(600);
+        assert!(app.status_message.as_deref().unwrap().contains("40 min"));
+        app.status_message = None;
+        app.check_break_reminder();
+        assert_eq!(app.status_message, None);
+    }
+
+    #[test]
+    fn test_break_reminder_counts_time_actually_worked() {
+        let mut app = test_app();
+        app.break_reminder = 10;
+        
+        // A five-minute session four minutes in is four minutes of work
+        app.toggle_timer();
+        app.aligned_length = Some(300);
+        app.remaining_seconds = 300;
+        app.tick(240);
+        app.check_break_reminder();
+        assert_eq!(app.status_message, None);
+    }
+
+    #[test]
+    fn test_merge_stats_tag() {
+        let mut app = test_app();
+        app.add_tag("Read").unwrap();
+        app.selected_tag_index = app.tags.iter().position(|t| t == "Read").unwrap();
+        app.toggle_timer();
+        app.stats_tag_index = app.all_tags.iter().position(|t| t == "Read").unwrap() + 1;
+        
+        app.open_tag_merge();
+        assert_eq!(app.current_screen, Screen::Home);
+        app.tick(app.work_duration);
+        
+        app.open_tag_merge();
+        assert_eq!(app.current_screen, Screen::MergeTags);
+        assert_eq!(app.merge_targets, ["Study", "Work"]);
+        app.merge_selected_tag();
+        
+        assert_eq!(app.get_stats_tag(), Some("Study"));
+        assert!(!app.tags.contains(&"Read".to_string()));
+        assert_eq!(app.recent_sessions[0].tag, "Study");
+        assert_eq!(app.selected_tag(), Some("Study"));
+        assert_eq!(app.status_message.as_deref(), Some("Merged Read into Study (1 session)"));
+    }
+
+    #[test]
+    fn test_break_can_follow_work_tag() {
+        let mut app = test_app();
+        let work_tag = app.selected_tag().unwrap().to_string();
+        app.toggle_timer();
+        app.tick(app.work_duration);
+        
+        // Off by default: the break takes the tag selected when it ends
+        app.next_tag();
+        let other_tag = app.selected_tag().unwrap().to_string();
+        app.toggle_timer();
+        app.tick(app.break_duration);
+        assert_eq!(app.recent_sessions[0].tag, other_tag);
+        
+        app.change_setting(Setting::BreakTag);
+        app.toggle_timer();
+        app.tick(app.work_duration);
+        app.prev_tag();
+        assert_eq!(app.logged_tags(), [other_tag.as_str()]);
+        app.toggle_timer();
+        app.tick(app.break_duration);
+        assert_eq!(app.recent_sessions[0].tag, other_tag);
+        assert_eq!(app.selected_tag(), Some(work_tag.as_str()));
+    }
+
+    #[test]
+    fn test_selected_tag_is_restored_by_name() {
+        let mut app = test_app();
+        app.next_tag();
+        let tag = app.selected_tag().unwrap().to_string();
+        app.add_tag("Archery").unwrap();
+        
+        // "Archery" sorts first on reload, shifting every index
+        let app = App::with_database(app.db).unwrap();
+        assert_eq!(app.selected_tag(), Some(tag.as_str()));
+        
+        // A tag that's gone falls back to the default one
+        app.db.set_config("selected_tag", "Gone").unwrap();
+        let app = App::with_database(app.db).unwrap();
+        assert_eq!(app.selected_tag(), Some(DEFAULT_TAG));
+    }
+
+    #[test]
+    fn test_default_tag_is_created_and_used() {
+        let mut app = test_app();
+        app.settings_index = Setting::ALL.iter().position(|s| *s == Setting::DefaultTag).unwrap();
+        app.input_buffer = "  deep   work ".to_string();
+        app.apply_setting_input().unwrap();
+        
+        // Discarding leaves no tag behind
+        assert!(!app.tags.contains(&"deep work".to_string()));
+        app.discard_settings();
+        assert!(!app.db.get_all_tags().unwrap().contains(&"deep work".to_string()));
+        
+        app.input_buffer = "  deep   work ".to_string();
+        app.apply_setting_input().unwrap();
+        app.save_settings();
+        assert_eq!(app.default_tag, "deep work");
+        assert!(app.tags.contains(&"deep work".to_string()));
+        
+        // With no tags left, sessions fall back to it
+        app.tags.clear();
+        assert_eq!(app.session_tags(), ["deep work"]);
+        app.db.save_session(&Local::now(), &Local::now(), 1500, &[], "work").unwrap();
+        assert_eq!(app.db.get_recent_sessions(1).unwrap()[0].tag, "deep work");
+        
+        let app = App::with_database(app.db).unwrap();
+        assert_eq!(app.selected_tag(), Some("deep work"));
+    }
+
+    #[test]
+    fn test_tag_categories() {
+        assert_eq!(tag_category("Study/Math"), Some("Study"));
+        assert_eq!(tag_category("Study/Math/Algebra"), Some("Study"));
+        assert_eq!(tag_category("Study"), None);
+        assert_eq!(tag_category("/Math"), None);
+        
+        let tags = vec!["Study/Math".to_string(), "Work".to_string(), "Study/Physics".to_string()];
+        assert_eq!(with_categories(tags), vec!["Study", "Study/Math", "Study/Physics", "Work"]);
+    }
+
+    #[test]
+    fn test_weekly_targets_stay_per_tag_in_category_view() {
+        let mut app = test_app();
+        app.next_stats_tag();
+        let tag = app.get_stats_tag().unwrap().to_string();
+        app.adjust_stats_tag_target(30);
+        assert_eq!(app.db.get_tag_weekly_target(&tag), Some(30));
+        
+        app.toggle_stats_categories();
+        app.adjust_stats_tag_target(30);
+        assert_eq!(app.db.get_tag_weekly_target(&tag), Some(30));
+        assert!(app.status_message.is_some());
+    }
+
+    #[test]
+    fn test_autosave_skips_plain_countdown() {
+        let mut app = test_app();
+        app.toggle_timer();
+        app.autosave_timer_state();
+        assert_eq!(app.db.load_timer_state().unwrap().remaining_seconds, 1500);
+        
+        // Counting down alone waits for the interval...
+        app.tick(5);
+        app.autosave_timer_state();
+        assert_eq!(app.db.load_timer_state().unwrap().remaining_seconds, 1500);
+        
+        // ...while pausing is saved straight away
+        app.toggle_timer();
+        app.autosave_timer_state();
+        let state = app.db.load_timer_state().unwrap();
+        assert!(!state.running);
+        assert_eq!(state.remaining_seconds, 1495);
+    }
+
+    #[test]
+    fn test_sound_falls_back_to_next_player() {
+        let path = std::path::Path::new("/dev/null");
+        let players = |names: &[&str]| names.iter().map(|name| name.to_string()).collect::<Vec<_>>();
+        
+        assert!(play_with_fallback(&players(&["no-such-player-pomodoro", "true"]), path));
+        assert!(!play_with_fallback(&players(&["no-such-player-pomodoro", "false"]), path));
+        assert!(!play_with_fallback(&[], path));
+    }
+
+    #[test]
+    fn test_notify_send_args() {
+        assert_eq!(notify_send_args(Urgency::Critical, 0, "Pomodoro++", "Break is over!"),
+            ["-u", "critical", "Pomodoro++", "Break is over!"]);
+        assert_eq!(notify_send_args(Urgency::Low, 5, "Pomodoro++", "Done"),
+            ["-u", "low", "-t", "5000", "Pomodoro++", "Done"]);
+    }
+
+    #[test]
+    fn test_long_break_cycle_counts_from_database() {
+        let mut app = test_app();
+        app.long_break_every = 2;
+        let finish = |app: &mut App| {
+            app.toggle_timer();
+            app.tick(app.remaining_seconds);
+        };
+        
+        finish(&mut app);
+        assert!(app.mode == PomodoroMode::Break && !app.long_break);
+        finish(&mut app);
+        finish(&mut app);
+        assert!(app.long_break);
+        assert_eq!(app.remaining_seconds, app.long_break_duration);
+        
+        // A restart derives the same state from the logged sessions
+        app.long_break = false;
+        app.reset_timer();
+        assert!(app.long_break);
+        
+        finish(&mut app);
+        assert_eq!(app.db.count_work_since_long_break(None), 0);
+        finish(&mut app);
+        assert!(app.mode == PomodoroMode::Break && !app.long_break);
+    }
+
+    #[test]
+    fn test_long_breaks_start_off_and_count_from_turning_on() {
+        let mut app = test_app();
+        assert_eq!(app.long_break_every, 0);
+        let earlier = Local::now() - chrono::Duration::hours(2);
+        for _ in 0..3 {
+            let _ = app.db.save_session(&earlier, &(earlier + chrono::Duration::minutes(25)), 1500, &["Work"], "work");
+        }
+        
+        assert_eq!(app.set_text_setting(Setting::LongBreakEvery, "2".to_string()), Ok(()));
+        app.save_settings();
+        assert_eq!(app.cycle_position(), Some((1, 2)));
+    }
+
+    #[test]
+    fn test_daily_cycle_reset_ignores_earlier_days() {
+        let mut app = test_app();
+        app.long_break_every = 2;
+        let yesterday = Local::now() - chrono::Duration::days(1);
+        let _ = app.db.save_session(&yesterday, &(yesterday + chrono::Duration::minutes(25)), 1500, &["Work"], "work");
+        app.refresh_today_summary();
+        assert_eq!(app.cycle_position(), Some((2, 2)));
+        
+        app.cycle_reset = CycleReset::Daily;
+        app.refresh_today_summary();
+        assert_eq!(app.cycle_position(), Some((1, 2)));
+        app.toggle_timer();
+        app.tick(app.work_duration);
+        assert!(!app.long_break);
+    }
+
+    #[test]
+    fn test_focus_music_follows_running_work() {
+        let mut app = test_app();
+        app.silent = false;
+        app.focus_music = true;
+        
+        app.toggle_timer();
+        assert!(app.music_playing);
+        app.toggle_timer();
+        assert!(!app.music_playing);
+        app.toggle_timer();
+        app.tick(app.remaining_seconds);
+        assert_eq!(app.mode, PomodoroMode::Break);
+        assert!(!app.music_playing);
+        
+        // Breaks stay quiet
+        app.toggle_timer();
+        assert!(!app.music_playing);
+    }
+
+    #[test]
+    fn test_auto_pause_stops_focus_music() {
+        let mut app = test_app();
+        app.silent = false;
+        app.focus_music = true;
+        app.auto_pause = true;
+        
+        app.toggle_timer();
+        assert!(app.music_playing);
+        app.navigate_to(Screen::Stats);
+        assert!(!app.music_playing);
+        app.navigate_to(Screen::Home);
+        assert!(app.music_playing);
+    }
+
+    #[test]
+    fn test_discard_restores_saved_settings() {
+        let mut app = test_app();
+        app.open_settings();
+        app.change_setting(Setting::Theme);
+        app.change_setting(Setting::NotePrompt);
+        app.input_buffer = "90".to_string();
+        app.settings_index = Setting::ALL.iter().position(|s| *s == Setting::DailyGoal).unwrap();
+        assert_eq!(app.apply_setting_input(), Ok(()));
+        assert!(app.setting_modified(Setting::Theme));
+        assert!(app.setting_modified(Setting::DailyGoal));
+        assert_eq!(app.db.get_config("note_prompt", "false"), "false");
+        assert_eq!(app.db.get_config("daily_goal", "0"), "0");
+        
+        app.leave_settings();
+        assert_eq!(app.current_screen, Screen::Settings);
+        
+        app.discard_settings();
+        assert!(!app.has_unsaved_settings());
+        assert_eq!(app.theme.name, Theme::DEFAULT.name);
+        assert_eq!(app.daily_goal, 0);
+        assert!(!app.note_prompt);
+        
+        // Saved values become the new baseline
+        app.change_setting(Setting::Overtime);
+        app.save_settings();
+        assert!(!app.has_unsaved_settings());
+        app.leave_settings();
+        assert_eq!(app.current_screen, Screen::Home);
+        assert!(app.overtime_mode);
+        assert_eq!(app.db.get_config("overtime_mode", "false"), "true");
+    }
+
+    #[test]
+    fn test_heatmap_metric_swaps_data_source() {
+        let mut app = test_app();
+        let start = Local::now() - chrono::Duration::hours(1);
+        for _ in 0..2 {
+            let _ = app.db.save_session(&start, &(start + chrono::Duration::minutes(25)), 1500, &["Work"], "work");
+        }
+        let value = |app: &App| app.heatmap_window_data().iter().map(|(_, value)| *value).sum::<i64>();
+        assert_eq!(value(&app), 50);
+        
+        app.toggle_heat_metric();
+        assert_eq!(app.heat_metric(), HeatMetric::Sessions);
+        assert_eq!(value(&app), 2);
+        app.toggle_heat_scale();
+        assert_eq!(app.heat_scale(), HeatScale::Absolute(SESSION_THRESHOLDS));
+        assert_eq!(app.db.get_config("heatmap_sessions", "false"), "true");
+    }
+
+    #[test]
+    fn test_heatmap_scrolls_back_to_first_work() {
+        let mut app = test_app();
+        app.scroll_heatmap_back();
+        assert_eq!(app.heatmap_offset, 0);
+        
+        let start = Local::now() - chrono::Duration::days(HEATMAP_DAYS + 45);
+        let _ = app.db.save_session(&start, &(start + chrono::Duration::minutes(25)), 1500, &["Work"], "work");
+        for _ in 0..5 {
+            app.scroll_heatmap_back();
+        }
+        assert!((1..=2).contains(&app.heatmap_offset));
+        assert!(app.heatmap_window_data().iter().any(|(day, _)| *day == start.date_naive()));
+        
+        app.scroll_heatmap_forward();
+        app.heatmap_to_today();
+        assert_eq!(app.heatmap_offset, 0);
+        app.scroll_heatmap_forward();
+        assert_eq!(app.heatmap_offset, 0);
+    }
+
+    #[test]
+    fn test_parse_manual_entry() {
+        let now = chrono::NaiveDate::from_ymd_opt(2024, 6, 10).unwrap().and_hms_opt(15, 0, 0).unwrap();
+        let at = |h, m| chrono::NaiveDate::from_ymd_opt(2024, 6, 10).unwrap().and_hms_opt(h, m, 0).unwrap();
+        assert_eq!(parse_manual_entry("45", now), Ok((45, at(14, 15))));
+        assert_eq!(parse_manual_entry("30 9:00", now), Ok((30, at(9, 0))));
+        assert_eq!(parse_manual_entry(" 25  2024-06-10 13:00 ", now), Ok((25, at(13, 0))));
+        assert!(parse_manual_entry("60 14:30", now).is_err()); // ends in the future
+        assert!(parse_manual_entry("0", now).is_err());
+        assert!(parse_manual_entry("45 tomorrow", now).is_err());
+    }
+
+    #[test]
+    fn test_parse_thresholds() {
+        assert_eq!(parse_thresholds("15, 30,60"), Ok([15, 30, 60]));
+        assert!(parse_thresholds("30,15,60").is_err());
+        assert!(parse_thresholds("15,30").is_err());
+        assert!(parse_thresholds("a,b,c").is_err());
+    }
+
+    #[test]
+    fn test_tag_selection_wraps_at_list_ends() {
+        let mut app = test_app();
+        let last = app.tags.len() - 1;
+        app.selected_tag_index = 0;
+        app.prev_tag();
+        assert_eq!(app.selected_tag_index, last);
+        app.next_tag();
+        assert_eq!(app.selected_tag_index, 0);
+        app.next_tag();
+        assert_eq!(app.selected_tag_index, 1);
+    }
+
+    #[test]
+    fn test_focus_lock_blocks_quit_during_work() {
+        let mut app = test_app();
+        app.focus_lock = true;
+        app.toggle_timer();
+        app.request_quit();
+        assert!(!app.should_quit);
+        assert!(app.status_message.is_some());
+        
+        // Pausing doesn't release the lock, but `Q` gets past it
+        app.toggle_timer();
+        app.request_quit();
+        assert!(!app.should_quit);
+        app.force_quit();
+        assert!(app.should_quit);
+        
+        // Resetting does
+        app.should_quit = false;
+        app.reset_timer();
+        app.request_quit();
+        assert!(app.should_quit);
+    }
+
+    #[test]
+    fn test_reset_confirms_long_sessions() {
+        let mut app = test_app();
+        app.toggle_timer();
+        app.tick(60);
+        app.request_reset();
+        assert_eq!(app.current_screen, Screen::Home);
+        assert!(app.session_start.is_none());
+        
+        app.toggle_timer();
+        app.tick(RESET_CONFIRM_SECONDS);
+        app.request_reset();
+        assert_eq!(app.current_screen, Screen::ResetConfirm);
+        assert!(app.session_start.is_some());
+    }
+
+    #[test]
+    fn test_skip_confirmations_acts_at_once() {
+        let mut app = test_app();
+        app.change_setting(Setting::SkipConfirmations);
+        app.toggle_timer();
+        app.tick(RESET_CONFIRM_SECONDS);
+        app.request_reset();
+        assert_eq!(app.current_screen, Screen::Home);
+        assert!(app.session_start.is_none());
+        
+        app.add_tag("Scratch").unwrap();
+        app.selected_tag_index = app.tags.iter().position(|tag| tag == "Scratch").unwrap();
+        app.request_tag_deletion();
+        assert_eq!(app.current_screen, Screen::Home);
+        assert!(!app.tags.contains(&"Scratch".to_string()));
+    }
+
+    #[test]
+    fn test_ascii_mode_toggles_and_persists() {
+        let mut app = test_app();
+        assert_eq!(app.glyphs().pomodoro, "🍅");
+        app.toggle_ascii();
+        assert_eq!(app.glyphs().pomodoro, "(o)");
+        assert!(app.glyphs().heat.iter().all(|glyph| glyph.is_ascii()));
+        assert_eq!(app.db.get_config("ascii_mode", "auto"), "true");
+    }
+
+    #[test]
+    fn test_adjust_duration_reports_limits() {
+        let mut app = test_app();
+        app.set_work_duration(120);
+        assert!(app.adjust_work_duration(-60));
+        assert!(app.limit_hint().is_none());
+        assert!(!app.adjust_work_duration(-60));
+        assert_eq!(app.work_duration, 60);
+        assert_eq!(app.limit_hint(), Some("work minimum 1 min"));
+        
+        app.set_break_duration(app.max_break_duration as i64);
+        assert!(!app.adjust_break_duration(60));
+        assert_eq!(app.limit_hint(), Some("break maximum 60 min (raise it in Settings)"));
+    }
+
+    #[test]
+    fn test_templates_set_tag_and_durations() {
+        let mut app = test_app();
+        assert!(app.create_template("  ").is_err());
+        app.add_tag("Math").unwrap();
+        app.selected_tag_index = app.tags.iter().position(|t| t == "Math").unwrap();
+        app.set_work_duration(50 * 60);
+        app.set_break_duration(10 * 60);
+        assert_eq!(app.create_template("Deep Math"), Ok(()));
+        
+        app.selected_tag_index = 0;
+        app.set_work_duration(25 * 60);
+        app.set_break_duration(5 * 60);
+        app.template_index = 0;
+        app.apply_selected_template(true);
+        assert_eq!(app.selected_tag(), Some("Math"));
+        assert_eq!((app.work_duration, app.break_duration), (50 * 60, 10 * 60));
+        assert!(app.timer_running);
+        assert_eq!(app.db.get_templates().unwrap()[0].tag, "Math");
+        
+        app.delete_selected_template();
+        assert!(app.templates.is_empty());
+        assert!(app.db.get_templates().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_parse_duration_preset() {
+        assert_eq!(parse_duration_preset("50/10"), Ok((50, 10)));
+        assert_eq!(parse_duration_preset(" 25 / 5 "), Ok((25, 5)));
+        assert!(parse_duration_preset("50").is_err());
+        assert!(parse_duration_preset("fifty/10").is_err());
+
+        // Values outside the limits are clamped when applied
+        let mut app = test_app();
+        assert_eq!(app.apply_duration_preset("500/0"), Ok(()));
+        assert_eq!(app.work_duration, 7200);
+        assert_eq!(app.break_duration, 60);
+        
+        // ...and the limits can be raised
+        app.max_work_duration = 180 * 60;
+        assert_eq!(app.apply_duration_preset("150/5"), Ok(()));
+        assert_eq!(app.work_duration, 150 * 60);
+    }
+
+    #[test]
+    fn test_add_tag_normalizes_and_rejects_duplicates() {
+        let mut app = test_app();
+        assert_eq!(app.add_tag("  Deep \n  Work  "), Ok(()));
+        assert!(app.tags.contains(&"Deep Work".to_string()));
+        assert!(app.add_tag("deep work").is_err());
+        assert!(app.add_tag("work").is_err()); // default "Work" tag
+        assert!(app.add_tag("   ").is_err());
+        assert!(app.add_tag(&"x".repeat(MAX_TAG_LENGTH + 1)).is_err());
+    }
 }
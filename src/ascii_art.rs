@@ -1,6 +1,5 @@
 /// ASCII art digit representations for the timer display
 /// Each digit is represented as a 5-line array of strings
-
 const DIGIT_0: [&str; 5] = [
     " ███ ",
     "█   █",
@@ -0,0 +1,55 @@
+use std::path::PathBuf;
+
+use crate::db::Database;
+
+/// Two-note chime (E5 then A5) bundled into the binary
+pub const CHIME_WAV: &[u8] = include_bytes!("../assets/chime.wav");
+
+/// Play the bundled chime in-process, blocking until it finishes.
+/// Returns false when there is no audio device or the app was built
+/// without the `rodio` feature.
+#[cfg(feature = "rodio")]
+pub fn play_bundled_chime() -> bool {
+    use rodio::{Decoder, OutputStream, Sink};
+
+    let Ok((_stream, handle)) = OutputStream::try_default() else {
+        return false;
+    };
+    let Ok(sink) = Sink::try_new(&handle) else {
+        return false;
+    };
+    let Ok(source) = Decoder::new(std::io::Cursor::new(CHIME_WAV)) else {
+        return false;
+    };
+    sink.append(source);
+    sink.sleep_until_end();
+    true
+}
+
+#[cfg(not(feature = "rodio"))]
+pub fn play_bundled_chime() -> bool {
+    false
+}
+
+/// Path of the bundled chime in the data directory for external players,
+/// written on first use
+pub fn default_chime_path() -> Option<PathBuf> {
+    let path = Database::data_dir().join("chime.wav");
+    if !path.exists() {
+        std::fs::write(&path, CHIME_WAV).ok()?;
+    }
+    Some(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_chime_wav_header() {
+        assert_eq!(&CHIME_WAV[0..4], b"RIFF");
+        assert_eq!(&CHIME_WAV[8..16], b"WAVEfmt ");
+        let data_len = u32::from_le_bytes(CHIME_WAV[40..44].try_into().unwrap()) as usize;
+        assert_eq!(CHIME_WAV.len(), 44 + data_len);
+    }
+}
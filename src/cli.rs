@@ -0,0 +1,198 @@
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use crate::app::{App, PomodoroMode};
+use crate::db::{Database, TimerState};
+use crate::export::{current_streak, weekly_digest, ExportFormat, RestDays};
+
+/// Runs a command-line subcommand instead of the TUI
+pub fn run(command: &str, args: &[String]) -> Result<(), Box<dyn std::error::Error>> {
+    match command {
+        "status" => {
+            let db = Database::new()?;
+            let now = chrono::Local::now().timestamp();
+            println!("{}", status_line(db.load_timer_state().as_ref(), now));
+            Ok(())
+        }
+        "digest" => {
+            let db = Database::new()?;
+            let to = db.today();
+            let from = to - chrono::Duration::days(6);
+            
+            let daily = db.get_daily_totals(from, to)?;
+            let tags = db.get_tag_totals(from, to)?;
+            let notes = db.get_noted_sessions(from, to)?;
+            let worked_days: Vec<_> = db.get_heatmap_data()?.into_iter().map(|(day, _)| day).collect();
+            let goal: u64 = db.get_config("daily_goal", "0").parse().unwrap_or(0);
+            
+            let digest = weekly_digest(from, to, &daily, &tags, &notes, current_streak(&worked_days, to, &RestDays::load(&db)), goal);
+            let path = Database::data_dir().join(format!("digest-{}_{}.md", from, to));
+            std::fs::write(&path, digest)?;
+            println!("{}", path.display());
+            Ok(())
+        }
+        "export-csv" => {
+            let db = Database::new()?;
+            let path = match args.first() {
+                Some(path) => PathBuf::from(path),
+                None => Database::data_dir().join("sessions.csv"),
+            };
+            let count = db.export(ExportFormat::Csv, &path)?;
+            println!("Exported {} sessions to {}", count, path.display());
+            Ok(())
+        }
+        "import-csv" => {
+            let Some(path) = args.first() else {
+                eprintln!("Usage: pomodoro-pp import-csv <file>");
+                std::process::exit(2);
+            };
+            let db = Database::new()?;
+            let count = db.import_sessions_csv(Path::new(path))?;
+            println!("Imported {} sessions", count);
+            Ok(())
+        }
+        "purge" => {
+            let db = Database::new()?;
+            let yes = args.iter().any(|arg| arg == "--yes" || arg == "-y");
+            // Days from the command line, else the retention window set in the app
+            let days = match args.iter().find(|arg| !arg.starts_with('-')) {
+                Some(days) => days.parse::<i64>().ok().filter(|days| *days > 0),
+                None => Some(db.retention_days()).filter(|days| *days > 0),
+            };
+            let Some(days) = days else {
+                eprintln!("Usage: pomodoro-pp purge <days> [--yes]");
+                std::process::exit(2);
+            };
+            
+            let Some(cutoff) = db.retention_cutoff(days) else {
+                eprintln!("Cannot purge: {} days reaches too far back", days);
+                std::process::exit(2);
+            };
+            let count = db.count_sessions_before(cutoff);
+            if count == 0 {
+                println!("No sessions from before {}", cutoff);
+                return Ok(());
+            }
+            if !yes {
+                print!("Delete {} sessions from before {}? This can't be undone. [y/N] ", count, cutoff);
+                std::io::stdout().flush()?;
+                let mut answer = String::new();
+                std::io::stdin().read_line(&mut answer)?;
+                if !answer.trim().eq_ignore_ascii_case("y") {
+                    println!("Nothing deleted");
+                    return Ok(());
+                }
+            }
+            let deleted = db.purge_sessions_before(cutoff)?;
+            db.vacuum()?;
+            println!("Deleted {} sessions", deleted);
+            Ok(())
+        }
+        "--selftest" | "selftest" => {
+            // A throwaway database, so the user's own sessions are never touched
+            let path = std::env::temp_dir().join(format!("pomodoro++-selftest-{}.db", std::process::id()));
+            let result = selftest(&path);
+            let _ = std::fs::remove_file(&path);
+            result?;
+            println!("Selftest passed");
+            Ok(())
+        }
+        _ => {
+            eprintln!("Unknown command: {}", command);
+            eprintln!("Usage: pomodoro-pp [status|digest|export-csv [file]|import-csv <file>|purge [days] [--yes]|--selftest]");
+            std::process::exit(2);
+        }
+    }
+}
+
+/// Seeds a fresh database at `path` and runs the timer through a full cycle,
+/// printing each check, so the binary can be verified without a terminal
+fn selftest(path: &Path) -> Result<(), Box<dyn std::error::Error>> {
+    let db = Database::open(path)?;
+    let end = chrono::Local::now();
+    let start = end - chrono::Duration::minutes(25);
+    db.add_tag("Selftest")?;
+    db.save_session(&start, &end, 1500, &["Selftest"], "work")?;
+    db.save_session(&end, &(end + chrono::Duration::minutes(5)), 300, &["Selftest"], "break")?;
+    
+    let today = db.today();
+    let yesterday = today - chrono::Duration::days(1);
+    check("seeded sessions are totalled", db.get_work_break_totals(None, None)? == (1500, 300))?;
+    check("seeded work shows in the daily totals", db.get_daily_totals(yesterday, today)?.iter().map(|(_, s)| s).sum::<i64>() == 1500)?;
+    check("seeded work shows in the tag totals", db.get_tag_totals(yesterday, today)? == vec![("Selftest".to_string(), 1500)])?;
+    
+    let mut app = App::with_database(db)?;
+    app.sound_enabled = false;
+    app.silent = true;
+    app.toggle_timer();
+    check("the timer starts", app.timer_running)?;
+    app.tick(app.work_duration);
+    check("a finished work session moves on to a break", app.mode == PomodoroMode::Break)?;
+    app.toggle_timer();
+    app.tick(app.break_duration);
+    check("a finished break moves back to work", app.mode == PomodoroMode::Work)?;
+    let logged = (1500 + app.work_duration as i64, 300 + app.break_duration as i64);
+    check("completed sessions are saved", app.db.get_work_break_totals(None, None)? == logged)?;
+    Ok(())
+}
+
+fn check(name: &str, passed: bool) -> Result<(), Box<dyn std::error::Error>> {
+    if !passed {
+        return Err(format!("selftest failed: {}", name).into());
+    }
+    println!("ok  {}", name);
+    Ok(())
+}
+
+/// One-line timer summary for status bars, e.g. `WORK 12:34 ▶`.
+/// Prints `IDLE` when no session is in progress.
+fn status_line(state: Option<&TimerState>, now: i64) -> String {
+    let state = match state {
+        Some(state) => state,
+        None => return "IDLE".to_string(),
+    };
+    
+    // A running timer has kept counting down since the snapshot was taken
+    let remaining = if state.running {
+        let since_save = (now - state.saved_at).max(0) as u64;
+        state.remaining_seconds.saturating_sub(since_save)
+    } else {
+        state.remaining_seconds
+    };
+    
+    format!(
+        "{} {:02}:{:02} {}",
+        state.session_type.to_uppercase(),
+        remaining / 60,
+        remaining % 60,
+        if state.running { "▶" } else { "⏸" }
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_status_line() {
+        let state = TimerState {
+            session_type: "work".to_string(),
+            running: true,
+            remaining_seconds: 754,
+            saved_at: 1000,
+            started_at: 250,
+            elapsed_seconds: 746,
+            tags: vec!["Study".to_string()],
+        };
+        assert_eq!(status_line(Some(&state), 1004), "WORK 12:30 ▶");
+        assert_eq!(status_line(None, 1004), "IDLE");
+    }
+
+    #[test]
+    fn test_selftest_passes() {
+        let path = std::env::temp_dir().join(format!("pomodoro++-selftest-test-{}.db", std::process::id()));
+        let result = selftest(&path);
+        let _ = std::fs::remove_file(&path);
+        assert!(result.is_ok());
+    }
+}
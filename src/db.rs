@@ -1,12 +1,30 @@
-use chrono::{DateTime, Local, NaiveDate};
-use rusqlite::{Connection, Result, params};
-use std::path::PathBuf;
+use chrono::{DateTime, Local, NaiveDate, NaiveDateTime, Timelike};
+use rusqlite::{Connection, DatabaseName, Result, params};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use crate::export::{parse_sessions_csv, ExportFormat};
+
+/// Tag for sessions logged without one, unless `default_tag` is configured
+pub const DEFAULT_TAG: &str = "Work";
+
+/// Longest retention window, in days (about a hundred years)
+pub const MAX_RETENTION_DAYS: i64 = 36_500;
+
+/// How session timestamps are stored (local time)
+const TIMESTAMP_FORMAT: &str = "%Y-%m-%d %H:%M:%S";
+
+/// Session columns, with every tag of a session joined as "Study + Math"
+const SESSION_COLUMNS: &str = "id, start_time, end_time, duration,
+    COALESCE((SELECT GROUP_CONCAT(tag, ' + ') FROM session_tags WHERE session_id = sessions.id), tag),
+    type, note";
 
 pub struct Database {
     conn: Connection,
+    rollover_hour: u32, // Hour at which a new day starts, for night owls
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct Session {
     pub id: i64,
     pub start_time: String,
@@ -14,28 +32,169 @@ pub struct Session {
     pub duration: i64,
     pub tag: String,
     pub session_type: String,
+    pub note: Option<String>,
+}
+
+/// Back-to-back work sessions under one tag, reported together
+#[derive(Debug, Clone, PartialEq)]
+pub struct SessionBlock {
+    pub tag: String,
+    pub start_time: String, // Start of the first session
+    pub end_time: String,   // End of the last session
+    pub duration: i64,      // Work seconds, breaks in between left out
+    pub sessions: usize,
+}
+
+/// Table or view the by-tag stats join sessions to: `category_tags` maps
+/// each tag to its category ("Study" for "Study/Math")
+fn tag_source(by_category: bool) -> &'static str {
+    if by_category { "category_tags" } else { "session_tags" }
+}
+
+/// Coalesces work sessions into blocks, newest first: a session joins the block
+/// before it when it has the same tags and starts at most `max_gap_minutes`
+/// after that block ends. Breaks are skipped, so a short break doesn't split a
+/// block. `sessions` may be in any order.
+pub fn group_into_blocks(sessions: &[Session], max_gap_minutes: i64) -> Vec<SessionBlock> {
+    let parse = |timestamp: &str| NaiveDateTime::parse_from_str(timestamp, TIMESTAMP_FORMAT).ok();
+    let mut work: Vec<&Session> = sessions.iter().filter(|session| session.session_type == "work").collect();
+    work.sort_by(|a, b| (&a.start_time, a.id).cmp(&(&b.start_time, b.id)));
+    
+    let mut blocks: Vec<SessionBlock> = Vec::new();
+    for session in work {
+        let joins = blocks.last().is_some_and(|block| {
+            block.tag == session.tag
+                && match (parse(&block.end_time), parse(&session.start_time)) {
+                    (Some(end), Some(start)) => (start - end).num_minutes() <= max_gap_minutes,
+                    _ => false,
+                }
+        });
+        match blocks.last_mut() {
+            Some(block) if joins => {
+                block.end_time = block.end_time.clone().max(session.end_time.clone());
+                block.duration += session.duration;
+                block.sessions += 1;
+            }
+            _ => blocks.push(SessionBlock {
+                tag: session.tag.clone(),
+                start_time: session.start_time.clone(),
+                end_time: session.end_time.clone(),
+                duration: session.duration,
+                sessions: 1,
+            }),
+        }
+    }
+    blocks.reverse();
+    blocks
+}
+
+/// A saved combination of tag and durations, started from the templates popup
+#[derive(Debug, Clone, PartialEq)]
+pub struct Template {
+    pub name: String,
+    pub tag: String,
+    pub work_minutes: u64,
+    pub break_minutes: u64,
+}
+
+/// Timer state published by the running TUI for other processes to read
+#[derive(Debug, Clone, PartialEq)]
+pub struct TimerState {
+    pub session_type: String,  // "work" or "break"
+    pub running: bool,
+    pub remaining_seconds: u64,
+    pub saved_at: i64,         // Unix timestamp of the snapshot
+    pub started_at: i64,       // Unix timestamp the session started
+    pub elapsed_seconds: u64,  // Time counted so far, overtime included
+    pub tags: Vec<String>,
 }
 
 impl Database {
     pub fn new() -> Result<Self> {
-        let db_path = Self::get_db_path();
-        
+        Self::open(&Self::get_db_path())
+    }
+    
+    /// Opens (or creates) the database file at `db_path`
+    pub fn open(db_path: &Path) -> Result<Self> {
         // Ensure parent directory exists
         if let Some(parent) = db_path.parent() {
             std::fs::create_dir_all(parent).ok();
         }
         
-        let conn = Connection::open(&db_path)?;
-        let db = Database { conn };
+        Self::from_connection(Connection::open(db_path)?)
+    }
+    
+    #[cfg(test)]
+    pub fn open_in_memory() -> Result<Self> {
+        Self::from_connection(Connection::open_in_memory()?)
+    }
+    
+    fn from_connection(conn: Connection) -> Result<Self> {
+        let mut db = Database { conn, rollover_hour: 0 };
         db.initialize_schema()?;
+        db.rollover_hour = db.get_config("day_rollover", "0").parse().unwrap_or(0).min(23);
         Ok(db)
     }
     
-    fn get_db_path() -> PathBuf {
+    pub fn rollover_hour(&self) -> u32 {
+        self.rollover_hour
+    }
+    
+    pub fn set_rollover_hour(&mut self, hour: u32) -> Result<()> {
+        self.rollover_hour = hour;
+        self.set_config("day_rollover", &hour.to_string())
+    }
+    
+    /// The current day, which only starts at the rollover hour
+    pub fn today(&self) -> NaiveDate {
+        (Local::now() - chrono::Duration::hours(self.rollover_hour as i64)).date_naive()
+    }
+    
+    /// SQLite modifier moving timestamps back by the rollover hour, so that
+    /// `DATE(start_time, <shift>)` gives the day a session counts towards
+    fn day_shift(&self) -> String {
+        format!("-{} hours", self.rollover_hour)
+    }
+    
+    /// `WITH` clause defining `day_sessions`: the sessions table with each
+    /// session that runs past the end of its day split in two, its duration
+    /// shared in proportion to the clock time on either side. The second part
+    /// starts at the new day and has `part` 1, so counts can skip it. Anything
+    /// past a second midnight stays with the day the session ended.
+    fn with_day_sessions(&self) -> String {
+        let shift = self.day_shift();
+        let crosses = format!("DATE(end_time, '{0}') > DATE(start_time, '{0}') AND end_time > start_time", shift);
+        format!(
+            "WITH spans AS (
+                SELECT *, {crosses} AS crosses,
+                    CAST(ROUND(duration * MIN(1.0,
+                        (JULIANDAY(DATE(start_time, '{shift}', '+1 day')) - JULIANDAY(start_time, '{shift}'))
+                        / MAX(JULIANDAY(end_time) - JULIANDAY(start_time), 1e-9)
+                    )) AS INTEGER) AS first_day
+                FROM sessions
+            ),
+            day_sessions AS (
+                SELECT id, type, start_time, CASE WHEN crosses THEN first_day ELSE duration END AS duration, 0 AS part
+                FROM spans
+                UNION ALL
+                SELECT id, type, DATETIME(DATE(end_time, '{shift}'), '+{hours} hours'), duration - first_day, 1
+                FROM spans WHERE crosses
+            )",
+            crosses = crosses,
+            shift = shift,
+            hours = self.rollover_hour,
+        )
+    }
+    
+    /// Directory holding the database and any exported files
+    pub fn data_dir() -> PathBuf {
         dirs::data_local_dir()
             .unwrap_or_else(|| PathBuf::from("."))
             .join("pomodoro++")
-            .join("pomodoro.db")
+    }
+    
+    fn get_db_path() -> PathBuf {
+        Self::data_dir().join("pomodoro.db")
     }
     
     fn initialize_schema(&self) -> Result<()> {
@@ -66,6 +225,62 @@ impl Database {
             [],
         )?;
         
+        // Every tag of a session; `sessions.tag` holds only the first (primary) one
+        self.conn.execute(
+            "CREATE TABLE IF NOT EXISTS session_tags (
+                session_id INTEGER NOT NULL,
+                tag TEXT NOT NULL,
+                PRIMARY KEY (session_id, tag)
+            )",
+            [],
+        )?;
+        
+        self.conn.execute(
+            "CREATE TABLE IF NOT EXISTS templates (
+                name TEXT PRIMARY KEY,
+                tag TEXT NOT NULL,
+                work_minutes INTEGER NOT NULL,
+                break_minutes INTEGER NOT NULL
+            )",
+            [],
+        )?;
+        
+        // Sessions logged before multi-tag support have just their primary tag,
+        // copied over once on the first start with `session_tags`
+        if self.get_config("session_tags_backfilled", "false") != "true" {
+            self.conn.execute(
+                "INSERT OR IGNORE INTO session_tags (session_id, tag)
+                 SELECT id, tag FROM sessions WHERE id NOT IN (SELECT session_id FROM session_tags)",
+                [],
+            )?;
+            self.set_config("session_tags_backfilled", "true")?;
+        }
+        
+        // Sub-tags such as "Study/Math" also count towards their category
+        // ("Study"), the part before the first slash
+        self.conn.execute(
+            "CREATE TEMP VIEW IF NOT EXISTS tag_sessions AS
+             SELECT session_id, tag FROM session_tags
+             UNION
+             SELECT session_id, SUBSTR(tag, 1, INSTR(tag, '/') - 1) FROM session_tags
+             WHERE INSTR(tag, '/') > 1",
+            [],
+        )?;
+        self.conn.execute(
+            "CREATE TEMP VIEW IF NOT EXISTS category_tags AS
+             SELECT DISTINCT session_id,
+                 CASE WHEN INSTR(tag, '/') > 1 THEN SUBSTR(tag, 1, INSTR(tag, '/') - 1) ELSE tag END AS tag
+             FROM session_tags",
+            [],
+        )?;
+        
+        // Columns added after the initial schema
+        self.add_column_if_missing("sessions", "note", "TEXT")?;
+        self.add_column_if_missing("tags", "weekly_target", "INTEGER")?;
+        self.add_column_if_missing("tags", "archived", "INTEGER NOT NULL DEFAULT 0")?;
+        self.add_column_if_missing("sessions", "long_break", "INTEGER NOT NULL DEFAULT 0")?;
+        self.add_column_if_missing("sessions", "rating", "INTEGER")?;
+        
         // Insert default tags if none exist
         let tag_count: i64 = self.conn.query_row(
             "SELECT COUNT(*) FROM tags",
@@ -81,22 +296,74 @@ impl Database {
         Ok(())
     }
     
+    fn add_column_if_missing(&self, table: &str, column: &str, definition: &str) -> Result<()> {
+        let mut stmt = self.conn.prepare(&format!("PRAGMA table_info({})", table))?;
+        let exists = stmt.query_map([], |row| row.get::<_, String>(1))?
+            .filter_map(|r| r.ok())
+            .any(|name| name == column);
+        
+        if !exists {
+            self.conn.execute(
+                &format!("ALTER TABLE {} ADD COLUMN {} {}", table, column, definition),
+                [],
+            )?;
+        }
+        Ok(())
+    }
+    
     // Tag operations
+    
+    /// Active (non-archived) tags
     pub fn get_tags(&self) -> Result<Vec<String>> {
-        let mut stmt = self.conn.prepare("SELECT name FROM tags ORDER BY name")?;
+        self.query_tags("SELECT name FROM tags WHERE archived = 0 ORDER BY name")
+    }
+    
+    /// Start time of each tag's most recent work session, keyed by tag.
+    /// Tags that were never used are missing.
+    pub fn get_last_session_per_tag(&self) -> Result<HashMap<String, String>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT ts.tag, MAX(s.start_time)
+             FROM tag_sessions ts JOIN sessions s ON s.id = ts.session_id
+             WHERE s.type = 'work'
+             GROUP BY ts.tag"
+        )?;
+        let rows = stmt.query_map([], |row| Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?)))?;
+        Ok(rows.flatten().collect())
+    }
+    
+    /// Every tag including archived ones, for historical stats
+    pub fn get_all_tags(&self) -> Result<Vec<String>> {
+        self.query_tags("SELECT name FROM tags ORDER BY name")
+    }
+    
+    pub fn get_archived_tags(&self) -> Result<Vec<String>> {
+        self.query_tags("SELECT name FROM tags WHERE archived = 1 ORDER BY name")
+    }
+    
+    fn query_tags(&self, sql: &str) -> Result<Vec<String>> {
+        let mut stmt = self.conn.prepare(sql)?;
         let tags = stmt.query_map([], |row| row.get(0))?
             .filter_map(|r| r.ok())
             .collect();
         Ok(tags)
     }
     
+    /// Add a tag, reactivating it if it was archived
     pub fn add_tag(&self, name: &str) -> Result<()> {
         self.conn.execute(
-            "INSERT OR IGNORE INTO tags (name) VALUES (?)",
+            "INSERT INTO tags (name) VALUES (?) ON CONFLICT(name) DO UPDATE SET archived = 0",
             [name],
         )?;
         Ok(())
     }
+    
+    pub fn set_tag_archived(&self, name: &str, archived: bool) -> Result<()> {
+        self.conn.execute(
+            "UPDATE tags SET archived = ? WHERE name = ?",
+            params![archived, name],
+        )?;
+        Ok(())
+    }
 
     #[allow(dead_code)]
     pub fn delete_tag(&self, name: &str) -> Result<()> {
@@ -104,22 +371,319 @@ impl Database {
         Ok(())
     }
     
+    /// Move every session of `from` over to `to`, then delete `from`, returning
+    /// how many sessions moved. `to` keeps its weekly target, or takes over the
+    /// one `from` had.
+    pub fn merge_tags(&self, from: &str, to: &str) -> Result<usize> {
+        let tx = self.conn.unchecked_transaction()?;
+        let moved: i64 = tx.query_row("SELECT COUNT(*) FROM session_tags WHERE tag = ?", [from], |row| row.get(0))?;
+        tx.execute(
+            "INSERT OR IGNORE INTO session_tags (session_id, tag)
+             SELECT session_id, ?2 FROM session_tags WHERE tag = ?1",
+            params![from, to],
+        )?;
+        tx.execute("DELETE FROM session_tags WHERE tag = ?", [from])?;
+        tx.execute("UPDATE sessions SET tag = ?2 WHERE tag = ?1", params![from, to])?;
+        tx.execute(
+            "UPDATE tags SET weekly_target = COALESCE(weekly_target, (SELECT weekly_target FROM tags WHERE name = ?1))
+             WHERE name = ?2",
+            params![from, to],
+        )?;
+        tx.execute("DELETE FROM tags WHERE name = ?", [from])?;
+        tx.execute("UPDATE templates SET tag = ?2 WHERE tag = ?1", params![from, to])?;
+        tx.execute(
+            "UPDATE config SET value = ?2 WHERE key IN ('selected_tag', 'default_tag') AND value = ?1",
+            params![from, to],
+        )?;
+        tx.commit()?;
+        Ok(moved as usize)
+    }
+    
+    pub fn get_templates(&self) -> Result<Vec<Template>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT name, tag, work_minutes, break_minutes FROM templates ORDER BY name"
+        )?;
+        let templates = stmt.query_map([], |row| {
+            Ok(Template {
+                name: row.get(0)?,
+                tag: row.get(1)?,
+                work_minutes: row.get::<_, i64>(2)? as u64,
+                break_minutes: row.get::<_, i64>(3)? as u64,
+            })
+        })?;
+        Ok(templates.flatten().collect())
+    }
+    
+    /// Save a template, replacing any with the same name
+    pub fn save_template(&self, template: &Template) -> Result<()> {
+        self.conn.execute(
+            "INSERT OR REPLACE INTO templates (name, tag, work_minutes, break_minutes) VALUES (?, ?, ?, ?)",
+            params![template.name, template.tag, template.work_minutes as i64, template.break_minutes as i64],
+        )?;
+        Ok(())
+    }
+    
+    pub fn delete_template(&self, name: &str) -> Result<()> {
+        self.conn.execute("DELETE FROM templates WHERE name = ?", [name])?;
+        Ok(())
+    }
+    
+    /// Weekly target for a tag in minutes, if one has been set
+    pub fn get_tag_weekly_target(&self, name: &str) -> Option<i64> {
+        self.conn.query_row(
+            "SELECT weekly_target FROM tags WHERE name = ?",
+            [name],
+            |row| row.get::<_, Option<i64>>(0),
+        ).ok().flatten()
+    }
+    
+    pub fn set_tag_weekly_target(&self, name: &str, minutes: Option<i64>) -> Result<()> {
+        self.conn.execute(
+            "UPDATE tags SET weekly_target = ? WHERE name = ?",
+            params![minutes, name],
+        )?;
+        Ok(())
+    }
+    
     // Session operations
+    
+    /// Log a session under one or more tags (falling back to the default tag); the first is its primary tag
     pub fn save_session(&self, start_time: &DateTime<Local>, end_time: &DateTime<Local>, 
-                        duration: i64, tag: &str, session_type: &str) -> Result<()> {
+                        duration: i64, tags: &[&str], session_type: &str) -> Result<i64> {
+        self.insert_session(
+            &start_time.format(TIMESTAMP_FORMAT).to_string(),
+            &end_time.format(TIMESTAMP_FORMAT).to_string(),
+            duration,
+            tags,
+            session_type,
+            None,
+        )
+    }
+    
+    fn insert_session(&self, start_time: &str, end_time: &str, duration: i64,
+                      tags: &[&str], session_type: &str, note: Option<&str>) -> Result<i64> {
+        let default_tag = self.get_config("default_tag", DEFAULT_TAG);
+        let tags = if tags.is_empty() { &[default_tag.as_str()][..] } else { tags };
+        self.conn.execute(
+            "INSERT INTO sessions (start_time, end_time, duration, tag, type, note) VALUES (?, ?, ?, ?, ?, ?)",
+            params![start_time, end_time, duration, tags[0], session_type, note],
+        )?;
+        let id = self.conn.last_insert_rowid();
+        
+        for tag in tags {
+            self.conn.execute(
+                "INSERT OR IGNORE INTO session_tags (session_id, tag) VALUES (?, ?)",
+                params![id, tag],
+            )?;
+        }
+        Ok(id)
+    }
+    
+    /// Most recently finished sessions, newest first.
+    /// `tag` lists every tag of a session, e.g. "Study + Math".
+    pub fn get_recent_sessions(&self, limit: usize) -> Result<Vec<Session>> {
+        self.query_sessions(
+            &format!("SELECT {} FROM sessions ORDER BY end_time DESC, id DESC LIMIT ?", SESSION_COLUMNS),
+            [limit as i64],
+        )
+    }
+    
+    /// Work sessions in one Stats bucket, oldest first: `bucket` is a day
+    /// ("2024-06-10"), month ("2024-06") or year ("2024") as the charts label them.
+    /// Sessions are listed whole under the day they started, even past midnight.
+    pub fn get_sessions_in_bucket(&self, bucket: &str, tag: Option<&str>) -> Result<Vec<Session>> {
+        self.query_sessions(
+            &format!(
+                "SELECT {} FROM sessions
+                 WHERE type = 'work' AND SUBSTR(DATE(start_time, ?3), 1, LENGTH(?1)) = ?1
+                 AND (?2 IS NULL OR id IN (SELECT session_id FROM tag_sessions WHERE tag = ?2))
+                 ORDER BY start_time, id",
+                SESSION_COLUMNS
+            ),
+            params![bucket, tag, self.day_shift()],
+        )
+    }
+    
+    /// Average focus rating of the rated work sessions in a chart bucket
+    /// (see `get_sessions_in_bucket`, whose sessions it averages), or `None` if none were rated
+    pub fn get_average_rating(&self, bucket: &str, tag: Option<&str>) -> Option<f64> {
+        self.conn.query_row(
+            "SELECT AVG(rating) FROM sessions
+             WHERE type = 'work' AND rating IS NOT NULL
+             AND SUBSTR(DATE(start_time, ?3), 1, LENGTH(?1)) = ?1
+             AND (?2 IS NULL OR id IN (SELECT session_id FROM tag_sessions WHERE tag = ?2))",
+            params![bucket, tag, self.day_shift()],
+            |row| row.get(0),
+        ).ok().flatten()
+    }
+    
+    /// Sessions with a note that started between two days (inclusive), oldest first
+    pub fn get_noted_sessions(&self, from: NaiveDate, to: NaiveDate) -> Result<Vec<Session>> {
+        self.query_sessions(
+            &format!(
+                "SELECT {} FROM sessions
+                 WHERE note IS NOT NULL AND note != '' AND DATE(start_time, ?3) BETWEEN ?1 AND ?2
+                 ORDER BY start_time, id",
+                SESSION_COLUMNS
+            ),
+            params![from.to_string(), to.to_string(), self.day_shift()],
+        )
+    }
+    
+    /// Every session, oldest first
+    pub fn get_all_sessions(&self) -> Result<Vec<Session>> {
+        self.query_sessions(&format!("SELECT {} FROM sessions ORDER BY start_time, id", SESSION_COLUMNS), [])
+    }
+    
+    fn query_sessions<P: rusqlite::Params>(&self, sql: &str, params: P) -> Result<Vec<Session>> {
+        let mut stmt = self.conn.prepare(sql)?;
+        let rows = stmt.query_map(params, |row| {
+            Ok(Session {
+                id: row.get(0)?,
+                start_time: row.get(1)?,
+                end_time: row.get(2)?,
+                duration: row.get(3)?,
+                tag: row.get(4)?,
+                session_type: row.get(5)?,
+                note: row.get(6)?,
+            })
+        })?;
+        Ok(rows.flatten().collect())
+    }
+    
+    pub fn delete_session(&self, id: i64) -> Result<()> {
+        self.conn.execute("DELETE FROM session_tags WHERE session_id = ?", [id])?;
+        self.conn.execute("DELETE FROM sessions WHERE id = ?", [id])?;
+        Ok(())
+    }
+    
+    /// Days of sessions to keep when purging old ones; 0 keeps everything
+    pub fn retention_days(&self) -> i64 {
+        self.get_config("retention_days", "0").parse().unwrap_or(0).clamp(0, MAX_RETENTION_DAYS)
+    }
+    
+    /// First day kept when keeping `days` days of sessions, or `None` when
+    /// that reaches past the earliest representable date
+    pub fn retention_cutoff(&self, days: i64) -> Option<NaiveDate> {
+        self.today().checked_sub_signed(chrono::Duration::try_days(days)?)
+    }
+    
+    /// Sessions (work and breaks) that started on a day before `date`
+    pub fn count_sessions_before(&self, date: NaiveDate) -> i64 {
+        self.conn.query_row(
+            "SELECT COUNT(*) FROM sessions WHERE DATE(start_time, ?2) < ?1",
+            params![date.to_string(), self.day_shift()],
+            |row| row.get(0),
+        ).unwrap_or(0)
+    }
+    
+    /// Delete every session that started on a day before `date`, with its
+    /// tags, returning how many were deleted
+    pub fn purge_sessions_before(&self, date: NaiveDate) -> Result<usize> {
+        let tx = self.conn.unchecked_transaction()?;
+        tx.execute(
+            "DELETE FROM session_tags WHERE session_id IN
+             (SELECT id FROM sessions WHERE DATE(start_time, ?2) < ?1)",
+            params![date.to_string(), self.day_shift()],
+        )?;
+        let deleted = tx.execute(
+            "DELETE FROM sessions WHERE DATE(start_time, ?2) < ?1",
+            params![date.to_string(), self.day_shift()],
+        )?;
+        tx.commit()?;
+        Ok(deleted)
+    }
+    
+    /// Shrink the database file after a purge
+    pub fn vacuum(&self) -> Result<()> {
+        self.conn.execute_batch("VACUUM")
+    }
+    
+    /// Copy the whole database to `path` with SQLite's online backup, so the
+    /// copy is consistent even if a write is in progress
+    pub fn backup_to(&self, path: &Path) -> Result<()> {
+        self.conn.backup(DatabaseName::Main, path, None)
+    }
+    
+    /// Write every session to a file in `format`, returning how many were written
+    pub fn export(&self, format: ExportFormat, path: &Path) -> std::result::Result<usize, Box<dyn std::error::Error>> {
+        let sessions = self.get_all_sessions()?;
+        std::fs::write(path, format.render(&sessions))?;
+        Ok(sessions.len())
+    }
+    
+    /// Add the sessions from a CSV file written by `export`, returning how many
+    /// were inserted. Malformed rows are skipped and unknown tags are created.
+    /// A failed insert rolls back the whole import, so it can simply be rerun.
+    pub fn import_sessions_csv(&self, path: &Path) -> std::result::Result<usize, Box<dyn std::error::Error>> {
+        let text = std::fs::read_to_string(path)?;
+        let tx = self.conn.unchecked_transaction()?;
+        let mut inserted = 0;
+        
+        for session in parse_sessions_csv(&text) {
+            let tags: Vec<&str> = session.tags.iter().map(String::as_str).collect();
+            for tag in &tags {
+                self.conn.execute("INSERT OR IGNORE INTO tags (name) VALUES (?)", [tag])?;
+            }
+            self.insert_session(
+                &session.start_time,
+                &session.end_time,
+                session.duration,
+                &tags,
+                &session.session_type,
+                session.note.as_deref(),
+            )?;
+            inserted += 1;
+        }
+        tx.commit()?;
+        Ok(inserted)
+    }
+    
+    #[cfg(test)]
+    pub fn count_sessions(&self) -> i64 {
+        self.conn.query_row("SELECT COUNT(*) FROM sessions", [], |row| row.get(0)).unwrap_or(0)
+    }
+    
+    pub fn set_session_note(&self, id: i64, note: &str) -> Result<()> {
         self.conn.execute(
-            "INSERT INTO sessions (start_time, end_time, duration, tag, type) VALUES (?, ?, ?, ?, ?)",
-            params![
-                start_time.format("%Y-%m-%d %H:%M:%S").to_string(),
-                end_time.format("%Y-%m-%d %H:%M:%S").to_string(),
-                duration,
-                tag,
-                session_type
-            ],
+            "UPDATE sessions SET note = ? WHERE id = ?",
+            params![note, id],
         )?;
         Ok(())
     }
     
+    /// Focus quality from 1 to 5, as rated right after the session
+    pub fn set_session_rating(&self, id: i64, rating: u8) -> Result<()> {
+        self.conn.execute("UPDATE sessions SET rating = ? WHERE id = ?", params![rating, id])?;
+        Ok(())
+    }
+    
+    pub fn mark_long_break(&self, id: i64) -> Result<()> {
+        self.conn.execute("UPDATE sessions SET long_break = 1 WHERE id = ?", [id])?;
+        Ok(())
+    }
+    
+    /// Work sessions logged since the most recent long break, or since the
+    /// cycle was last started over, which decides when the next long break is
+    /// due. With `since`, the cycle also starts over on that day.
+    pub fn count_work_since_long_break(&self, since: Option<NaiveDate>) -> i64 {
+        self.conn.query_row(
+            "SELECT COUNT(*) FROM sessions
+             WHERE type = 'work'
+             AND end_time > COALESCE((SELECT MAX(end_time) FROM sessions WHERE long_break = 1), '')
+             AND end_time > COALESCE((SELECT value FROM config WHERE key = 'long_break_cycle_start'), '')
+             AND (?1 IS NULL OR DATE(start_time, ?2) >= ?1)",
+            params![since.map(|day| day.to_string()), self.day_shift()],
+            |row| row.get(0),
+        ).unwrap_or(0)
+    }
+    
+    /// Start the long break count over from `now`, so sessions logged
+    /// before long breaks were turned on don't count towards one
+    pub fn restart_long_break_cycle(&self, now: &DateTime<Local>) -> Result<()> {
+        self.set_config("long_break_cycle_start", &now.format(TIMESTAMP_FORMAT).to_string())
+    }
+    
     // Config operations
     pub fn get_config(&self, key: &str, default: &str) -> String {
         self.conn.query_row(
@@ -129,6 +693,27 @@ impl Database {
         ).unwrap_or_else(|_| default.to_string())
     }
     
+    /// Reads a numeric config value, falling back to `default` when it won't
+    /// parse and clamping it into `range`; a stored value that needed fixing
+    /// is written back so the repair sticks
+    pub fn get_config_clamped(&self, key: &str, default: u64, range: std::ops::RangeInclusive<u64>) -> u64 {
+        let stored = self.get_config(key, &default.to_string());
+        let value = stored.trim().parse::<u64>().unwrap_or(default).clamp(*range.start(), *range.end());
+        if stored != value.to_string() {
+            let _ = self.set_config(key, &value.to_string());
+        }
+        value
+    }
+    
+    /// Write several config values at once, all or none of them
+    pub fn set_configs(&self, values: &[(&str, String)]) -> Result<()> {
+        let tx = self.conn.unchecked_transaction()?;
+        for (key, value) in values {
+            tx.execute("INSERT OR REPLACE INTO config (key, value) VALUES (?, ?)", params![key, value])?;
+        }
+        tx.commit()
+    }
+    
     pub fn set_config(&self, key: &str, value: &str) -> Result<()> {
         self.conn.execute(
             "INSERT OR REPLACE INTO config (key, value) VALUES (?, ?)",
@@ -137,42 +722,83 @@ impl Database {
         Ok(())
     }
     
+    // Timer state operations
+    pub fn save_timer_state(&self, state: Option<&TimerState>) -> Result<()> {
+        let value = match state {
+            Some(state) => format!(
+                "{}|{}|{}|{}|{}|{}|{}",
+                state.session_type, state.running as u8, state.remaining_seconds, state.saved_at,
+                state.started_at, state.elapsed_seconds, state.tags.join(" + ")
+            ),
+            None => String::new(),
+        };
+        self.set_config("timer_state", &value)
+    }
+    
+    pub fn load_timer_state(&self) -> Option<TimerState> {
+        let value = self.get_config("timer_state", "");
+        // Tags come last, so they can't be cut short by the separator
+        let parts: Vec<&str> = value.splitn(7, '|').collect();
+        if parts.len() != 4 && parts.len() != 7 {
+            return None;
+        }
+        let saved_at = parts[3].parse().ok()?;
+        // States saved before the start and tags were recorded have nothing to log
+        let (started_at, elapsed_seconds, tags) = match parts.get(4..7) {
+            Some([started_at, elapsed, tags]) => (
+                started_at.parse().ok()?,
+                elapsed.parse().ok()?,
+                tags.split(" + ").filter(|tag| !tag.is_empty()).map(String::from).collect(),
+            ),
+            _ => (saved_at, 0, Vec::new()),
+        };
+        Some(TimerState {
+            session_type: parts[0].to_string(),
+            running: parts[1] == "1",
+            remaining_seconds: parts[2].parse().ok()?,
+            saved_at,
+            started_at,
+            elapsed_seconds,
+            tags,
+        })
+    }
+    
     // Statistics queries
+    //
+    // "All Tags" totals read `sessions` directly, so a multi-tag session counts once.
+    // Per-tag figures go through `tag_sessions`, counting it under each of its tags
+    // and their categories. Breakdowns group by full tag or, with `by_category`, by
+    // category only.
     pub fn get_weekly_stats(&self, tag: Option<&str>) -> Result<Vec<(String, i64)>> {
         let mut results = Vec::new();
         
         if let Some(t) = tag {
-            let mut stmt = self.conn.prepare(
-                "SELECT DATE(start_time) as day, SUM(duration) as total
-                 FROM sessions
-                 WHERE tag = ? AND type = 'work' AND start_time >= DATE('now', '-7 days')
+            let mut stmt = self.conn.prepare(&format!(
+                "{} SELECT DATE(start_time, ?2) as day, SUM(duration) as total
+                 FROM day_sessions AS sessions
+                 WHERE id IN (SELECT session_id FROM tag_sessions WHERE tag = ?1)
+                 AND type = 'work' AND start_time >= DATE('now', '-7 days')
                  GROUP BY day
-                 ORDER BY day"
-            )?;
-            let rows = stmt.query_map([t], |row| {
+                 ORDER BY day",
+                self.with_day_sessions()
+            ))?;
+            let rows = stmt.query_map(params![t, self.day_shift()], |row| {
                 Ok((row.get::<_, String>(0)?, row.get::<_, i64>(1)?))
             })?;
-            for row in rows {
-                if let Ok(r) = row {
-                    results.push(r);
-                }
-            }
+            results.extend(rows.flatten());
         } else {
-            let mut stmt = self.conn.prepare(
-                "SELECT DATE(start_time) as day, SUM(duration) as total
-                 FROM sessions
+            let mut stmt = self.conn.prepare(&format!(
+                "{} SELECT DATE(start_time, ?1) as day, SUM(duration) as total
+                 FROM day_sessions AS sessions
                  WHERE type = 'work' AND start_time >= DATE('now', '-7 days')
                  GROUP BY day
-                 ORDER BY day"
-            )?;
-            let rows = stmt.query_map([], |row| {
+                 ORDER BY day",
+                self.with_day_sessions()
+            ))?;
+            let rows = stmt.query_map([self.day_shift()], |row| {
                 Ok((row.get::<_, String>(0)?, row.get::<_, i64>(1)?))
             })?;
-            for row in rows {
-                if let Ok(r) = row {
-                    results.push(r);
-                }
-            }
+            results.extend(rows.flatten());
         }
         
         Ok(results)
@@ -182,54 +808,242 @@ impl Database {
         let mut results = Vec::new();
         
         if let Some(t) = tag {
-            let mut stmt = self.conn.prepare(
-                "SELECT STRFTIME('%Y-%m', start_time) as month, SUM(duration) as total
-                 FROM sessions
-                 WHERE tag = ? AND type = 'work'
+            let mut stmt = self.conn.prepare(&format!(
+                "{} SELECT STRFTIME('%Y-%m', start_time, ?2) as month, SUM(duration) as total
+                 FROM day_sessions AS sessions
+                 WHERE id IN (SELECT session_id FROM tag_sessions WHERE tag = ?1) AND type = 'work'
                  GROUP BY month
+                 HAVING total > 0
                  ORDER BY month DESC
-                 LIMIT 12"
-            )?;
-            let rows = stmt.query_map([t], |row| {
+                 LIMIT 12",
+                self.with_day_sessions()
+            ))?;
+            let rows = stmt.query_map(params![t, self.day_shift()], |row| {
                 Ok((row.get::<_, String>(0)?, row.get::<_, i64>(1)?))
             })?;
-            for row in rows {
-                if let Ok(r) = row {
-                    results.push(r);
-                }
-            }
+            results.extend(rows.flatten());
         } else {
-            let mut stmt = self.conn.prepare(
-                "SELECT STRFTIME('%Y-%m', start_time) as month, SUM(duration) as total
-                 FROM sessions
+            let mut stmt = self.conn.prepare(&format!(
+                "{} SELECT STRFTIME('%Y-%m', start_time, ?1) as month, SUM(duration) as total
+                 FROM day_sessions AS sessions
                  WHERE type = 'work'
                  GROUP BY month
+                 HAVING total > 0
                  ORDER BY month DESC
-                 LIMIT 12"
-            )?;
-            let rows = stmt.query_map([], |row| {
+                 LIMIT 12",
+                self.with_day_sessions()
+            ))?;
+            let rows = stmt.query_map([self.day_shift()], |row| {
                 Ok((row.get::<_, String>(0)?, row.get::<_, i64>(1)?))
             })?;
-            for row in rows {
-                if let Ok(r) = row {
-                    results.push(r);
-                }
-            }
+            results.extend(rows.flatten());
         }
         
         Ok(results)
     }
     
-    pub fn get_heatmap_data(&self) -> Result<Vec<(NaiveDate, i64)>> {
-        let mut stmt = self.conn.prepare(
-            "SELECT DATE(start_time) as day, SUM(duration) as total
+    /// Daily work seconds over the last 7 days, split by tag: (day, tag, total)
+    pub fn get_weekly_stats_by_tag(&self, by_category: bool) -> Result<Vec<(String, String, i64)>> {
+        self.query_stats_by_tag(&format!(
+            "{} SELECT DATE(start_time, ?1) as day, session_tags.tag, SUM(duration) as total
+             FROM day_sessions AS sessions JOIN {} AS session_tags ON session_tags.session_id = sessions.id
+             WHERE type = 'work' AND start_time >= DATE('now', '-7 days')
+             GROUP BY day, session_tags.tag
+             ORDER BY day, session_tags.tag",
+            self.with_day_sessions(),
+            tag_source(by_category)
+        ))
+    }
+    
+    /// Monthly work seconds over the last 12 months, split by tag: (month, tag, total)
+    pub fn get_monthly_stats_by_tag(&self, by_category: bool) -> Result<Vec<(String, String, i64)>> {
+        self.query_stats_by_tag(&format!(
+            "{} SELECT STRFTIME('%Y-%m', start_time, ?1) as month, session_tags.tag, SUM(duration) as total
+             FROM day_sessions AS sessions JOIN {} AS session_tags ON session_tags.session_id = sessions.id
+             WHERE type = 'work' AND start_time >= DATE('now', 'start of month', '-11 months')
+             GROUP BY month, session_tags.tag
+             HAVING total > 0
+             ORDER BY month DESC, session_tags.tag",
+            self.with_day_sessions(),
+            tag_source(by_category)
+        ))
+    }
+    
+    /// Yearly work seconds, split by tag: (year, tag, total)
+    pub fn get_yearly_stats_by_tag(&self, by_category: bool) -> Result<Vec<(String, String, i64)>> {
+        self.query_stats_by_tag(&format!(
+            "{} SELECT STRFTIME('%Y', start_time, ?1) as year, session_tags.tag, SUM(duration) as total
+             FROM day_sessions AS sessions JOIN {} AS session_tags ON session_tags.session_id = sessions.id
+             WHERE type = 'work'
+             GROUP BY year, session_tags.tag
+             HAVING total > 0
+             ORDER BY year, session_tags.tag",
+            self.with_day_sessions(),
+            tag_source(by_category)
+        ))
+    }
+    
+    fn query_stats_by_tag(&self, sql: &str) -> Result<Vec<(String, String, i64)>> {
+        let mut stmt = self.conn.prepare(sql)?;
+        let rows = stmt.query_map([self.day_shift()], |row| {
+            Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?, row.get::<_, i64>(2)?))
+        })?;
+        Ok(rows.flatten().collect())
+    }
+    
+    /// Work seconds in the last 7 days and in the 7 days before that
+    pub fn get_week_over_week(&self, tag: Option<&str>) -> Result<(i64, i64)> {
+        self.conn.query_row(
+            "SELECT
+                COALESCE(SUM(CASE WHEN start_time >= DATETIME('now', 'localtime', '-7 days') THEN duration END), 0),
+                COALESCE(SUM(CASE WHEN start_time < DATETIME('now', 'localtime', '-7 days') THEN duration END), 0)
              FROM sessions
-             WHERE type = 'work' AND start_time >= DATE('now', '-180 days')
-             GROUP BY day
-             ORDER BY day"
+             WHERE type = 'work' AND start_time >= DATETIME('now', 'localtime', '-14 days')
+             AND (?1 IS NULL OR id IN (SELECT session_id FROM tag_sessions WHERE tag = ?1))",
+            [tag],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )
+    }
+    
+    /// Total work and break seconds since `since` (a "YYYY-MM-DD" date, or all time)
+    pub fn get_work_break_totals(&self, since: Option<&str>, tag: Option<&str>) -> Result<(i64, i64)> {
+        self.conn.query_row(
+            "SELECT
+                COALESCE(SUM(CASE WHEN type = 'work' THEN duration END), 0),
+                COALESCE(SUM(CASE WHEN type = 'break' THEN duration END), 0)
+             FROM sessions
+             WHERE (?1 IS NULL OR start_time >= ?1)
+             AND (?2 IS NULL OR id IN (SELECT session_id FROM tag_sessions WHERE tag = ?2))",
+            params![since, tag],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )
+    }
+    
+    /// Work seconds logged in the last hour after the most recent break. A
+    /// session that started before that window counts only its part inside it.
+    pub fn get_work_since_break_last_hour(&self) -> i64 {
+        self.conn.query_row(
+            "WITH since AS (
+                 SELECT MAX(DATETIME('now', 'localtime', '-1 hour'),
+                            COALESCE((SELECT MAX(end_time) FROM sessions WHERE type = 'break'), '')) AS start
+             )
+             SELECT COALESCE(SUM(MIN(duration, CAST(ROUND((JULIANDAY(end_time) - JULIANDAY(since.start)) * 86400) AS INTEGER))), 0)
+             FROM sessions, since
+             WHERE type = 'work' AND end_time > since.start",
+            [],
+            |row| row.get(0),
+        ).unwrap_or(0)
+    }
+    
+    /// All-time work seconds per weekday, Monday first, plus the first day with
+    /// work (to count how many of each weekday have passed since)
+    pub fn get_weekday_totals(&self, tag: Option<&str>) -> Result<([i64; 7], Option<NaiveDate>)> {
+        let mut totals = [0; 7];
+        let mut stmt = self.conn.prepare(&format!(
+            "{} SELECT CAST(STRFTIME('%w', start_time, ?2) AS INTEGER) as weekday, SUM(duration)
+             FROM day_sessions AS sessions
+             WHERE type = 'work' AND (?1 IS NULL OR id IN (SELECT session_id FROM tag_sessions WHERE tag = ?1))
+             GROUP BY weekday",
+            self.with_day_sessions()
+        ))?;
+        let rows = stmt.query_map(params![tag, self.day_shift()], |row| Ok((row.get::<_, i64>(0)?, row.get::<_, i64>(1)?)))?;
+        for (weekday, total) in rows.flatten() {
+            // %w counts from Sunday
+            totals[((weekday + 6) % 7) as usize] = total;
+        }
+        
+        let first: Option<String> = self.conn.query_row(
+            "SELECT MIN(DATE(start_time, ?2)) FROM sessions
+             WHERE type = 'work' AND (?1 IS NULL OR id IN (SELECT session_id FROM tag_sessions WHERE tag = ?1))",
+            params![tag, self.day_shift()],
+            |row| row.get(0),
         )?;
+        Ok((totals, first.and_then(|day| NaiveDate::parse_from_str(&day, "%Y-%m-%d").ok())))
+    }
+    
+    /// All-time work seconds per hour of the day, midnight first. A session is
+    /// spread over the hours it covered, so 09:40-10:30 adds 20 min to 9 and 30 to 10.
+    pub fn get_hourly_distribution(&self, tag: Option<&str>) -> Result<[i64; 24]> {
+        let mut totals = [0; 24];
+        let mut stmt = self.conn.prepare(
+            "SELECT start_time, duration FROM sessions
+             WHERE type = 'work' AND (?1 IS NULL OR id IN (SELECT session_id FROM tag_sessions WHERE tag = ?1))"
+        )?;
+        let rows = stmt.query_map(params![tag], |row| Ok((row.get::<_, String>(0)?, row.get::<_, i64>(1)?)))?;
+        for (start, duration) in rows.flatten() {
+            let Ok(start) = NaiveDateTime::parse_from_str(&start, TIMESTAMP_FORMAT) else { continue };
+            let mut second = start.num_seconds_from_midnight() as i64;
+            let mut left = duration;
+            while left > 0 {
+                let chunk = left.min(3600 - second % 3600);
+                totals[(second / 3600 % 24) as usize] += chunk;
+                second += chunk;
+                left -= chunk;
+            }
+        }
+        Ok(totals)
+    }
+    
+    /// Total work seconds for a tag since `week_start`, the first day of the
+    /// current week
+    pub fn get_week_total_for_tag(&self, tag: &str, week_start: NaiveDate) -> i64 {
+        self.conn.query_row(
+            &format!(
+                "{} SELECT COALESCE(SUM(duration), 0) FROM day_sessions
+                 WHERE id IN (SELECT session_id FROM tag_sessions WHERE tag = ?1) AND type = 'work'
+                 AND DATE(start_time, ?2) >= ?3",
+                self.with_day_sessions()
+            ),
+            params![tag, self.day_shift(), week_start.to_string()],
+            |row| row.get(0),
+        ).unwrap_or(0)
+    }
+    
+    pub fn get_yearly_stats(&self, tag: Option<&str>) -> Result<Vec<(String, i64)>> {
+        let mut stmt = self.conn.prepare(&format!(
+            "{} SELECT STRFTIME('%Y', start_time, ?2) as year, SUM(duration) as total
+             FROM day_sessions AS sessions
+             WHERE type = 'work' AND (?1 IS NULL OR id IN (SELECT session_id FROM tag_sessions WHERE tag = ?1))
+             GROUP BY year
+             HAVING total > 0
+             ORDER BY year",
+            self.with_day_sessions()
+        ))?;
+        let rows = stmt.query_map(params![tag, self.day_shift()], |row| {
+            Ok((row.get::<_, String>(0)?, row.get::<_, i64>(1)?))
+        })?;
+        Ok(rows.flatten().collect())
+    }
+    
+    pub fn get_heatmap_data(&self) -> Result<Vec<(NaiveDate, i64)>> {
+        self.get_heatmap_data_for_tag(None, 180)
+    }
+    
+    /// Work seconds per day over the last `days` days, optionally for a single tag
+    pub fn get_heatmap_data_for_tag(&self, tag: Option<&str>, days: i64) -> Result<Vec<(NaiveDate, i64)>> {
+        self.query_heatmap("SUM(duration)", tag, days)
+    }
+    
+    /// Completed work sessions per day over the last `days` days, optionally for a single tag.
+    /// A session past midnight counts once, on the day it started.
+    pub fn get_heatmap_counts_for_tag(&self, tag: Option<&str>, days: i64) -> Result<Vec<(NaiveDate, i64)>> {
+        self.query_heatmap("SUM(part = 0)", tag, days)
+    }
+    
+    fn query_heatmap(&self, aggregate: &str, tag: Option<&str>, days: i64) -> Result<Vec<(NaiveDate, i64)>> {
+        let mut stmt = self.conn.prepare(&format!(
+            "{} SELECT DATE(start_time, ?3) as day, {} as total
+             FROM day_sessions AS sessions
+             WHERE type = 'work' AND start_time >= DATE('now', '-' || ?2 || ' days')
+             AND (?1 IS NULL OR id IN (SELECT session_id FROM tag_sessions WHERE tag = ?1))
+             GROUP BY day
+             HAVING total > 0
+             ORDER BY day",
+            self.with_day_sessions(),
+            aggregate
+        ))?;
         
-        let rows = stmt.query_map([], |row| {
+        let rows = stmt.query_map(params![tag, days, self.day_shift()], |row| {
             let date_str: String = row.get(0)?;
             let total: i64 = row.get(1)?;
             Ok((date_str, total))
@@ -245,13 +1059,455 @@ impl Database {
             .collect())
     }
 
-    #[allow(dead_code)]
+    /// Days within the last `days` days (today included) whose work reached `goal_minutes`
+    pub fn get_goal_met_days(&self, goal_minutes: u64, days: i64) -> Result<Vec<NaiveDate>> {
+        let mut stmt = self.conn.prepare(&format!(
+            "{} SELECT DATE(start_time, ?3) as day
+             FROM day_sessions AS sessions
+             WHERE type = 'work' AND DATE(start_time, ?3) > DATE(?4, '-' || ?2 || ' days')
+             GROUP BY day
+             HAVING SUM(duration) >= ?1 * 60
+             ORDER BY day",
+            self.with_day_sessions()
+        ))?;
+        let rows = stmt.query_map(params![goal_minutes as i64, days, self.day_shift(), self.today().to_string()], |row| row.get::<_, String>(0))?;
+        Ok(rows
+            .flatten()
+            .filter_map(|day| NaiveDate::parse_from_str(&day, "%Y-%m-%d").ok())
+            .collect())
+    }
+    
+    /// Work seconds per day between `from` and `to` (inclusive), days without work omitted
+    pub fn get_daily_totals(&self, from: NaiveDate, to: NaiveDate) -> Result<Vec<(NaiveDate, i64)>> {
+        let mut stmt = self.conn.prepare(&format!(
+            "{} SELECT DATE(start_time, ?3) as day, SUM(duration) as total
+             FROM day_sessions AS sessions
+             WHERE type = 'work' AND DATE(start_time, ?3) BETWEEN ?1 AND ?2
+             GROUP BY day
+             HAVING total > 0
+             ORDER BY day",
+            self.with_day_sessions()
+        ))?;
+        let rows = stmt.query_map(params![from.to_string(), to.to_string(), self.day_shift()], |row| {
+            Ok((row.get::<_, String>(0)?, row.get::<_, i64>(1)?))
+        })?;
+        
+        Ok(rows
+            .flatten()
+            .filter_map(|(day, total)| NaiveDate::parse_from_str(&day, "%Y-%m-%d").ok().map(|d| (d, total)))
+            .collect())
+    }
+    
+    /// Work seconds per tag between `from` and `to` (inclusive), busiest first
+    pub fn get_tag_totals(&self, from: NaiveDate, to: NaiveDate) -> Result<Vec<(String, i64)>> {
+        let mut stmt = self.conn.prepare(&format!(
+            "{} SELECT session_tags.tag, SUM(duration) as total
+             FROM day_sessions AS sessions JOIN session_tags ON session_tags.session_id = sessions.id
+             WHERE type = 'work' AND DATE(start_time, ?3) BETWEEN ?1 AND ?2
+             GROUP BY session_tags.tag
+             HAVING total > 0
+             ORDER BY total DESC, session_tags.tag",
+            self.with_day_sessions()
+        ))?;
+        let rows = stmt.query_map(params![from.to_string(), to.to_string(), self.day_shift()], |row| {
+            Ok((row.get::<_, String>(0)?, row.get::<_, i64>(1)?))
+        })?;
+        Ok(rows.flatten().collect())
+    }
+    
+    /// Work sessions started between `from` and `to` (inclusive)
+    pub fn get_session_count_between(&self, from: NaiveDate, to: NaiveDate) -> i64 {
+        self.conn.query_row(
+            "SELECT COUNT(*) FROM sessions
+             WHERE type = 'work' AND DATE(start_time, ?3) BETWEEN ?1 AND ?2",
+            params![from.to_string(), to.to_string(), self.day_shift()],
+            |row| row.get(0),
+        ).unwrap_or(0)
+    }
+    
+    pub fn get_session_count_today(&self) -> i64 {
+        self.conn.query_row(
+            "SELECT COUNT(*) FROM sessions 
+             WHERE type = 'work' AND DATE(start_time, ?1) = ?2",
+            params![self.day_shift(), self.today().to_string()],
+            |row| row.get(0),
+        ).unwrap_or(0)
+    }
+    
+    /// Work seconds logged from `from` up to today, e.g. over the current week
+    pub fn get_total_since(&self, from: NaiveDate) -> i64 {
+        self.conn.query_row(
+            &format!(
+                "{} SELECT COALESCE(SUM(duration), 0) FROM day_sessions
+                 WHERE type = 'work' AND DATE(start_time, ?1) >= ?2",
+                self.with_day_sessions()
+            ),
+            params![self.day_shift(), from.to_string()],
+            |row| row.get(0),
+        ).unwrap_or(0)
+    }
+    
     pub fn get_total_today(&self) -> i64 {
         self.conn.query_row(
-            "SELECT COALESCE(SUM(duration), 0) FROM sessions 
-             WHERE type = 'work' AND DATE(start_time) = DATE('now')",
-            [],
+            &format!(
+                "{} SELECT COALESCE(SUM(duration), 0) FROM day_sessions
+                 WHERE type = 'work' AND DATE(start_time, ?1) = ?2",
+                self.with_day_sessions()
+            ),
+            params![self.day_shift(), self.today().to_string()],
             |row| row.get(0),
         ).unwrap_or(0)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_csv_export_import_round_trip() {
+        let source = Database::open_in_memory().unwrap();
+        let start = Local::now() - chrono::Duration::hours(2);
+        let end = start + chrono::Duration::minutes(25);
+        source.save_session(&start, &end, 1500, &["Study", "Work"], "work").unwrap();
+        let id = source.save_session(&end, &(end + chrono::Duration::minutes(5)), 300, &["Work"], "break").unwrap();
+        source.set_session_note(id, "stretched, had \"coffee\"\nand water").unwrap();
+        source.add_tag("Reading").unwrap();
+        source.save_session(&start, &end, 1500, &["Reading"], "work").unwrap();
+        
+        let path = std::env::temp_dir().join(format!("pomodoro-csv-test-{}.csv", std::process::id()));
+        assert_eq!(source.export(ExportFormat::Csv, &path).unwrap(), 3);
+        
+        let target = Database::open_in_memory().unwrap();
+        assert_eq!(target.import_sessions_csv(&path).unwrap(), 3);
+        let _ = std::fs::remove_file(&path);
+        
+        let strip_ids = |db: &Database| -> Vec<Session> {
+            db.get_all_sessions().unwrap().into_iter().map(|s| Session { id: 0, ..s }).collect()
+        };
+        assert_eq!(strip_ids(&source), strip_ids(&target));
+        assert!(target.get_tags().unwrap().contains(&"Reading".to_string()));
+    }
+
+    #[test]
+    fn test_backup_copies_sessions() {
+        let source = Database::open_in_memory().unwrap();
+        let end = Local::now();
+        source.save_session(&(end - chrono::Duration::minutes(25)), &end, 1500, &["Work"], "work").unwrap();
+        
+        let path = std::env::temp_dir().join(format!("pomodoro-backup-test-{}.db", std::process::id()));
+        source.backup_to(&path).unwrap();
+        let backup = Database::from_connection(Connection::open(&path).unwrap()).unwrap();
+        let _ = std::fs::remove_file(&path);
+        
+        assert_eq!(backup.get_all_sessions().unwrap(), source.get_all_sessions().unwrap());
+    }
+
+    #[test]
+    fn test_timer_state_round_trip() {
+        let db = Database::open_in_memory().unwrap();
+        let state = TimerState {
+            session_type: "work".to_string(),
+            running: true,
+            remaining_seconds: 300,
+            saved_at: 2000,
+            started_at: 800,
+            elapsed_seconds: 1200,
+            tags: vec!["Study/Math".to_string(), "Work".to_string()],
+        };
+        db.save_timer_state(Some(&state)).unwrap();
+        assert_eq!(db.load_timer_state(), Some(state));
+        
+        // The older four-field form still loads, with nothing to log
+        db.set_config("timer_state", "break|0|120|2000").unwrap();
+        let legacy = db.load_timer_state().unwrap();
+        assert_eq!((legacy.started_at, legacy.elapsed_seconds), (2000, 0));
+        assert!(legacy.tags.is_empty());
+    }
+
+    #[test]
+    fn test_rollover_hour_moves_late_sessions_to_the_previous_day() {
+        let mut db = Database::open_in_memory().unwrap();
+        let day = NaiveDate::from_ymd_opt(2024, 6, 10).unwrap();
+        let at = |hour| day.and_hms_opt(hour, 30, 0).unwrap().and_local_timezone(Local).unwrap();
+        db.save_session(&at(1), &at(2), 1500, &["Work"], "work").unwrap();
+        db.save_session(&at(9), &at(10), 1500, &["Work"], "work").unwrap();
+        let next_day = day.succ_opt().unwrap();
+        
+        assert_eq!(db.get_daily_totals(day.pred_opt().unwrap(), next_day).unwrap(), vec![(day, 3000)]);
+        
+        db.set_rollover_hour(4).unwrap();
+        assert_eq!(
+            db.get_daily_totals(day.pred_opt().unwrap(), next_day).unwrap(),
+            vec![(day.pred_opt().unwrap(), 1500), (day, 1500)]
+        );
+    }
+
+    #[test]
+    fn test_hourly_distribution_spreads_sessions_over_hours() {
+        let db = Database::open_in_memory().unwrap();
+        let at = |h, m| Local.with_ymd_and_hms(2024, 6, 10, h, m, 0).unwrap();
+        db.save_session(&at(9, 40), &at(10, 30), 3000, &["Study"], "work").unwrap();
+        db.save_session(&at(23, 30), &(at(23, 30) + chrono::Duration::hours(1)), 3600, &["Work"], "work").unwrap();
+        db.save_session(&at(12, 0), &at(12, 10), 600, &["Study"], "break").unwrap();
+        
+        let all = db.get_hourly_distribution(None).unwrap();
+        assert_eq!((all[9], all[10], all[23], all[0]), (1200, 1800, 1800, 1800));
+        assert_eq!(all[12], 0);
+        assert_eq!(db.get_hourly_distribution(Some("Study")).unwrap().iter().sum::<i64>(), 3000);
+    }
+
+    #[test]
+    fn test_merge_tags_moves_sessions_and_target() {
+        let db = Database::open_in_memory().unwrap();
+        db.add_tag("Read").unwrap();
+        db.add_tag("Reading").unwrap();
+        db.set_tag_weekly_target("Read", Some(120)).unwrap();
+        let end = Local::now();
+        let start = end - chrono::Duration::minutes(25);
+        db.save_session(&start, &end, 1500, &["Read"], "work").unwrap();
+        db.save_session(&start, &end, 1500, &["Read", "Reading"], "work").unwrap();
+        db.save_session(&start, &end, 1500, &["Study", "Read"], "work").unwrap();
+        
+        db.set_config("default_tag", "Read").unwrap();
+        db.set_config("selected_tag", "Study").unwrap();
+        
+        // Every session of "Read" counts, including the one already tagged "Reading"
+        assert_eq!(db.merge_tags("Read", "Reading").unwrap(), 3);
+        assert_eq!(db.get_config("default_tag", ""), "Reading");
+        assert_eq!(db.get_config("selected_tag", ""), "Study");
+        assert!(!db.get_all_tags().unwrap().contains(&"Read".to_string()));
+        assert_eq!(db.get_tag_weekly_target("Reading"), Some(120));
+        let total = |tag| db.get_yearly_stats(Some(tag)).unwrap().iter().map(|(_, s)| s).sum::<i64>();
+        assert_eq!((total("Reading"), total("Read"), total("Study")), (4500, 0, 1500));
+        assert_eq!(db.get_all_sessions().unwrap()[0].tag, "Reading");
+    }
+
+    #[test]
+    fn test_sessions_in_bucket() {
+        let db = Database::open_in_memory().unwrap();
+        let at = |m, d| Local.with_ymd_and_hms(2024, m, d, 9, 0, 0).unwrap();
+        for (start, tag) in [(at(6, 10), "Math"), (at(6, 10), "Art"), (at(6, 11), "Math"), (at(7, 1), "Math")] {
+            db.save_session(&start, &(start + chrono::Duration::minutes(25)), 1500, &[tag], "work").unwrap();
+        }
+        db.save_session(&at(6, 10), &(at(6, 10) + chrono::Duration::minutes(5)), 300, &["Math"], "break").unwrap();
+        
+        let count = |bucket, tag| db.get_sessions_in_bucket(bucket, tag).unwrap().len();
+        assert_eq!(count("2024-06-10", None), 2);
+        assert_eq!(count("2024-06", None), 3);
+        assert_eq!(count("2024-06", Some("Math")), 2);
+        assert_eq!(count("2024", Some("Math")), 3);
+    }
+
+    #[test]
+    fn test_purge_sessions_before() {
+        let db = Database::open_in_memory().unwrap();
+        let at = |d| Local.with_ymd_and_hms(2024, 6, d, 9, 0, 0).unwrap();
+        for (d, session_type) in [(1, "work"), (2, "break"), (3, "work")] {
+            db.save_session(&at(d), &(at(d) + chrono::Duration::minutes(25)), 1500, &["Math", "Art"], session_type).unwrap();
+        }
+        let cutoff = NaiveDate::from_ymd_opt(2024, 6, 3).unwrap();
+        
+        assert_eq!(db.count_sessions_before(cutoff), 2);
+        assert_eq!(db.purge_sessions_before(cutoff).unwrap(), 2);
+        assert_eq!(db.count_sessions_before(cutoff), 0);
+        assert_eq!(db.get_all_sessions().unwrap().len(), 1);
+        let orphans: i64 = db.conn.query_row(
+            "SELECT COUNT(*) FROM session_tags WHERE session_id NOT IN (SELECT id FROM sessions)",
+            [],
+            |row| row.get(0),
+        ).unwrap();
+        assert_eq!(orphans, 0);
+        db.vacuum().unwrap();
+    }
+
+    #[test]
+    fn test_average_rating() {
+        let db = Database::open_in_memory().unwrap();
+        let at = |d| Local.with_ymd_and_hms(2024, 6, d, 9, 0, 0).unwrap();
+        let save = |d, tag| db.save_session(&at(d), &(at(d) + chrono::Duration::minutes(25)), 1500, &[tag], "work").unwrap();
+        let rated = [(save(10, "Math"), 5), (save(10, "Art"), 2), (save(11, "Math"), 4)];
+        save(11, "Math"); // Unrated, so left out of the average
+        for (id, rating) in rated {
+            db.set_session_rating(id, rating).unwrap();
+        }
+        
+        assert_eq!(db.get_average_rating("2024-06-10", None), Some(3.5));
+        assert_eq!(db.get_average_rating("2024-06", Some("Math")), Some(4.5));
+        assert_eq!(db.get_average_rating("2024-07", None), None);
+    }
+
+    #[test]
+    fn test_session_tags_backfilled_once() {
+        let db = Database::open_in_memory().unwrap();
+        let insert_old_session = || db.conn.execute(
+            "INSERT INTO sessions (start_time, end_time, duration, tag, type)
+             VALUES ('2024-06-03 09:00:00', '2024-06-03 09:25:00', 1500, 'Math', 'work')",
+            [],
+        ).unwrap();
+        let tagged = || db.conn.query_row("SELECT COUNT(*) FROM session_tags", [], |row| row.get::<_, i64>(0)).unwrap();
+        
+        // A database from before multi-tag support gets its primary tags copied
+        insert_old_session();
+        db.conn.execute("DELETE FROM config WHERE key = 'session_tags_backfilled'", []).unwrap();
+        db.initialize_schema().unwrap();
+        assert_eq!(tagged(), 1);
+        
+        // Later starts leave `session_tags` alone
+        insert_old_session();
+        db.initialize_schema().unwrap();
+        assert_eq!(tagged(), 1);
+    }
+
+    #[test]
+    fn test_work_since_break_counts_only_the_last_hour() {
+        let db = Database::open_in_memory().unwrap();
+        let now = Local::now();
+        let minutes = chrono::Duration::minutes;
+        let within = |seconds: i64, expected: i64| (expected - 1..=expected + 1).contains(&seconds);
+        
+        // Only the last 50 of these 90 minutes fall inside the hour
+        db.save_session(&(now - minutes(100)), &(now - minutes(10)), 5400, &["Work"], "work").unwrap();
+        db.save_session(&(now - minutes(120)), &(now - minutes(70)), 3000, &["Work"], "work").unwrap();
+        assert!(within(db.get_work_since_break_last_hour(), 3000));
+        
+        // ...and only the part after a break
+        db.save_session(&(now - minutes(35)), &(now - minutes(30)), 300, &["Work"], "break").unwrap();
+        db.save_session(&(now - minutes(30)), &(now - minutes(5)), 1500, &["Work"], "work").unwrap();
+        assert!(within(db.get_work_since_break_last_hour(), 1500 + 1200));
+    }
+
+    #[test]
+    fn test_week_total_for_tag_counts_from_week_start() {
+        let db = Database::open_in_memory().unwrap();
+        let today = db.today();
+        let at = |day: NaiveDate| day.and_hms_opt(12, 0, 0).unwrap().and_local_timezone(Local).unwrap();
+        let yesterday = today - chrono::Duration::days(1);
+        db.save_session(&at(today), &(at(today) + chrono::Duration::minutes(25)), 1500, &["Math"], "work").unwrap();
+        db.save_session(&at(yesterday), &(at(yesterday) + chrono::Duration::minutes(10)), 600, &["Math"], "work").unwrap();
+        
+        assert_eq!(db.get_week_total_for_tag("Math", today), 1500);
+        assert_eq!(db.get_week_total_for_tag("Math", yesterday), 2100);
+    }
+
+    #[test]
+    fn test_last_session_per_tag() {
+        let db = Database::open_in_memory().unwrap();
+        let day = |d| Local.with_ymd_and_hms(2024, 6, d, 9, 0, 0).unwrap();
+        db.save_session(&day(3), &(day(3) + chrono::Duration::minutes(25)), 1500, &["Math"], "work").unwrap();
+        db.save_session(&day(5), &(day(5) + chrono::Duration::minutes(25)), 1500, &["Math", "Art"], "work").unwrap();
+        db.save_session(&day(6), &(day(6) + chrono::Duration::minutes(5)), 300, &["Art"], "break").unwrap();
+        
+        let last = db.get_last_session_per_tag().unwrap();
+        assert_eq!(last.get("Math").map(String::as_str), Some("2024-06-05 09:00:00"));
+        assert_eq!(last.get("Art").map(String::as_str), Some("2024-06-05 09:00:00"));
+        assert!(!last.contains_key("Work"));
+    }
+
+    #[test]
+    fn test_goal_met_days() {
+        let db = Database::open_in_memory().unwrap();
+        let today = Local::now().date_naive().and_hms_opt(9, 0, 0).unwrap().and_local_timezone(Local).unwrap();
+        let yesterday = today - chrono::Duration::days(1);
+        let long_ago = today - chrono::Duration::days(40);
+        for start in [today, today, yesterday, long_ago] {
+            db.save_session(&start, &(start + chrono::Duration::minutes(30)), 1800, &["Work"], "work").unwrap();
+        }
+        db.save_session(&yesterday, &yesterday, 3600, &["Work"], "break").unwrap();
+        
+        assert_eq!(db.get_goal_met_days(60, 30).unwrap(), vec![today.date_naive()]);
+        assert_eq!(db.get_goal_met_days(30, 30).unwrap(), vec![yesterday.date_naive(), today.date_naive()]);
+        assert_eq!(db.get_goal_met_days(30, 60).unwrap().len(), 3);
+    }
+
+    #[test]
+    fn test_sub_tags_roll_up_into_their_category() {
+        let db = Database::open_in_memory().unwrap();
+        let end = Local::now();
+        let start = end - chrono::Duration::minutes(25);
+        db.save_session(&start, &end, 1500, &["Study/Math"], "work").unwrap();
+        db.save_session(&start, &end, 600, &["Study/Math", "Study/Physics"], "work").unwrap();
+        db.save_session(&start, &end, 300, &["Study"], "work").unwrap();
+        db.save_session(&start, &end, 60, &["Studying"], "work").unwrap();
+        
+        let work = |tag| db.get_work_break_totals(None, Some(tag)).unwrap().0;
+        assert_eq!(work("Study"), 1500 + 600 + 300);
+        assert_eq!(work("Study/Math"), 1500 + 600);
+        assert_eq!(work("Study/Physics"), 600);
+        
+        let categories: Vec<(String, i64)> = db.get_yearly_stats_by_tag(true).unwrap()
+            .into_iter().map(|(_, tag, total)| (tag, total)).collect();
+        assert_eq!(categories, vec![("Study".to_string(), 2400), ("Studying".to_string(), 60)]);
+    }
+
+    #[test]
+    fn test_sessions_past_midnight_split_between_days() {
+        let mut db = Database::open_in_memory().unwrap();
+        let day = |d| NaiveDate::from_ymd_opt(2024, 6, d).unwrap();
+        let start = Local.with_ymd_and_hms(2024, 6, 10, 23, 50, 0).unwrap();
+        let end = Local.with_ymd_and_hms(2024, 6, 11, 0, 5, 0).unwrap();
+        db.save_session(&start, &end, 900, &["Work"], "work").unwrap();
+        
+        // 10 of the 15 minutes fell before midnight; the session itself still starts on the 10th
+        assert_eq!(db.get_daily_totals(day(10), day(11)).unwrap(), vec![(day(10), 600), (day(11), 300)]);
+        assert_eq!(db.get_tag_totals(day(11), day(11)).unwrap(), vec![("Work".to_string(), 300)]);
+        assert_eq!(db.get_session_count_between(day(11), day(11)), 0);
+        
+        // Past the rollover hour, the whole session belongs to the 10th
+        db.set_rollover_hour(4).unwrap();
+        assert_eq!(db.get_daily_totals(day(10), day(11)).unwrap(), vec![(day(10), 900)]);
+        
+        // Years and weekdays split the same way, from Sunday the 31st into Monday
+        let db = Database::open_in_memory().unwrap();
+        let start = Local.with_ymd_and_hms(2023, 12, 31, 23, 50, 0).unwrap();
+        let end = Local.with_ymd_and_hms(2024, 1, 1, 0, 5, 0).unwrap();
+        db.save_session(&start, &end, 900, &["Work"], "work").unwrap();
+        assert_eq!(db.get_yearly_stats(None).unwrap(), vec![("2023".to_string(), 600), ("2024".to_string(), 300)]);
+        assert_eq!(db.get_weekday_totals(Some("Work")).unwrap().0, [300, 0, 0, 0, 0, 0, 600]);
+    }
+
+    #[test]
+    fn test_clamped_config_repairs_bad_values() {
+        let db = Database::open_in_memory().unwrap();
+        db.set_config("work_duration", "-5").unwrap();
+        db.set_config("break_duration", "abc").unwrap();
+        db.set_config("long_break_duration", "999999").unwrap();
+        
+        assert_eq!(db.get_config_clamped("work_duration", 1500, 60..=7200), 1500);
+        assert_eq!(db.get_config_clamped("break_duration", 300, 60..=3600), 300);
+        assert_eq!(db.get_config_clamped("long_break_duration", 900, 60..=3600), 3600);
+        assert_eq!(db.get_config_clamped("missing", 20, 60..=120), 60);
+        
+        assert_eq!(db.get_config("work_duration", ""), "1500");
+        assert_eq!(db.get_config("break_duration", ""), "300");
+        assert_eq!(db.get_config("long_break_duration", ""), "3600");
+    }
+
+    #[test]
+    fn test_group_into_blocks() {
+        let db = Database::open_in_memory().unwrap();
+        let at = |h, m| Local.with_ymd_and_hms(2024, 6, 10, h, m, 0).unwrap();
+        // Two pomodoros with a break between, then a late one, then another tag
+        db.save_session(&at(9, 0), &at(9, 25), 1500, &["Study"], "work").unwrap();
+        db.save_session(&at(9, 25), &at(9, 30), 300, &["Study"], "break").unwrap();
+        db.save_session(&at(9, 30), &at(9, 55), 1500, &["Study"], "work").unwrap();
+        db.save_session(&at(11, 0), &at(11, 25), 1500, &["Study"], "work").unwrap();
+        db.save_session(&at(11, 30), &at(11, 55), 1500, &["Work"], "work").unwrap();
+        
+        let sessions = db.get_recent_sessions(10).unwrap();
+        let blocks: Vec<(String, i64, usize)> = group_into_blocks(&sessions, 20)
+            .into_iter()
+            .map(|block| (block.tag, block.duration, block.sessions))
+            .collect();
+        assert_eq!(blocks, vec![
+            ("Work".to_string(), 1500, 1),
+            ("Study".to_string(), 1500, 1),
+            ("Study".to_string(), 3000, 2),
+        ]);
+        
+        let first = group_into_blocks(&sessions, 20).pop().unwrap();
+        assert_eq!((first.start_time.as_str(), first.end_time.as_str()), ("2024-06-10 09:00:00", "2024-06-10 09:55:00"));
+        assert_eq!(group_into_blocks(&sessions, 90).len(), 2);
+    }
+}
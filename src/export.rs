@@ -0,0 +1,512 @@
+use chrono::{Datelike, NaiveDate, Weekday};
+use std::collections::HashMap;
+use std::fmt::Write;
+
+use crate::db::{Database, Session};
+use crate::ui::format_duration;
+use crate::ui::heatmap::{build_weeks, day_labels, intensity_level, month_name, HeatMetric, HeatScale};
+
+/// GitHub-style fills, from no activity to busiest
+const SVG_FILLS: [&str; 5] = ["#ebedf0", "#9be9a8", "#40c463", "#30a14e", "#216e39"];
+
+const CELL: usize = 11;
+const STEP: usize = 13;
+const LEFT: usize = 32;
+const TOP: usize = 20;
+
+/// Renders the heatmap as an SVG contribution graph, mirroring the on-screen grid.
+/// `data` holds each day's value in `metric`, as shown on the Heatmap screen.
+pub fn heatmap_svg(data: &[(NaiveDate, i64)], today: NaiveDate, week_start: Weekday, scale: HeatScale, metric: HeatMetric) -> String {
+    let data_map: HashMap<NaiveDate, i64> = data.iter().copied().collect();
+    let max_value = data_map.values().copied().max().unwrap_or(1).max(1);
+    let weeks = build_weeks(&data_map, today, week_start);
+
+    let width = LEFT + weeks.len() * STEP;
+    let height = TOP + 7 * STEP;
+    let mut svg = String::new();
+    let _ = writeln!(
+        svg,
+        r##"<svg xmlns="http://www.w3.org/2000/svg" width="{}" height="{}" font-family="sans-serif" font-size="9" fill="#57606a">"##,
+        width, height
+    );
+
+    // Day labels
+    for (day_idx, day_name) in day_labels(week_start).iter().enumerate() {
+        let _ = writeln!(svg, r#"  <text x="0" y="{}">{}</text>"#, TOP + day_idx * STEP + 9, day_name);
+    }
+
+    let mut last_month = 0u32;
+    for (week_idx, week) in weeks.iter().enumerate() {
+        let x = LEFT + week_idx * STEP;
+
+        // Month labels above the first week of each month
+        if let Some(Some((date, _))) = week.first() {
+            if date.month() != last_month {
+                let _ = writeln!(svg, r#"  <text x="{}" y="12">{}</text>"#, x, month_name(date.month()));
+                last_month = date.month();
+            }
+        }
+
+        for (day_idx, day) in week.iter().enumerate() {
+            if let Some((date, value)) = day {
+                let fill = SVG_FILLS[intensity_level(*value, max_value, scale)];
+                let unit = match metric {
+                    HeatMetric::Minutes => "min",
+                    HeatMetric::Sessions => "sessions",
+                };
+                let _ = writeln!(
+                    svg,
+                    r#"  <rect x="{}" y="{}" width="{}" height="{}" rx="2" fill="{}"><title>{}: {} {}</title></rect>"#,
+                    x, TOP + day_idx * STEP, CELL, CELL, fill, date.format("%Y-%m-%d"), value, unit
+                );
+            }
+        }
+    }
+
+    svg.push_str("</svg>\n");
+    svg
+}
+
+/// Markdown summary of the work logged between `from` and `to` (inclusive).
+/// `daily` and `tags` hold work seconds; `goal_minutes` of 0 leaves out goal adherence.
+pub fn weekly_digest(
+    from: NaiveDate,
+    to: NaiveDate,
+    daily: &[(NaiveDate, i64)],
+    tags: &[(String, i64)],
+    notes: &[Session],
+    streak: u32,
+    goal_minutes: u64,
+) -> String {
+    let daily_map: HashMap<NaiveDate, i64> = daily.iter().copied().collect();
+    let days: Vec<NaiveDate> = from.iter_days().take_while(|day| *day <= to).collect();
+    let total: i64 = daily.iter().map(|(_, seconds)| seconds).sum();
+    let goal_met = |seconds: i64| goal_minutes > 0 && seconds >= goal_minutes as i64 * 60;
+    
+    let mut md = String::new();
+    let _ = writeln!(md, "# Pomodoro++ digest: {} to {}\n", from, to);
+    let _ = writeln!(md, "- Total focus time: {}", format_duration(total));
+    let _ = writeln!(md, "- Days worked: {} of {}", daily.iter().filter(|(_, s)| *s > 0).count(), days.len());
+    let _ = writeln!(md, "- Current streak: {} day{}", streak, if streak == 1 { "" } else { "s" });
+    if goal_minutes > 0 {
+        let met = days.iter().filter(|day| goal_met(*daily_map.get(day).unwrap_or(&0))).count();
+        let _ = writeln!(md, "- Daily goal ({} min) met on {} of {} days", goal_minutes, met, days.len());
+    }
+    
+    md.push_str("\n## By tag\n\n");
+    if tags.is_empty() {
+        md.push_str("No sessions logged.\n");
+    } else {
+        md.push_str("| Tag | Time |\n|-----|------|\n");
+        for (tag, seconds) in tags {
+            let _ = writeln!(md, "| {} | {} |", tag, format_duration(*seconds));
+        }
+    }
+    
+    md.push_str("\n## By day\n\n| Day | Time |\n|-----|------|\n");
+    for day in &days {
+        let seconds = *daily_map.get(day).unwrap_or(&0);
+        let mark = if goal_met(seconds) { " ✔" } else { "" };
+        let _ = writeln!(md, "| {} | {}{} |", day.format("%a %Y-%m-%d"), format_duration(seconds), mark);
+    }
+    
+    if !notes.is_empty() {
+        md.push_str("\n## Notes\n\n");
+        for session in notes {
+            let when = session.start_time.get(..16).unwrap_or(&session.start_time);
+            let _ = writeln!(md, "- {} {}: {}", when, session.tag, session.note.as_deref().unwrap_or(""));
+        }
+    }
+    md
+}
+
+/// Days off that don't break a streak
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct RestDays {
+    pub weekdays: Vec<Weekday>, // Planned days off, e.g. weekends
+    pub freezes: u32,           // Other missed days a streak survives
+}
+
+impl RestDays {
+    pub fn load(db: &Database) -> Self {
+        RestDays {
+            weekdays: parse_rest_days(&db.get_config("rest_days", "")).unwrap_or_default(),
+            freezes: db.get_config("streak_freezes", "0").parse().unwrap_or(0),
+        }
+    }
+}
+
+/// Parses weekday names such as "sat,sun" or "Friday"; empty means none
+pub fn parse_rest_days(input: &str) -> Result<Vec<Weekday>, &'static str> {
+    let mut weekdays = Vec::new();
+    for name in input.split(',').map(str::trim).filter(|name| !name.is_empty()) {
+        let weekday: Weekday = name.parse().map_err(|_| "Use weekday names, e.g. sat,sun")?;
+        if !weekdays.contains(&weekday) {
+            weekdays.push(weekday);
+        }
+    }
+    weekdays.sort_by_key(|weekday| weekday.num_days_from_monday());
+    Ok(weekdays)
+}
+
+/// Consecutive days with work, counting back from `today` (or yesterday when
+/// nothing has been logged yet today). Rest weekdays without work are skipped
+/// over, as are up to `rest.freezes` other missed days.
+pub fn current_streak(worked_days: &[NaiveDate], today: NaiveDate, rest: &RestDays) -> u32 {
+    let Some(first) = worked_days.iter().min() else { return 0 };
+    let mut day = today;
+    if !worked_days.contains(&day) {
+        day = day.pred_opt().unwrap_or(day);
+    }
+    let mut streak = 0;
+    let mut freezes = rest.freezes;
+    while day >= *first {
+        if worked_days.contains(&day) {
+            streak += 1;
+        } else if rest.weekdays.contains(&day.weekday()) {
+            // A planned day off neither counts nor breaks the streak
+        } else if freezes > 0 {
+            freezes -= 1;
+        } else {
+            break;
+        }
+        day = match day.pred_opt() {
+            Some(prev) => prev,
+            None => break,
+        };
+    }
+    streak
+}
+
+/// File format for exporting every session
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ExportFormat {
+    Csv,
+    Json,
+    Markdown,
+}
+
+impl ExportFormat {
+    pub fn name(&self) -> &'static str {
+        match self {
+            ExportFormat::Csv => "CSV",
+            ExportFormat::Json => "JSON",
+            ExportFormat::Markdown => "Markdown",
+        }
+    }
+    
+    pub fn extension(&self) -> &'static str {
+        match self {
+            ExportFormat::Csv => "csv",
+            ExportFormat::Json => "json",
+            ExportFormat::Markdown => "md",
+        }
+    }
+    
+    /// Looks a format up by its file extension, falling back to CSV
+    pub fn from_extension(extension: &str) -> Self {
+        match extension {
+            "json" => ExportFormat::Json,
+            "md" => ExportFormat::Markdown,
+            _ => ExportFormat::Csv,
+        }
+    }
+    
+    pub fn next(&self) -> Self {
+        match self {
+            ExportFormat::Csv => ExportFormat::Json,
+            ExportFormat::Json => ExportFormat::Markdown,
+            ExportFormat::Markdown => ExportFormat::Csv,
+        }
+    }
+    
+    /// The sessions written out in this format
+    pub fn render(&self, sessions: &[Session]) -> String {
+        match self {
+            ExportFormat::Csv => sessions_csv(sessions),
+            ExportFormat::Json => sessions_json(sessions),
+            ExportFormat::Markdown => sessions_markdown(sessions),
+        }
+    }
+}
+
+/// Sessions as a JSON array, with each session's tags as a list
+fn sessions_json(sessions: &[Session]) -> String {
+    let mut json = String::from("[");
+    for (i, session) in sessions.iter().enumerate() {
+        let tags: Vec<String> = session.tag.split(" + ").map(json_string).collect();
+        let note = session.note.as_deref().map(json_string).unwrap_or_else(|| "null".to_string());
+        let _ = write!(
+            json,
+            "{}\n  {{\"start_time\": {}, \"end_time\": {}, \"duration\": {}, \"tags\": [{}], \"type\": {}, \"note\": {}}}",
+            if i == 0 { "" } else { "," },
+            json_string(&session.start_time),
+            json_string(&session.end_time),
+            session.duration,
+            tags.join(", "),
+            json_string(&session.session_type),
+            note,
+        );
+    }
+    json.push_str(if sessions.is_empty() { "]\n" } else { "\n]\n" });
+    json
+}
+
+/// Quotes a JSON string, escaping quotes, backslashes and control characters
+fn json_string(value: &str) -> String {
+    let mut quoted = String::from("\"");
+    for c in value.chars() {
+        match c {
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            '\n' => quoted.push_str("\\n"),
+            '\r' => quoted.push_str("\\r"),
+            '\t' => quoted.push_str("\\t"),
+            c if c.is_control() => {
+                let _ = write!(quoted, "\\u{:04x}", c as u32);
+            }
+            c => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
+/// Sessions as a Markdown table, with durations written out for reading
+fn sessions_markdown(sessions: &[Session]) -> String {
+    let mut md = String::from("# Pomodoro++ sessions\n\n");
+    if sessions.is_empty() {
+        md.push_str("No sessions logged.\n");
+        return md;
+    }
+    md.push_str("| Start | End | Duration | Tags | Type | Note |\n|-------|-----|----------|------|------|------|\n");
+    // Pipes would end a cell early and line breaks would end the row
+    let cell = |value: &str| value.replace('|', "\\|").replace(['\r', '\n'], " ");
+    for session in sessions {
+        let _ = writeln!(
+            md,
+            "| {} | {} | {} | {} | {} | {} |",
+            session.start_time,
+            session.end_time,
+            format_duration(session.duration),
+            cell(&session.tag),
+            session.session_type,
+            cell(session.note.as_deref().unwrap_or("")),
+        );
+    }
+    md
+}
+
+/// Column order of the sessions CSV, also written as its header row
+const CSV_HEADER: [&str; 6] = ["start_time", "end_time", "duration", "tags", "type", "note"];
+
+/// A session read back from CSV, before it is inserted
+pub struct CsvSession {
+    pub start_time: String,
+    pub end_time: String,
+    pub duration: i64,
+    pub tags: Vec<String>,
+    pub session_type: String,
+    pub note: Option<String>,
+}
+
+/// Sessions as CSV with a header row. Multiple tags share one column, joined by " + ".
+fn sessions_csv(sessions: &[Session]) -> String {
+    let mut csv = CSV_HEADER.join(",");
+    csv.push('\n');
+    for session in sessions {
+        let fields = [
+            session.start_time.clone(),
+            session.end_time.clone(),
+            session.duration.to_string(),
+            session.tag.clone(),
+            session.session_type.clone(),
+            session.note.clone().unwrap_or_default(),
+        ];
+        let fields: Vec<String> = fields.iter().map(|field| csv_field(field)).collect();
+        csv.push_str(&fields.join(","));
+        csv.push('\n');
+    }
+    csv
+}
+
+/// Quotes a field when it contains a separator, quote or line break
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+/// Reads sessions written by `sessions_csv`. The header row is optional and
+/// rows with missing or invalid fields are skipped.
+pub fn parse_sessions_csv(text: &str) -> Vec<CsvSession> {
+    parse_csv(text)
+        .into_iter()
+        .filter(|record| record.first().map(String::as_str) != Some(CSV_HEADER[0]))
+        .filter_map(|record| parse_session_record(&record))
+        .collect()
+}
+
+fn parse_session_record(record: &[String]) -> Option<CsvSession> {
+    let [start_time, end_time, duration, tags, session_type, rest @ ..] = record else {
+        return None;
+    };
+    for timestamp in [start_time, end_time] {
+        chrono::NaiveDateTime::parse_from_str(timestamp, "%Y-%m-%d %H:%M:%S").ok()?;
+    }
+    let duration: i64 = duration.trim().parse().ok().filter(|seconds| *seconds >= 0)?;
+    if session_type != "work" && session_type != "break" {
+        return None;
+    }
+    let tags: Vec<String> = tags
+        .split(" + ")
+        .map(|tag| tag.trim().to_string())
+        .filter(|tag| !tag.is_empty())
+        .collect();
+    if tags.is_empty() {
+        return None;
+    }
+    
+    Some(CsvSession {
+        start_time: start_time.clone(),
+        end_time: end_time.clone(),
+        duration,
+        tags,
+        session_type: session_type.clone(),
+        note: rest.first().filter(|note| !note.is_empty()).cloned(),
+    })
+}
+
+/// Splits CSV text into records, honouring quoted fields with embedded
+/// separators, doubled quotes and line breaks. Blank lines are dropped.
+fn parse_csv(text: &str) -> Vec<Vec<String>> {
+    let mut records = Vec::new();
+    let mut record = Vec::new();
+    let mut field = String::new();
+    let mut in_quotes = false;
+    let mut chars = text.chars().peekable();
+    
+    while let Some(c) = chars.next() {
+        match (c, in_quotes) {
+            ('"', true) if chars.peek() == Some(&'"') => {
+                field.push('"');
+                chars.next();
+            }
+            ('"', _) => in_quotes = !in_quotes,
+            (',', false) => record.push(std::mem::take(&mut field)),
+            ('\r', false) => {}
+            ('\n', false) => {
+                record.push(std::mem::take(&mut field));
+                if record.iter().any(|f| !f.is_empty()) {
+                    records.push(std::mem::take(&mut record));
+                }
+                record.clear();
+            }
+            _ => field.push(c),
+        }
+    }
+    record.push(field);
+    if record.iter().any(|f| !f.is_empty()) {
+        records.push(record);
+    }
+    records
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2024, 6, day).unwrap()
+    }
+
+    #[test]
+    fn test_current_streak() {
+        let worked = [date(8), date(9), date(10), date(12)];
+        let none = RestDays::default();
+        assert_eq!(current_streak(&worked, date(12), &none), 1);
+        assert_eq!(current_streak(&worked, date(11), &none), 3);
+        assert_eq!(current_streak(&worked, date(14), &none), 0);
+        assert_eq!(current_streak(&[], date(14), &none), 0);
+    }
+
+    #[test]
+    fn test_streak_skips_rest_days() {
+        // Thursday and Friday, a weekend off, then Monday
+        let worked = [date(6), date(7), date(10)];
+        let weekends = RestDays { weekdays: parse_rest_days("sat, Sunday").unwrap(), freezes: 0 };
+        assert_eq!(weekends.weekdays, [Weekday::Sat, Weekday::Sun]);
+        assert_eq!(current_streak(&worked, date(10), &RestDays::default()), 1);
+        assert_eq!(current_streak(&worked, date(10), &weekends), 3);
+        // A missed Tuesday still ends it
+        assert_eq!(current_streak(&worked, date(12), &weekends), 0);
+        
+        let one_freeze = RestDays { weekdays: Vec::new(), freezes: 1 };
+        assert_eq!(current_streak(&[date(9), date(11), date(12)], date(12), &one_freeze), 3);
+        assert_eq!(current_streak(&[date(8), date(11), date(12)], date(12), &one_freeze), 2);
+        assert!(parse_rest_days("weekend").is_err());
+    }
+
+    #[test]
+    fn test_weekly_digest() {
+        let daily = [(date(10), 3000), (date(12), 1500)];
+        let tags = [("Work".to_string(), 4500)];
+        let notes = [Session {
+            id: 1,
+            start_time: "2024-06-10 09:00:00".to_string(),
+            end_time: "2024-06-10 09:25:00".to_string(),
+            duration: 1500,
+            tag: "Work".to_string(),
+            session_type: "work".to_string(),
+            note: Some("Outlined the report".to_string()),
+        }];
+        let md = weekly_digest(date(6), date(12), &daily, &tags, &notes, 1, 45);
+        
+        assert!(md.starts_with("# Pomodoro++ digest: 2024-06-06 to 2024-06-12\n"));
+        assert!(md.contains("- Total focus time: 1h 15m\n"));
+        assert!(md.contains("- Daily goal (45 min) met on 1 of 7 days\n"));
+        assert!(md.contains("| Work | 1h 15m |\n"));
+        assert!(md.contains("| Mon 2024-06-10 | 50m ✔ |\n"));
+        assert!(md.contains("| Sat 2024-06-08 | 0m |\n"));
+        assert!(md.ends_with("## Notes\n\n- 2024-06-10 09:00 Work: Outlined the report\n"));
+    }
+
+    #[test]
+    fn test_parse_sessions_csv_without_header_skips_bad_rows() {
+        let text = "2024-06-10 09:00:00,2024-06-10 09:25:00,1500,Study + Math,work,\"a, b\"\n\
+                    not a date,2024-06-10 09:25:00,1500,Work,work,\n\
+                    2024-06-10 09:25:00,2024-06-10 09:30:00,300,Work,break\n";
+        let sessions = parse_sessions_csv(text);
+        assert_eq!(sessions.len(), 2);
+        assert_eq!(sessions[0].tags, ["Study", "Math"]);
+        assert_eq!(sessions[0].note.as_deref(), Some("a, b"));
+        assert_eq!(sessions[1].session_type, "break");
+        assert_eq!(sessions[1].note, None);
+    }
+
+    #[test]
+    fn test_export_formats() {
+        let sessions = vec![Session {
+            id: 1,
+            start_time: "2024-06-10 09:00:00".to_string(),
+            end_time: "2024-06-10 09:25:00".to_string(),
+            duration: 1500,
+            tag: "Study + Math".to_string(),
+            session_type: "work".to_string(),
+            note: Some("said \"hi\" | left".to_string()),
+        }];
+        
+        let json = ExportFormat::Json.render(&sessions);
+        assert!(json.contains("\"tags\": [\"Study\", \"Math\"]"));
+        assert!(json.contains("\"note\": \"said \\\"hi\\\" | left\""));
+        assert_eq!(ExportFormat::Json.render(&[]), "[]\n");
+        
+        let md = ExportFormat::Markdown.render(&sessions);
+        assert!(md.contains("| 2024-06-10 09:00:00 | 2024-06-10 09:25:00 | 25m | Study + Math | work | said \"hi\" \\| left |"));
+        
+        assert!(ExportFormat::Csv.render(&sessions).starts_with("start_time,end_time,duration,tags,type,note\n"));
+        assert_eq!(ExportFormat::Markdown.next().next(), ExportFormat::Json);
+    }
+}
@@ -0,0 +1,165 @@
+use ratatui::symbols::bar;
+
+/// Emoji, markers and shading used across the screens, so limited terminals
+/// can swap them all for plain ASCII at once
+#[derive(Debug, Clone)]
+pub struct Glyphs {
+    pub app: &'static str,          // Home title
+    pub clock: &'static str,
+    pub timer: &'static str,        // Settings bar
+    pub work: &'static str,
+    pub short_break: &'static str,
+    pub long_break: &'static str,
+    pub pomodoro: &'static str,     // Pomodoro goal count
+    pub tag: &'static str,
+    pub silent: &'static str,
+    pub sound: &'static str,
+    pub incognito: &'static str,
+    pub lock: &'static str,
+    pub warning: &'static str,
+    pub celebrate: &'static str,
+    pub milestone: &'static str,
+    pub stats: &'static str,        // Screen titles
+    pub calendar: &'static str,
+    pub settings: &'static str,
+    pub review: &'static str,
+    pub running: &'static str,
+    pub paused: &'static str,
+    pub stopped: &'static str,
+    pub pointer: &'static str,      // Selected list row
+    pub check: &'static str,
+    pub dot: &'static str,
+    pub hollow_dot: &'static str,
+    pub up: &'static str,
+    pub down: &'static str,
+    pub left: &'static str,
+    pub right: &'static str,
+    pub square: &'static str,       // Chart legends
+    pub goal: &'static str,         // Heatmap days that met the daily goal
+    pub star: &'static str,         // Focus ratings
+    pub full: &'static str,         // Solid bar cell
+    pub empty: &'static str,        // Empty bar cell
+    pub heat: [&'static str; 5],    // Heatmap intensity, from empty to busiest
+    pub braille: [&'static str; 3], // Braille progress bar: full, half, empty
+    pub line: [&'static str; 2],    // Countdown line: remaining, elapsed
+    pub sub_tag: &'static str,      // Sub-tags indented under their category
+    pub bars: bar::Set,             // Bar chart cells
+}
+
+impl Glyphs {
+    pub const UNICODE: Glyphs = Glyphs {
+        app: "🍅",
+        clock: "🕒",
+        timer: "⏱ ",
+        work: "📚",
+        short_break: "☕",
+        long_break: "🌴",
+        pomodoro: "🍅",
+        tag: "🏷",
+        silent: "🔕",
+        sound: "🔔",
+        incognito: "🕶",
+        lock: "🔒",
+        warning: "⚠",
+        celebrate: "🎉",
+        milestone: "🏅",
+        stats: "📊",
+        calendar: "📅",
+        settings: "⚙",
+        review: "🗓",
+        running: "▶",
+        paused: "⏸",
+        stopped: "⏹",
+        pointer: "▶ ",
+        check: "✔",
+        dot: "●",
+        hollow_dot: "○",
+        up: "▲",
+        down: "▼",
+        left: "◀",
+        right: "▶",
+        square: "■",
+        goal: "◆",
+        star: "★",
+        full: "█",
+        empty: "░",
+        heat: ["░", "▒", "▓", "▓", "█"],
+        braille: ["⣿", "⡇", "⣀"],
+        line: ["━", "╌"],
+        sub_tag: "└",
+        bars: bar::NINE_LEVELS,
+    };
+
+    pub const ASCII: Glyphs = Glyphs {
+        app: "(o)",
+        clock: "@",
+        timer: "*",
+        work: "[WORK]",
+        short_break: "[BREAK]",
+        long_break: "[BREAK]",
+        pomodoro: "(o)",
+        tag: "#",
+        silent: "[muted]",
+        sound: "[sound]",
+        incognito: "[incognito]",
+        lock: "[locked]",
+        warning: "!",
+        celebrate: "*",
+        milestone: "*",
+        stats: "#",
+        calendar: "#",
+        settings: "#",
+        review: "#",
+        running: ">",
+        paused: "||",
+        stopped: "[]",
+        pointer: "> ",
+        check: "x",
+        dot: "*",
+        hollow_dot: "o",
+        up: "^",
+        down: "v",
+        left: "<",
+        right: ">",
+        square: "#",
+        goal: "*",
+        star: "*",
+        full: "#",
+        empty: ".",
+        heat: [".", ":", "+", "+", "#"],
+        braille: ["#", ":", "."],
+        line: ["=", "-"],
+        sub_tag: "`-",
+        bars: bar::Set {
+            full: "#",
+            seven_eighths: "#",
+            three_quarters: "#",
+            five_eighths: "=",
+            half: "=",
+            three_eighths: "-",
+            one_quarter: "-",
+            one_eighth: ".",
+            empty: " ",
+        },
+    };
+
+    /// Whether the terminal probably can't show emoji: a bare console `TERM`
+    /// or a locale that isn't UTF-8
+    pub fn terminal_lacks_unicode() -> bool {
+        let term = std::env::var("TERM").unwrap_or_default();
+        if matches!(term.as_str(), "linux" | "dumb" | "vt100" | "vt220") {
+            return true;
+        }
+        let locale = ["LC_ALL", "LC_CTYPE", "LANG"]
+            .iter()
+            .filter_map(|name| std::env::var(name).ok())
+            .find(|value| !value.is_empty());
+        match locale {
+            Some(locale) => {
+                let locale = locale.to_lowercase();
+                !locale.contains("utf-8") && !locale.contains("utf8")
+            }
+            None => false,
+        }
+    }
+}
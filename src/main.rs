@@ -1,10 +1,16 @@
 mod app;
 mod ascii_art;
+mod chime;
+mod cli;
 mod db;
+mod export;
+mod glyphs;
+mod review;
+mod theme;
 mod ui;
 
 use std::io;
-use std::time::{Duration, Instant};
+use std::time::{Duration, Instant, SystemTime};
 
 use crossterm::{
     event::{self, DisableMouseCapture, EnableMouseCapture, Event, KeyCode, KeyEventKind},
@@ -16,6 +22,12 @@ use ratatui::{backend::CrosstermBackend, Terminal};
 use app::{App, InputMode, Screen};
 
 fn main() -> Result<(), Box<dyn std::error::Error>> {
+    // Subcommands run without the TUI
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if let Some((command, rest)) = args.split_first() {
+        return cli::run(command, rest);
+    }
+    
     // Setup terminal
     enable_raw_mode()?;
     let mut stdout = io::stdout();
@@ -23,8 +35,10 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     let backend = CrosstermBackend::new(stdout);
     let mut terminal = Terminal::new(backend)?;
 
-    // Create app
+    // Create app, greeting the user with today's progress, or first with the
+    // session a crash left behind
     let mut app = App::new()?;
+    app.navigate_to(if app.abandoned_session.is_some() { Screen::CatchUp } else { Screen::TodaySummary });
     
     // Run app
     let res = run_app(&mut terminal, &mut app);
@@ -49,71 +63,113 @@ fn run_app<B: ratatui::backend::Backend>(
     terminal: &mut Terminal<B>,
     app: &mut App,
 ) -> io::Result<()> {
-    let tick_rate = Duration::from_millis(100);
     let mut last_tick = Instant::now();
-    let mut second_tracker = Instant::now();
+    let mut second_tracker = SystemTime::now();
 
     loop {
         // Draw UI
+        app.refresh_theme();
+        app.open_queued_prompt();
         terminal.draw(|f| {
             match app.current_screen {
-                Screen::Home | Screen::TagInput | Screen::DeleteConfirm => ui::render_home(f, app),
-                Screen::Stats => ui::render_stats(f, app),
+                Screen::Home
+                | Screen::TagInput
+                | Screen::DeleteConfirm
+                | Screen::NoteInput
+                | Screen::IntentionInput
+                | Screen::ManualEntry
+                | Screen::ArchivedTags
+                | Screen::DurationInput
+                | Screen::TodaySummary
+                | Screen::CatchUp
+                | Screen::Templates
+                | Screen::TemplateInput
+                | Screen::ResetConfirm
+                | Screen::RatingInput
+                | Screen::StatsPeek => {
+                    ui::render_home(f, app)
+                }
+                Screen::Stats | Screen::MergeTags | Screen::BucketSessions | Screen::ExportDone => {
+                    ui::render_stats(f, app)
+                }
                 Screen::Heatmap => ui::render_heatmap(f, app),
-                Screen::Settings => ui::render_home(f, app),
+                Screen::WeekReview => ui::render_week_review(f, app),
+                Screen::Settings | Screen::SettingInput | Screen::BackupDone | Screen::PurgeConfirm => {
+                    ui::render_settings(f, app)
+                }
             }
         })?;
 
-        // Handle input with timeout
+        // Handle input with timeout, polling less often while idle
+        let tick_rate = app.poll_interval();
         let timeout = tick_rate.saturating_sub(last_tick.elapsed());
         if event::poll(timeout)? {
             if let Event::Key(key) = event::read()? {
                 if key.kind == KeyEventKind::Press {
                     handle_key_event(app, key.code);
+                    app.autosave_timer_state();
                 }
             }
         }
 
-        // Timer tick (every second)
-        if second_tracker.elapsed() >= Duration::from_secs(1) {
-            app.tick();
-            second_tracker = Instant::now();
+        // Timer tick (every whole second). Wall-clock time keeps running while
+        // the machine is suspended, unlike `Instant`, so the timer catches up.
+        match second_tracker.elapsed() {
+            Ok(elapsed) if elapsed.as_secs() >= 1 => {
+                app.tick(elapsed.as_secs());
+                app.autosave_timer_state();
+                second_tracker += Duration::from_secs(elapsed.as_secs());
+            }
+            Ok(_) => {}
+            Err(_) => second_tracker = SystemTime::now(), // Clock moved backwards
         }
 
         if last_tick.elapsed() >= tick_rate {
             last_tick = Instant::now();
         }
 
+        // Quitting ends the session, so nothing is left for the status line
         if app.should_quit {
+            app.stop_focus_music();
+            let _ = app.db.save_timer_state(None);
             return Ok(());
         }
     }
 }
 
 fn handle_key_event(app: &mut App, key: KeyCode) {
+    // Feedback messages last until the next key press
+    app.status_message = None;
+    
     // Handle input mode separately
     if app.current_screen == Screen::TagInput {
         match app.input_mode {
             InputMode::Editing => match key {
                 KeyCode::Enter => {
-                    let tag = app.input_buffer.trim().to_string();
-                    if !tag.is_empty() {
-                        app.add_tag(tag);
+                    let tag = app.input_buffer.clone();
+                    match app.add_tag(&tag) {
+                        Ok(()) => {
+                            app.input_buffer.clear();
+                            app.input_error = None;
+                            app.input_mode = InputMode::Normal;
+                            app.current_screen = Screen::Home;
+                        }
+                        Err(message) => app.input_error = Some(message),
                     }
-                    app.input_buffer.clear();
-                    app.input_mode = InputMode::Normal;
-                    app.current_screen = Screen::Home;
                 }
                 KeyCode::Esc => {
                     app.input_buffer.clear();
+                    app.input_error = None;
                     app.input_mode = InputMode::Normal;
                     app.current_screen = Screen::Home;
                 }
                 KeyCode::Backspace => {
                     app.input_buffer.pop();
+                    app.input_error = None;
                 }
                 KeyCode::Char(c) => {
                     app.input_buffer.push(c);
+                    app.input_error = None;
                 }
                 _ => {}
             },
@@ -124,6 +180,92 @@ fn handle_key_event(app: &mut App, key: KeyCode) {
         return;
     }
     
+    // Handle note input after a completed session
+    if app.current_screen == Screen::NoteInput {
+        match key {
+            KeyCode::Enter | KeyCode::Esc => {
+                if key == KeyCode::Enter {
+                    app.save_note();
+                }
+                close_input_popup(app);
+            }
+            _ => edit_input_buffer(app, key),
+        }
+        return;
+    }
+    
+    // Rate the finished session's focus with a single key, or skip it
+    if app.current_screen == Screen::RatingInput {
+        match key {
+            KeyCode::Char(c @ '1'..='5') => app.answer_rating(c.to_digit(10).map(|n| n as u8)),
+            KeyCode::Esc | KeyCode::Enter => app.answer_rating(None),
+            _ => {}
+        }
+        return;
+    }
+    
+    // Answering or skipping the intention prompt starts the session
+    if app.current_screen == Screen::IntentionInput {
+        match key {
+            KeyCode::Enter | KeyCode::Esc => {
+                if key == KeyCode::Esc {
+                    app.input_buffer.clear();
+                }
+                app.start_with_intention();
+                close_input_popup(app);
+            }
+            _ => edit_input_buffer(app, key),
+        }
+        return;
+    }
+    
+    // Handle manually logged sessions
+    if app.current_screen == Screen::ManualEntry {
+        match key {
+            KeyCode::Enter => {
+                let input = app.input_buffer.clone();
+                match app.log_manual_session(&input) {
+                    Ok(()) => close_input_popup(app),
+                    Err(message) => app.input_error = Some(message),
+                }
+            }
+            KeyCode::Esc => close_input_popup(app),
+            _ => edit_input_buffer(app, key),
+        }
+        return;
+    }
+    
+    // Handle typed work/break durations
+    if app.current_screen == Screen::DurationInput {
+        match key {
+            KeyCode::Enter => {
+                let input = app.input_buffer.clone();
+                match app.apply_duration_preset(&input) {
+                    Ok(()) => close_input_popup(app),
+                    Err(message) => app.input_error = Some(message),
+                }
+            }
+            KeyCode::Esc => close_input_popup(app),
+            _ => edit_input_buffer(app, key),
+        }
+        return;
+    }
+    
+    // Confirm throwing away a long session's progress
+    if app.current_screen == Screen::ResetConfirm {
+        match key {
+            KeyCode::Char('y') | KeyCode::Char('Y') => {
+                app.reset_timer();
+                app.current_screen = Screen::Home;
+            }
+            KeyCode::Char('n') | KeyCode::Char('N') | KeyCode::Esc => {
+                app.current_screen = Screen::Home;
+            }
+            _ => {}
+        }
+        return;
+    }
+    
     // Handle delete confirmation
     if app.current_screen == Screen::DeleteConfirm {
         match key {
@@ -139,47 +281,266 @@ fn handle_key_event(app: &mut App, key: KeyCode) {
         return;
     }
 
+    // Handle typed values for text settings
+    if app.current_screen == Screen::SettingInput {
+        match key {
+            KeyCode::Enter => match app.apply_setting_input() {
+                Ok(()) => close_input_popup(app),
+                Err(message) => app.input_error = Some(message),
+            },
+            KeyCode::Esc => close_input_popup(app),
+            _ => edit_input_buffer(app, key),
+        }
+        return;
+    }
+    
+    // Log or drop the session left over from a crash
+    if app.current_screen == Screen::CatchUp {
+        match key {
+            KeyCode::Char('l') | KeyCode::Enter => app.log_abandoned_session(),
+            KeyCode::Char('d') | KeyCode::Esc => app.discard_abandoned_session(),
+            _ => {}
+        }
+        return;
+    }
+    
+    // Any key closes the stats peek
+    if app.current_screen == Screen::StatsPeek {
+        app.toggle_stats_peek();
+        return;
+    }
+    
+    // Any key dismisses the today-at-a-glance card
+    if app.current_screen == Screen::TodaySummary {
+        app.current_screen = Screen::Home;
+        return;
+    }
+    
+    // Any key dismisses the backup confirmation
+    if app.current_screen == Screen::BackupDone {
+        app.current_screen = app.previous_screen;
+        return;
+    }
+    
+    // Any key dismisses the export confirmation
+    if app.current_screen == Screen::ExportDone {
+        app.navigate_to(Screen::Stats);
+        return;
+    }
+    
+    // Purging old sessions can't be undone, so it needs a yes
+    if app.current_screen == Screen::PurgeConfirm {
+        match key {
+            KeyCode::Char('y') | KeyCode::Char('Y') => app.purge_old_sessions(),
+            KeyCode::Char('n') | KeyCode::Esc => app.current_screen = Screen::Settings,
+            _ => {}
+        }
+        return;
+    }
+    
+    // Choose the tag to merge the Stats tag into
+    if app.current_screen == Screen::MergeTags {
+        match key {
+            KeyCode::Up => app.prev_merge_target(),
+            KeyCode::Down => app.next_merge_target(),
+            KeyCode::Enter => app.merge_selected_tag(),
+            KeyCode::Esc => app.navigate_to(Screen::Stats),
+            _ => {}
+        }
+        return;
+    }
+    
+    // Sessions behind a Stats bar
+    if app.current_screen == Screen::BucketSessions {
+        match key {
+            KeyCode::Up => app.prev_bucket_session(),
+            KeyCode::Down => app.next_bucket_session(),
+            KeyCode::Esc | KeyCode::Enter => app.navigate_to(Screen::Stats),
+            _ => {}
+        }
+        return;
+    }
+    
+    // Handle archived tag list
+    if app.current_screen == Screen::ArchivedTags {
+        match key {
+            KeyCode::Up => app.prev_archived_tag(),
+            KeyCode::Down => app.next_archived_tag(),
+            KeyCode::Enter | KeyCode::Char('a') => app.unarchive_selected_tag(),
+            KeyCode::Esc | KeyCode::Char('A') => app.current_screen = Screen::Home,
+            _ => {}
+        }
+        return;
+    }
+
+    // Handle the template list
+    if app.current_screen == Screen::Templates {
+        match key {
+            KeyCode::Up => app.prev_template(),
+            KeyCode::Down => app.next_template(),
+            KeyCode::Enter => app.apply_selected_template(false),
+            KeyCode::Char(' ') => app.apply_selected_template(true),
+            KeyCode::Char('n') => {
+                app.navigate_to(Screen::TemplateInput);
+                app.input_mode = InputMode::Editing;
+            }
+            KeyCode::Char('d') | KeyCode::Delete => app.delete_selected_template(),
+            KeyCode::Esc | KeyCode::Char('p') => app.current_screen = Screen::Home,
+            _ => {}
+        }
+        return;
+    }
+    
+    // Name a new template made from the current tag and durations
+    if app.current_screen == Screen::TemplateInput {
+        match key {
+            KeyCode::Enter => {
+                let input = app.input_buffer.clone();
+                match app.create_template(&input) {
+                    Ok(()) => close_input_popup(app),
+                    Err(message) => app.input_error = Some(message),
+                }
+            }
+            KeyCode::Esc => close_input_popup(app),
+            _ => edit_input_buffer(app, key),
+        }
+        return;
+    }
+
+    // Cycle through the main screens from any of them
+    match key {
+        KeyCode::Char(']') => return app.cycle_screen(1),
+        KeyCode::Char('[') => return app.cycle_screen(-1),
+        _ => {}
+    }
+
     match app.current_screen {
-        Screen::Home | Screen::Settings => match key {
-            KeyCode::Char('q') => app.should_quit = true,
+        Screen::Home => match key {
+            KeyCode::Char('q') => app.request_quit(),
+            KeyCode::Char('Q') => app.force_quit(),
             KeyCode::Char(' ') => app.toggle_timer(),
-            KeyCode::Char('r') => app.reset_timer(),
+            KeyCode::Char('r') => app.request_reset(),
             KeyCode::Char('t') | KeyCode::Tab => app.next_tag(),
             KeyCode::Char('T') | KeyCode::BackTab => app.prev_tag(),
             KeyCode::Char('+') | KeyCode::Char('n') => {
                 app.navigate_to(Screen::TagInput);
                 app.input_mode = InputMode::Editing;
             }
-            KeyCode::Char('-') => {
-                if !app.tags.is_empty() {
-                    app.navigate_to(Screen::DeleteConfirm);
-                }
+            KeyCode::Char('-') => app.request_tag_deletion(),
+            KeyCode::Char(c @ '1'..='9') => app.quick_start(c as usize - '0' as usize),
+            KeyCode::Char('x') => app.toggle_tag_mark(),
+            KeyCode::Char('u') => app.undo_last_session(),
+            KeyCode::Char('g') => app.show_today_summary(),
+            KeyCode::Char('S') => app.toggle_stats_peek(),
+            KeyCode::Char('z') => app.toggle_silent(),
+            KeyCode::Char('c') => app.toggle_compact(),
+            KeyCode::Char('e') => app.toggle_count_up(),
+            KeyCode::Char('i') => app.toggle_incognito(),
+            KeyCode::Char('l') => {
+                app.navigate_to(Screen::ManualEntry);
+                app.input_mode = InputMode::Editing;
+            }
+            KeyCode::Char('a') => app.archive_selected_tag(),
+            KeyCode::Char('A') => {
+                app.archived_index = 0;
+                app.navigate_to(Screen::ArchivedTags);
             }
             KeyCode::Char('s') => app.navigate_to(Screen::Stats),
             KeyCode::Char('m') => app.navigate_to(Screen::Heatmap),
-            KeyCode::Char('w') => app.adjust_work_duration(60),   // +1 min
-            KeyCode::Char('W') => app.adjust_work_duration(-60),  // -1 min
-            KeyCode::Char('b') => app.adjust_break_duration(60),  // +1 min
-            KeyCode::Char('B') => app.adjust_break_duration(-60), // -1 min
+            KeyCode::Char('o') => app.open_settings(),
+            KeyCode::Char('p') => app.open_templates(),
+            KeyCode::Char('v') => app.toggle_ascii(),
+            KeyCode::Char('j') => app.align_session_end(false),
+            KeyCode::Char('J') => app.align_session_end(true),
+            KeyCode::Char('d') => {
+                app.navigate_to(Screen::DurationInput);
+                app.input_mode = InputMode::Editing;
+            }
+            KeyCode::Char('w') => { app.adjust_work_duration(60); }   // +1 min
+            KeyCode::Char('W') => { app.adjust_work_duration(-60); }  // -1 min
+            KeyCode::Char('b') => { app.adjust_break_duration(60); }  // +1 min
+            KeyCode::Char('B') => { app.adjust_break_duration(-60); } // -1 min
+            // Down moves down the list, like Tab; Up moves back up
             KeyCode::Up => app.prev_tag(),
             KeyCode::Down => app.next_tag(),
             _ => {}
         },
         Screen::Stats => match key {
-            KeyCode::Char('q') => app.should_quit = true,
+            KeyCode::Char('q') => app.request_quit(),
             KeyCode::Char('h') => app.navigate_to(Screen::Home),
             KeyCode::Char('m') => app.navigate_to(Screen::Heatmap),
             KeyCode::Tab => app.toggle_stats_view(),
+            KeyCode::Char('g') => app.toggle_stats_breakdown(),
+            KeyCode::Char('c') => app.toggle_stats_categories(),
+            KeyCode::Char('r') => app.open_week_review(),
+            KeyCode::Char('M') => app.open_tag_merge(),
+            KeyCode::Char('l') => app.toggle_stats_leaderboard(),
+            KeyCode::Char(',') => app.prev_stats_bucket(),
+            KeyCode::Char('.') => app.next_stats_bucket(),
+            KeyCode::Enter => app.open_bucket_sessions(),
             KeyCode::Left => app.prev_stats_tag(),
             KeyCode::Right => app.next_stats_tag(),
+            KeyCode::Char('+') => app.adjust_stats_tag_target(30),
+            KeyCode::Char('-') => app.adjust_stats_tag_target(-30),
+            KeyCode::Char('f') => app.cycle_export_format(),
+            KeyCode::Char('x') => app.export_sessions(),
             _ => {}
         },
         Screen::Heatmap => match key {
-            KeyCode::Char('q') => app.should_quit = true,
+            KeyCode::Char('q') => app.request_quit(),
+            KeyCode::Char('e') => app.export_heatmap_svg(),
+            KeyCode::Char('t') => app.toggle_heat_scale(),
+            KeyCode::Char('c') => app.toggle_heat_metric(),
+            KeyCode::Left => app.prev_heatmap_tag(),
+            KeyCode::Right => app.next_heatmap_tag(),
+            KeyCode::PageUp => app.scroll_heatmap_back(),
+            KeyCode::PageDown => app.scroll_heatmap_forward(),
+            KeyCode::Char('n') | KeyCode::End => app.heatmap_to_today(),
             KeyCode::Char('h') => app.navigate_to(Screen::Home),
             KeyCode::Char('s') => app.navigate_to(Screen::Stats),
             _ => {}
         },
+        Screen::WeekReview => match key {
+            KeyCode::Char('q') => app.request_quit(),
+            KeyCode::Right | KeyCode::Char(' ') => app.next_review_card(),
+            KeyCode::Left => app.prev_review_card(),
+            KeyCode::Esc => app.navigate_to(app.previous_screen),
+            _ => {}
+        },
+        Screen::Settings => match key {
+            KeyCode::Char('q') => app.request_quit(),
+            KeyCode::Char('h') | KeyCode::Esc => app.leave_settings(),
+            KeyCode::Char('s') => app.save_settings(),
+            KeyCode::Char('x') => app.discard_settings(),
+            KeyCode::Up | KeyCode::Char('k') => app.prev_setting(),
+            KeyCode::Down | KeyCode::Char('j') => app.next_setting(),
+            KeyCode::Enter | KeyCode::Char(' ') => app.change_setting(app.selected_setting()),
+            KeyCode::Char('b') => app.backup_database(),
+            KeyCode::Char('P') => app.request_purge(),
+            _ => {}
+        },
         _ => {}
     }
 }
+
+/// Typing and backspace inside the text-input popups
+fn edit_input_buffer(app: &mut App, key: KeyCode) {
+    match key {
+        KeyCode::Backspace => {
+            app.input_buffer.pop();
+        }
+        KeyCode::Char(c) => {
+            app.input_buffer.push(c);
+        }
+        _ => return,
+    }
+    app.input_error = None;
+}
+
+/// Leave a text-input popup, returning to the screen it was opened from
+fn close_input_popup(app: &mut App) {
+    app.input_buffer.clear();
+    app.input_error = None;
+    app.input_mode = InputMode::Normal;
+    app.current_screen = app.previous_screen;
+}
@@ -0,0 +1,181 @@
+use chrono::NaiveDate;
+use std::collections::HashMap;
+
+use crate::db::Database;
+use crate::ui::format_duration;
+
+/// One insight on the week review screen
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReviewCard {
+    pub title: &'static str,
+    pub headline: String,
+    pub detail: String,
+}
+
+/// Everything the week review is built from, covering `from` to `to` (inclusive)
+pub struct WeekData {
+    pub from: NaiveDate,
+    pub to: NaiveDate,
+    pub daily: Vec<(NaiveDate, i64)>,
+    pub tags: Vec<(String, i64)>,
+    pub sessions: i64,
+    pub previous_total: i64,
+    pub streak: u32,
+    pub goal_minutes: u64,
+}
+
+impl WeekData {
+    /// The seven days ending today, and the week before for comparison
+    pub fn load(db: &Database, today: NaiveDate) -> Self {
+        let from = today - chrono::Duration::days(6);
+        let previous_from = from - chrono::Duration::days(7);
+        let worked_days: Vec<_> = db.get_heatmap_data().unwrap_or_default().into_iter().map(|(day, _)| day).collect();
+
+        WeekData {
+            from,
+            to: today,
+            daily: db.get_daily_totals(from, today).unwrap_or_default(),
+            tags: db.get_tag_totals(from, today).unwrap_or_default(),
+            sessions: db.get_session_count_between(from, today),
+            previous_total: db.get_daily_totals(previous_from, from.pred_opt().unwrap_or(from))
+                .unwrap_or_default()
+                .iter()
+                .map(|(_, seconds)| seconds)
+                .sum(),
+            streak: crate::export::current_streak(&worked_days, today, &crate::export::RestDays::load(db)),
+            goal_minutes: db.get_config("daily_goal", "0").parse().unwrap_or(0),
+        }
+    }
+}
+
+/// The review's cards in order: total, best day, top tag, streak, goal adherence
+pub fn week_review(week: &WeekData) -> Vec<ReviewCard> {
+    let total: i64 = week.daily.iter().map(|(_, seconds)| seconds).sum();
+    let plural = |n: i64| if n == 1 { "" } else { "s" };
+    let mut cards = Vec::new();
+
+    let comparison = if week.previous_total == 0 {
+        "Nothing was logged the week before.".to_string()
+    } else {
+        let change = (total - week.previous_total) * 100 / week.previous_total;
+        match change {
+            0 => "The same as the week before.".to_string(),
+            c if c > 0 => format!("Up {}% on the week before.", c),
+            c => format!("Down {}% on the week before.", -c),
+        }
+    };
+    cards.push(ReviewCard {
+        title: "This Week",
+        headline: format_duration(total),
+        detail: format!("{} session{} from {} to {}. {}", week.sessions, plural(week.sessions),
+            week.from.format("%b %-d"), week.to.format("%b %-d"), comparison),
+    });
+
+    cards.push(match week.daily.iter().max_by_key(|(day, seconds)| (*seconds, std::cmp::Reverse(*day))) {
+        Some((day, seconds)) => ReviewCard {
+            title: "Best Day",
+            headline: day.format("%A").to_string(),
+            detail: format!("{} of focus on {}.", format_duration(*seconds), day.format("%b %-d")),
+        },
+        None => ReviewCard {
+            title: "Best Day",
+            headline: "—".to_string(),
+            detail: "No work logged this week.".to_string(),
+        },
+    });
+
+    cards.push(match week.tags.first() {
+        Some((tag, seconds)) => ReviewCard {
+            title: "Most-Used Tag",
+            headline: tag.clone(),
+            detail: format!("{} ({}% of your focus time).", format_duration(*seconds),
+                if total > 0 { seconds * 100 / total } else { 0 }),
+        },
+        None => ReviewCard {
+            title: "Most-Used Tag",
+            headline: "—".to_string(),
+            detail: "No tags used this week.".to_string(),
+        },
+    });
+
+    let worked_today = week.daily.iter().any(|(day, seconds)| *day == week.to && *seconds > 0);
+    cards.push(ReviewCard {
+        title: "Streak",
+        headline: format!("{} day{}", week.streak, plural(week.streak as i64)),
+        detail: match (week.streak, worked_today) {
+            (0, _) => "Log a session today to start a new streak.".to_string(),
+            (_, false) => "Log a session today to keep it going.".to_string(),
+            _ => "Today already counts. Keep it up!".to_string(),
+        },
+    });
+
+    let days = (week.to - week.from).num_days() + 1;
+    cards.push(if week.goal_minutes == 0 {
+        ReviewCard {
+            title: "Daily Goal",
+            headline: "Not set".to_string(),
+            detail: "Set a daily goal on the Settings screen to track it here.".to_string(),
+        }
+    } else {
+        let daily_map: HashMap<NaiveDate, i64> = week.daily.iter().copied().collect();
+        let met = week.from.iter_days()
+            .take(days as usize)
+            .filter(|day| *daily_map.get(day).unwrap_or(&0) >= week.goal_minutes as i64 * 60)
+            .count();
+        ReviewCard {
+            title: "Daily Goal",
+            headline: format!("{} of {} days", met, days),
+            detail: format!("Days reaching your {} min goal.", week.goal_minutes),
+        }
+    });
+
+    cards
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn day(d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2024, 6, d).unwrap()
+    }
+
+    #[test]
+    fn test_week_review_cards() {
+        let week = WeekData {
+            from: day(4),
+            to: day(10),
+            daily: vec![(day(5), 3000), (day(8), 6000), (day(10), 3000)],
+            tags: vec![("Study".to_string(), 9000), ("Work".to_string(), 3000)],
+            sessions: 8,
+            previous_total: 8000,
+            streak: 1,
+            goal_minutes: 50,
+        };
+        let cards = week_review(&week);
+        let headlines: Vec<&str> = cards.iter().map(|card| card.headline.as_str()).collect();
+
+        assert_eq!(headlines, vec!["3h 20m", "Saturday", "Study", "1 day", "3 of 7 days"]);
+        assert!(cards[0].detail.contains("Up 50%"));
+        assert!(cards[2].detail.contains("75%"));
+        assert_eq!(cards[3].detail, "Today already counts. Keep it up!");
+    }
+
+    #[test]
+    fn test_week_review_without_data() {
+        let week = WeekData {
+            from: day(4),
+            to: day(10),
+            daily: vec![],
+            tags: vec![],
+            sessions: 0,
+            previous_total: 0,
+            streak: 0,
+            goal_minutes: 0,
+        };
+        let cards = week_review(&week);
+        assert_eq!(cards.len(), 5);
+        assert_eq!(cards[1].detail, "No work logged this week.");
+        assert_eq!(cards[4].headline, "Not set");
+    }
+}
@@ -0,0 +1,100 @@
+use ratatui::style::Color;
+
+/// Color palette shared by all screens
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Theme {
+    pub name: &'static str,
+    pub primary: Color,    // Main information (settings bar, charts)
+    pub secondary: Color,  // Panel borders
+    pub work: Color,       // Work mode
+    pub rest: Color,       // Break mode
+    pub accent: Color,     // Tags and filters
+    pub highlight: Color,  // Selection, running timer, popups
+    pub text: Color,       // Regular text
+    pub dim: Color,        // Help bars, separators, empty cells
+    pub heat: [Color; 5],  // Heatmap intensity, from empty to busiest
+}
+
+impl Theme {
+    pub const DEFAULT: Theme = Theme {
+        name: "default",
+        primary: Color::Cyan,
+        secondary: Color::Blue,
+        work: Color::Red,
+        rest: Color::Green,
+        accent: Color::Magenta,
+        highlight: Color::Yellow,
+        text: Color::White,
+        dim: Color::DarkGray,
+        heat: [Color::DarkGray, Color::Blue, Color::Cyan, Color::LightCyan, Color::Green],
+    };
+
+    pub const SOLARIZED: Theme = Theme {
+        name: "solarized",
+        primary: Color::Rgb(42, 161, 152),
+        secondary: Color::Rgb(38, 139, 210),
+        work: Color::Rgb(220, 50, 47),
+        rest: Color::Rgb(133, 153, 0),
+        accent: Color::Rgb(211, 54, 130),
+        highlight: Color::Rgb(181, 137, 0),
+        text: Color::Rgb(147, 161, 161),
+        dim: Color::Rgb(88, 110, 117),
+        heat: [
+            Color::Rgb(7, 54, 66),
+            Color::Rgb(38, 139, 210),
+            Color::Rgb(42, 161, 152),
+            Color::Rgb(108, 113, 196),
+            Color::Rgb(133, 153, 0),
+        ],
+    };
+
+    pub const MONOCHROME: Theme = Theme {
+        name: "monochrome",
+        primary: Color::White,
+        secondary: Color::Gray,
+        work: Color::White,
+        rest: Color::Gray,
+        accent: Color::White,
+        highlight: Color::White,
+        text: Color::Gray,
+        dim: Color::DarkGray,
+        heat: [Color::DarkGray, Color::DarkGray, Color::Gray, Color::Gray, Color::White],
+    };
+
+    /// Dim, warm colors for late sessions
+    pub const NIGHT: Theme = Theme {
+        name: "night",
+        primary: Color::Rgb(150, 120, 90),
+        secondary: Color::Rgb(90, 72, 60),
+        work: Color::Rgb(170, 85, 65),
+        rest: Color::Rgb(110, 130, 80),
+        accent: Color::Rgb(150, 100, 120),
+        highlight: Color::Rgb(190, 150, 80),
+        text: Color::Rgb(150, 140, 125),
+        dim: Color::Rgb(75, 66, 58),
+        heat: [
+            Color::Rgb(50, 44, 40),
+            Color::Rgb(85, 70, 52),
+            Color::Rgb(120, 95, 62),
+            Color::Rgb(155, 118, 70),
+            Color::Rgb(190, 150, 80),
+        ],
+    };
+
+    pub const ALL: [Theme; 4] = [Theme::DEFAULT, Theme::SOLARIZED, Theme::MONOCHROME, Theme::NIGHT];
+
+    /// Look up a built-in theme by name, falling back to the default
+    pub fn by_name(name: &str) -> Theme {
+        Self::ALL
+            .iter()
+            .find(|theme| theme.name == name)
+            .copied()
+            .unwrap_or(Theme::DEFAULT)
+    }
+
+    /// The built-in theme following this one, wrapping around
+    pub fn next(&self) -> Theme {
+        let index = Self::ALL.iter().position(|theme| theme.name == self.name).unwrap_or(0);
+        Self::ALL[(index + 1) % Self::ALL.len()]
+    }
+}
@@ -0,0 +1,35 @@
+use ratatui::{
+    Frame,
+    layout::{Alignment, Rect},
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::Paragraph,
+};
+
+use crate::app::{App, PomodoroMode};
+
+/// Right-aligned "● WORK 12:34" marker drawn in a screen's title row while the
+/// timer runs, so a session ticking in the background is never forgotten
+pub fn render_session_banner(frame: &mut Frame, app: &App, area: Rect) {
+    if !app.timer_running {
+        return;
+    }
+    let theme = &app.theme;
+    let (label, color) = match app.mode {
+        PomodoroMode::Work => ("WORK", theme.work),
+        PomodoroMode::Break => ("BREAK", theme.rest),
+    };
+    let time = app.timer_text();
+    
+    let mut spans = vec![
+        Span::styled(format!("{} ", app.glyphs().dot), Style::default().fg(color)),
+        Span::styled(format!("{} {}", label, time), Style::default().fg(color).add_modifier(Modifier::BOLD)),
+    ];
+    if let (PomodoroMode::Work, Some(tag)) = (app.mode, app.selected_tag()) {
+        spans.push(Span::styled(format!(" [{}]", tag), Style::default().fg(theme.accent)));
+    }
+    spans.push(Span::raw(" "));
+    
+    let banner = Paragraph::new(Line::from(spans)).alignment(Alignment::Right);
+    frame.render_widget(banner, Rect { height: 1, ..area });
+}
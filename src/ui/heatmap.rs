@@ -1,4 +1,4 @@
-use chrono::{Datelike, Duration, Local, NaiveDate, Weekday};
+use chrono::{Datelike, Duration, NaiveDate, Weekday};
 use ratatui::{
     Frame,
     layout::{Alignment, Constraint, Direction, Layout},
@@ -9,111 +9,147 @@ use ratatui::{
 use std::collections::HashMap;
 
 use crate::app::App;
+use crate::glyphs::Glyphs;
+use crate::theme::Theme;
+use crate::ui::banner::render_session_banner;
 
 pub fn render_heatmap(frame: &mut Frame, app: &App) {
+    let theme = &app.theme;
+    let glyphs = app.glyphs();
+    // Goal days are marked on the overall heatmap, as the goal counts every tag
+    let show_goal = app.daily_goal > 0 && app.get_heatmap_tag().is_none();
+    let today = app.db.today();
+    let end = app.heatmap_end();
+    let goal_days = if show_goal {
+        let days = (today - end).num_days() + HEATMAP_DAYS;
+        app.db.get_goal_met_days(app.daily_goal, days).unwrap_or_default()
+    } else {
+        Vec::new()
+    };
     let chunks = Layout::default()
         .direction(Direction::Vertical)
         .margin(1)
         .constraints([
             Constraint::Length(3),  // Title
             Constraint::Min(12),    // Heatmap
-            Constraint::Length(3),  // Legend
+            Constraint::Length(if show_goal { 4 } else { 3 }),  // Legend
             Constraint::Length(2),  // Help
         ])
         .split(frame.area());
     
     // Title
-    let title = Paragraph::new("📅 Activity Heatmap (Last 6 Months)")
-        .style(Style::default().fg(Color::Green).add_modifier(Modifier::BOLD))
+    let range = if app.heatmap_offset == 0 {
+        "Last 6 Months".to_string()
+    } else {
+        let start = end - Duration::days(HEATMAP_DAYS);
+        format!("{} – {}", start.format("%b %Y"), end.format("%b %Y"))
+    };
+    let title = Paragraph::new(format!(
+        "{} Activity Heatmap ({}) · {} · by {}",
+        glyphs.calendar,
+        range,
+        app.get_heatmap_tag().unwrap_or("All Tags"),
+        app.heat_metric().name()
+    ))
+        .style(Style::default().fg(theme.heat[4]).add_modifier(Modifier::BOLD))
         .alignment(Alignment::Center)
-        .block(Block::default().borders(Borders::BOTTOM).border_style(Style::default().fg(Color::DarkGray)));
+        .block(Block::default().borders(Borders::BOTTOM).border_style(Style::default().fg(theme.dim)));
     frame.render_widget(title, chunks[0]);
+    render_session_banner(frame, app, chunks[0]);
     
     // Heatmap
-    render_heatmap_grid(frame, app, chunks[1]);
+    render_heatmap_grid(frame, app, &goal_days, chunks[1]);
     
     // Legend
-    let legend = Paragraph::new(Line::from(vec![
-        Span::raw(" Less "),
-        Span::styled("░", Style::default().fg(Color::DarkGray)),
-        Span::raw(" "),
-        Span::styled("▒", Style::default().fg(Color::Blue)),
-        Span::raw(" "),
-        Span::styled("▓", Style::default().fg(Color::Cyan)),
-        Span::raw(" "),
-        Span::styled("█", Style::default().fg(Color::Green)),
-        Span::raw(" More"),
-    ]))
-    .alignment(Alignment::Center)
-    .block(Block::default().borders(Borders::ALL).title(" Legend "));
+    let legend_line = match app.heat_scale() {
+        HeatScale::Relative => Line::from(vec![
+            Span::raw(" Less "),
+            Span::styled(glyphs.heat[0], Style::default().fg(theme.heat[0])),
+            Span::raw(" "),
+            Span::styled(glyphs.heat[1], Style::default().fg(theme.heat[1])),
+            Span::raw(" "),
+            Span::styled(glyphs.heat[2], Style::default().fg(theme.heat[2])),
+            Span::raw(" "),
+            Span::styled(glyphs.heat[4], Style::default().fg(theme.heat[4])),
+            Span::raw(" More"),
+        ]),
+        HeatScale::Absolute([low, mid, high]) => {
+            let suffix = app.heat_metric().suffix();
+            Line::from(vec![
+                Span::styled(glyphs.heat[0], Style::default().fg(theme.heat[0])),
+                Span::raw(" none  "),
+                Span::styled(glyphs.heat[1], Style::default().fg(theme.heat[1])),
+                Span::raw(format!(" <{}{}  ", low, suffix)),
+                Span::styled(glyphs.heat[2], Style::default().fg(theme.heat[2])),
+                Span::raw(format!(" <{}{}  ", mid, suffix)),
+                Span::styled(glyphs.heat[3], Style::default().fg(theme.heat[3])),
+                Span::raw(format!(" <{}{}  ", high, suffix)),
+                Span::styled(glyphs.heat[4], Style::default().fg(theme.heat[4])),
+                Span::raw(format!(" {}{}+", high, suffix)),
+            ])
+        }
+    };
+    let legend_title = match app.heat_scale() {
+        HeatScale::Relative => " Legend (relative to busiest day) ".to_string(),
+        HeatScale::Absolute(_) => format!(" Legend ({} per day) ", app.heat_metric().name()),
+    };
+    let mut legend_lines = vec![legend_line];
+    if show_goal {
+        let last_30 = goal_days.iter().filter(|day| (today - **day).num_days() < 30).count();
+        legend_lines.push(Line::from(vec![
+            Span::styled(glyphs.goal, Style::default().fg(theme.heat[4])),
+            Span::raw(format!(" daily goal of {} min met · {} of the last 30 days", app.daily_goal, last_30)),
+        ]));
+    }
+    let legend = Paragraph::new(legend_lines)
+        .alignment(Alignment::Center)
+        .block(Block::default().borders(Borders::ALL).title(legend_title));
     frame.render_widget(legend, chunks[2]);
 
     // Help bar
-    let help_text = " [h] Home │ [s] Stats │ [q] Quit ";
+    let help_text = match &app.status_message {
+        Some(message) => format!(" {} ", message),
+        None => " [←/→] Change Tag │ [PgUp/PgDn] Scroll Months │ [n] Today │ [t] Relative/Absolute │ [c] Minutes/Sessions │ [e] Export SVG │ [h] Home │ [s] Stats │ [[/]] Screens │ [q] Quit ".to_string(),
+    };
     let help = Paragraph::new(help_text)
-        .style(Style::default().fg(Color::DarkGray))
+        .style(Style::default().fg(theme.dim))
         .alignment(Alignment::Center);
     frame.render_widget(help, chunks[3]);
 }
 
-fn render_heatmap_grid(frame: &mut Frame, app: &App, area: ratatui::layout::Rect) {
+fn render_heatmap_grid(frame: &mut Frame, app: &App, goal_days: &[NaiveDate], area: ratatui::layout::Rect) {
+    let theme = &app.theme;
     let block = Block::default()
         .borders(Borders::ALL)
-        .border_style(Style::default().fg(Color::DarkGray));
+        .border_style(Style::default().fg(theme.dim));
     
     let inner = block.inner(area);
     frame.render_widget(block, area);
     
     // Get heatmap data
-    let data = app.db.get_heatmap_data().unwrap_or_default();
-    let data_map: HashMap<NaiveDate, i64> = data.into_iter().collect();
-    
-    // Calculate date range (last 6 months, ~26 weeks)
-    let today = Local::now().date().naive_local();
-    let start_date = today - Duration::days(180);
+    let data_map: HashMap<NaiveDate, i64> = app.heatmap_window_data().into_iter().collect();
     
     // Find max value for intensity calculation
-    let max_minutes = data_map.values().map(|v| *v / 60).max().unwrap_or(60).max(1);
+    let max_value = data_map.values().copied().max().unwrap_or(1).max(1);
     
     // Build the grid
-    let days = ["Mon", "Tue", "Wed", "Thu", "Fri", "Sat", "Sun"];
+    let days = day_labels(app.week_start);
     let mut lines: Vec<Line> = Vec::new();
-    
-    // Calculate weeks
-    let mut weeks: Vec<Vec<Option<(NaiveDate, i64)>>> = Vec::new();
-    let mut current_date = start_date;
-    
-    // Align to Monday
-    while current_date.weekday() != Weekday::Mon {
-        current_date = current_date + Duration::days(1);
-    }
-    
-    while current_date <= today {
-        let mut week = Vec::new();
-        for _ in 0..7 {
-            if current_date <= today {
-                let minutes = data_map.get(&current_date).map(|v| *v / 60).unwrap_or(0);
-                week.push(Some((current_date, minutes)));
-            } else {
-                week.push(None);
-            }
-            current_date = current_date + Duration::days(1);
-        }
-        weeks.push(week);
-    }
+    let weeks = build_weeks(&data_map, app.heatmap_end(), app.week_start);
     
     // Transpose to get rows by day of week
     for (day_idx, day_name) in days.iter().enumerate() {
         let mut spans: Vec<Span> = vec![
-            Span::styled(format!("{} ", day_name), Style::default().fg(Color::White)),
+            Span::styled(format!("{} ", day_name), Style::default().fg(theme.text)),
         ];
         
         for week in &weeks {
-            if let Some(Some((_, minutes))) = week.get(day_idx) {
-                let (ch, color) = get_intensity_char(*minutes, max_minutes);
+            if let Some(Some((date, value))) = week.get(day_idx) {
+                let (ch, color) = get_intensity_char(*value, max_value, app.heat_scale(), theme, app.glyphs());
+                let ch = if goal_days.contains(date) { app.glyphs().goal } else { ch };
                 spans.push(Span::styled(ch, Style::default().fg(color)));
             } else if week.get(day_idx).is_some() {
-                spans.push(Span::styled("░", Style::default().fg(Color::DarkGray)));
+                spans.push(Span::styled(app.glyphs().heat[0], Style::default().fg(theme.dim)));
             } else {
                 spans.push(Span::raw(" "));
             }
@@ -128,13 +164,7 @@ fn render_heatmap_grid(frame: &mut Frame, app: &App, area: ratatui::layout::Rect
     for week in &weeks {
         if let Some(Some((date, _))) = week.first() {
             if date.month() != last_month {
-                let month_name = match date.month() {
-                    1 => "Jan", 2 => "Feb", 3 => "Mar", 4 => "Apr",
-                    5 => "May", 6 => "Jun", 7 => "Jul", 8 => "Aug",
-                    9 => "Sep", 10 => "Oct", 11 => "Nov", 12 => "Dec",
-                    _ => "",
-                };
-                month_labels.push(Span::styled(month_name, Style::default().fg(Color::Yellow)));
+                month_labels.push(Span::styled(month_name(date.month()), Style::default().fg(theme.highlight)));
                 last_month = date.month();
             } else {
                 month_labels.push(Span::raw(" "));
@@ -151,19 +181,140 @@ fn render_heatmap_grid(frame: &mut Frame, app: &App, area: ratatui::layout::Rect
     frame.render_widget(heatmap_text, inner);
 }
 
-fn get_intensity_char(minutes: i64, max_minutes: i64) -> (&'static str, Color) {
-    if minutes == 0 {
-        ("░", Color::DarkGray)
+/// One heatmap column: seven days with their values, `None` past today
+pub type Week = Vec<Option<(NaiveDate, i64)>>;
+
+/// Days the heatmap covers, about 6 months
+pub const HEATMAP_DAYS: i64 = 180;
+
+/// Weeks covering the 6 months (~26 weeks) up to `today`, each starting on `week_start`.
+/// `data_map` holds each day's value in the heatmap's metric (minutes or sessions).
+pub fn build_weeks(data_map: &HashMap<NaiveDate, i64>, today: NaiveDate, week_start: Weekday) -> Vec<Week> {
+    let mut weeks: Vec<Week> = Vec::new();
+    let mut current_date = today - Duration::days(HEATMAP_DAYS);
+    
+    // Align to the first day of the week
+    while current_date.weekday() != week_start {
+        current_date += Duration::days(1);
+    }
+    
+    while current_date <= today {
+        let mut week = Vec::new();
+        for _ in 0..7 {
+            if current_date <= today {
+                let value = data_map.get(&current_date).copied().unwrap_or(0);
+                week.push(Some((current_date, value)));
+            } else {
+                week.push(None);
+            }
+            current_date += Duration::days(1);
+        }
+        weeks.push(week);
+    }
+    weeks
+}
+
+/// Row labels for a week starting on `week_start`
+pub fn day_labels(week_start: Weekday) -> [&'static str; 7] {
+    const NAMES: [&str; 7] = ["Mon", "Tue", "Wed", "Thu", "Fri", "Sat", "Sun"];
+    let offset = week_start.num_days_from_monday() as usize;
+    std::array::from_fn(|i| NAMES[(offset + i) % 7])
+}
+
+pub fn month_name(month: u32) -> &'static str {
+    match month {
+        1 => "Jan", 2 => "Feb", 3 => "Mar", 4 => "Apr",
+        5 => "May", 6 => "Jun", 7 => "Jul", 8 => "Aug",
+        9 => "Sep", 10 => "Oct", 11 => "Nov", 12 => "Dec",
+        _ => "",
+    }
+}
+
+/// What the heatmap's cells measure
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum HeatMetric {
+    /// Minutes of work per day
+    Minutes,
+    /// Completed work sessions per day, rewarding consistency over raw time
+    Sessions,
+}
+
+impl HeatMetric {
+    pub fn name(&self) -> &'static str {
+        match self {
+            HeatMetric::Minutes => "minutes",
+            HeatMetric::Sessions => "sessions",
+        }
+    }
+    
+    /// Suffix after a value in the legend, e.g. `30m`
+    pub fn suffix(&self) -> &'static str {
+        match self {
+            HeatMetric::Minutes => "m",
+            HeatMetric::Sessions => "",
+        }
+    }
+}
+
+/// Absolute thresholds when counting sessions: <2, <4, <8 and 8+
+pub const SESSION_THRESHOLDS: [i64; 3] = [2, 4, 8];
+
+/// How heatmap cells are bucketed into intensity levels
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum HeatScale {
+    /// Quarters of the busiest day
+    Relative,
+    /// Fixed thresholds in the metric's unit, e.g. [15, 30, 60] for <15, <30, <60 and 60+
+    Absolute([i64; 3]),
+}
+
+/// Intensity bucket from 0 (no activity) to 4 (busiest)
+pub fn intensity_level(value: i64, max_value: i64, scale: HeatScale) -> usize {
+    if value == 0 {
+        0
+    } else if let HeatScale::Absolute(thresholds) = scale {
+        1 + thresholds.iter().filter(|threshold| value >= **threshold).count()
     } else {
-        let ratio = minutes as f64 / max_minutes as f64;
+        let ratio = value as f64 / max_value as f64;
         if ratio < 0.25 {
-            ("▒", Color::Blue)
+            1
         } else if ratio < 0.5 {
-            ("▓", Color::Cyan)
+            2
         } else if ratio < 0.75 {
-            ("▓", Color::LightCyan)
+            3
         } else {
-            ("█", Color::Green)
+            4
         }
     }
 }
+
+fn get_intensity_char(value: i64, max_value: i64, scale: HeatScale, theme: &Theme, glyphs: &Glyphs) -> (&'static str, Color) {
+    let level = intensity_level(value, max_value, scale);
+    (glyphs.heat[level], theme.heat[level])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_weeks_follow_configured_start_day() {
+        let today = NaiveDate::from_ymd_opt(2024, 6, 12).unwrap();
+        for start in [Weekday::Mon, Weekday::Sun] {
+            let weeks = build_weeks(&HashMap::new(), today, start);
+            let (first_day, _) = weeks[0][0].unwrap();
+            assert_eq!(first_day.weekday(), start);
+            assert_eq!(day_labels(start)[0], &format!("{:?}", start)[..3]);
+        }
+        assert_eq!(day_labels(Weekday::Sun), ["Sun", "Mon", "Tue", "Wed", "Thu", "Fri", "Sat"]);
+    }
+
+    #[test]
+    fn test_absolute_intensity_ignores_busiest_day() {
+        let scale = HeatScale::Absolute([15, 30, 60]);
+        let levels: Vec<usize> = [0, 10, 15, 45, 60, 300].iter().map(|m| intensity_level(*m, 600, scale)).collect();
+        assert_eq!(levels, [0, 1, 2, 3, 4, 4]);
+        // Relative to a 600-minute day, all but the last would be the lowest level
+        assert_eq!(intensity_level(60, 600, HeatScale::Relative), 1);
+    }
+}
@@ -1,76 +1,199 @@
 use ratatui::{
     Frame,
     layout::{Alignment, Constraint, Direction, Layout, Rect},
-    style::{Color, Modifier, Style},
+    style::{Modifier, Style},
     text::{Line, Span},
-    widgets::{Block, Borders, List, ListItem, Paragraph, Clear, Gauge},
+    widgets::{Block, BorderType, Borders, List, ListItem, Paragraph, Clear, Gauge, Tabs, Wrap},
 };
 
-use crate::app::{App, InputMode, PomodoroMode, Screen};
+use crate::app::{tag_category, App, PomodoroMode, ProgressStyle, Screen, TagLayout, RECENT_SESSION_COUNT};
+use crate::glyphs::Glyphs;
+use crate::ui::{format_duration, relative_time};
+use crate::ui::popup::{centered_rect, render_input_popup};
 
 pub fn render_home(frame: &mut Frame, app: &App) {
+    let theme = &app.theme;
+    let glyphs = app.glyphs();
     let chunks = Layout::default()
         .direction(Direction::Vertical)
         .margin(1)
         .constraints([
             Constraint::Length(3),  // Title
             Constraint::Min(10),    // Main content
-            Constraint::Length(3),  // Settings bar
+            Constraint::Length(if app.compact { 0 } else { 3 }),  // Settings bar
             Constraint::Length(3),  // Help bar
         ])
         .split(frame.area());
     
     // Title
-    let title = Paragraph::new("🍅 Pomodoro++")
-        .style(Style::default().fg(Color::Red).add_modifier(Modifier::BOLD))
+    let title = Paragraph::new(format!("{} Pomodoro++", glyphs.app))
+        .style(Style::default().fg(theme.work).add_modifier(Modifier::BOLD))
         .alignment(Alignment::Center)
-        .block(Block::default().borders(Borders::BOTTOM).border_style(Style::default().fg(Color::DarkGray)));
+        .block(Block::default().borders(Borders::BOTTOM).border_style(Style::default().fg(theme.dim)));
     frame.render_widget(title, chunks[0]);
     
-    // Main content - split into timer and tags
-    let main_chunks = Layout::default()
-        .direction(Direction::Horizontal)
-        .constraints([Constraint::Percentage(60), Constraint::Percentage(40)])
-        .split(chunks[1]);
+    // Wall clock in the top-right corner, dropped when it would crowd the title
+    if chunks[0].width >= 40 {
+        let clock = Paragraph::new(format!("{} {} ", glyphs.clock, app.format_clock()))
+            .style(Style::default().fg(theme.dim))
+            .alignment(Alignment::Right);
+        frame.render_widget(clock, Rect { height: 1, ..chunks[0] });
+    }
     
-    render_timer(frame, app, main_chunks[0]);
-    render_tags(frame, app, main_chunks[1]);
+    // Main content - split into timer and tags, or the timer alone in compact mode
+    if app.compact {
+        render_timer(frame, app, chunks[1]);
+    } else if app.tag_layout == TagLayout::Tabs {
+        // Tags in a row above the timer, which keeps the full height below
+        let rows = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Length(3), Constraint::Min(7)])
+            .split(chunks[1]);
+        let main_chunks = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Percentage(60), Constraint::Percentage(40)])
+            .split(rows[1]);
+        
+        render_tag_tabs(frame, app, rows[0]);
+        render_timer(frame, app, main_chunks[0]);
+        render_recent_sessions(frame, app, main_chunks[1]);
+    } else {
+        let main_chunks = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Percentage(60), Constraint::Percentage(40)])
+            .split(chunks[1]);
+        
+        let side_chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Min(4), Constraint::Length(RECENT_SESSION_COUNT as u16 + 2)])
+            .split(main_chunks[1]);
+        
+        render_timer(frame, app, main_chunks[0]);
+        render_tag_list(frame, app, side_chunks[0]);
+        render_recent_sessions(frame, app, side_chunks[1]);
+    }
     
     // Settings bar
     let work_mins = app.work_duration / 60;
     let break_mins = app.break_duration / 60;
-    let settings_text = format!(
-        " ⏱  Work: {} min  │  Break: {} min  │  [w/W] adjust work  │  [b/B] adjust break ",
-        work_mins, break_mins
+    let mut settings_text = format!(
+        " {} Work: {} min  │  Break: {} min  │  [w/W] adjust work  │  [b/B] adjust break  │  [d] type both ",
+        glyphs.timer, work_mins, break_mins
     );
-    let settings = Paragraph::new(settings_text)
-        .style(Style::default().fg(Color::Cyan))
-        .block(Block::default().borders(Borders::ALL).border_style(Style::default().fg(Color::DarkGray)));
-    frame.render_widget(settings, chunks[2]);
+    if app.silent {
+        settings_text.push_str(&format!(" │  {} silent ", glyphs.silent));
+    }
+    let mut settings_line = vec![Span::styled(settings_text, Style::default().fg(theme.primary))];
+    if let Some(hint) = app.limit_hint() {
+        settings_line.push(Span::styled(format!(" │  {} {} ", glyphs.warning, hint), Style::default().fg(theme.accent).add_modifier(Modifier::BOLD)));
+    }
+    if app.sound_failed() && !app.silent {
+        settings_line.push(Span::styled(format!(" │  {} sound failed — check Settings ", glyphs.warning), Style::default().fg(theme.accent)));
+    }
+    let settings = Paragraph::new(Line::from(settings_line))
+        .block(Block::default().borders(Borders::ALL).border_style(Style::default().fg(theme.dim)));
+    if !app.compact {
+        frame.render_widget(settings, chunks[2]);
+    }
     
     // Help bar
-    let help_text = " [Space] Start/Pause │ [r] Reset │ [t] Tag │ [+] Add │ [-] Delete │ [s] Stats │ [m] Map │ [q] Quit ";
+    let help_text = match &app.status_message {
+        Some(message) => format!(" {} ", message),
+        None if app.focus_locked() => format!(" {} Focus lock on: [Space] Pause/Resume │ [r] Reset │ [Q] Quit anyway ", glyphs.lock),
+        None => " [Space] Start/Pause │ [1-9] Quick Start │ [r] Reset │ [u] Undo │ [l] Log │ [g] Today │ [S] Peek │ [z] Silent │ [e] Count Up │ [c] Compact │ [i] Incognito │ [t] Tag │ [x] Combine │ [+] Add │ [-] Delete │ [a/A] Archive │ [s] Stats │ [m] Map │ [o] Settings │ [p] Templates │ [j/J] End on :00/:30 │ [v] ASCII │ [q] Quit ".to_string(),
+    };
     let help = Paragraph::new(help_text)
-        .style(Style::default().fg(Color::DarkGray))
+        .style(Style::default().fg(theme.dim))
         .alignment(Alignment::Center);
     frame.render_widget(help, chunks[3]);
     
     // Render tag input popup if in TagInput screen
     if app.current_screen == Screen::TagInput {
-        render_tag_input_popup(frame, app);
+        render_input_popup(frame, app, " New Tag ", "Enter tag name:");
+    }
+    
+    // Render note input popup after a completed work session
+    if app.current_screen == Screen::NoteInput {
+        render_input_popup(frame, app, " Session Note ", "What did you work on? (optional)");
+    }
+    
+    // Render the focus rating prompt after a completed work session
+    if app.current_screen == Screen::RatingInput {
+        render_rating_popup(frame, app);
+    }
+    
+    // Render intention popup before a work session starts
+    if app.current_screen == Screen::IntentionInput {
+        render_input_popup(frame, app, " Intention ", "What will you focus on? (Esc to skip)");
+    }
+    
+    // Render manual session entry popup
+    if app.current_screen == Screen::ManualEntry {
+        let prompt = format!("Log minutes for {} (e.g. 45 or 45 14:00):", app.session_tags().join(" + "));
+        render_input_popup(frame, app, " Log Session ", &prompt);
+    }
+    
+    // Render duration preset popup if in DurationInput screen
+    if app.current_screen == Screen::DurationInput {
+        render_input_popup(frame, app, " Set Durations ", "Work/break minutes (e.g. 50/10):");
+    }
+    
+    // Render archived tag list if in ArchivedTags screen
+    if app.current_screen == Screen::ArchivedTags {
+        render_archived_tags_popup(frame, app);
+    }
+    
+    // Render the session templates and the prompt naming a new one
+    if app.current_screen == Screen::Templates {
+        render_templates_popup(frame, app);
+    }
+    if app.current_screen == Screen::TemplateInput {
+        render_input_popup(frame, app, " New Template ", "Name for the current tag and durations:");
+    }
+    
+    // Render today's summary card at startup or on request
+    if app.current_screen == Screen::TodaySummary {
+        render_today_summary_popup(frame, app);
+    }
+    
+    if app.current_screen == Screen::StatsPeek {
+        render_stats_peek_popup(frame, app);
+    }
+    
+    // Render the catch-up prompt for a session left behind by a crash
+    if app.current_screen == Screen::CatchUp {
+        render_catch_up_popup(frame, app);
     }
     
     // Render delete confirmation popup if in DeleteConfirm screen
     if app.current_screen == Screen::DeleteConfirm {
         render_delete_confirm_popup(frame, app);
     }
+    
+    if app.current_screen == Screen::ResetConfirm {
+        render_reset_confirm_popup(frame, app);
+    }
 }
 
 fn render_timer(frame: &mut Frame, app: &App, area: Rect) {
+    let theme = &app.theme;
+    // Blink the border for a moment after a session completes
+    let border_style = if app.flash_on() {
+        Style::default().fg(theme.highlight).add_modifier(Modifier::REVERSED | Modifier::BOLD)
+    } else {
+        Style::default().fg(theme.secondary)
+    };
+    // Without the tag panel, the active tag goes in the title
+    let title = if app.compact {
+        format!(" Timer │ {} {} ", app.glyphs().tag, app.logged_tags().join(" + "))
+    } else {
+        " Timer ".to_string()
+    };
     let timer_block = Block::default()
-        .title(" Timer ")
+        .title(title)
         .borders(Borders::ALL)
-        .border_style(Style::default().fg(Color::Blue));
+        .border_type(if app.flash_on() { BorderType::Thick } else { BorderType::Plain })
+        .border_style(border_style);
     
     let inner = timer_block.inner(area);
     frame.render_widget(timer_block, area);
@@ -82,27 +205,52 @@ fn render_timer(frame: &mut Frame, app: &App, area: Rect) {
             Constraint::Length(2),  // Mode
             Constraint::Min(7),     // Timer display (increased for ASCII art)
             Constraint::Length(3),  // Progress bar
-            Constraint::Length(2),  // Status
+            Constraint::Length(3),  // Status
         ])
         .split(inner);
     
     // Mode indicator
     let mode_color = match app.mode {
-        PomodoroMode::Work => Color::Red,
-        PomodoroMode::Break => Color::Green,
+        PomodoroMode::Work => theme.work,
+        PomodoroMode::Break => theme.rest,
     };
-    let mode_text = match app.mode {
-        PomodoroMode::Work => "📚 WORK SESSION",
-        PomodoroMode::Break => "☕ BREAK TIME",
+    let glyphs = app.glyphs();
+    let mut mode_text = match app.mode {
+        PomodoroMode::Work => format!("{} WORK SESSION", glyphs.work),
+        PomodoroMode::Break if app.long_break => format!("{} LONG BREAK", glyphs.long_break),
+        PomodoroMode::Break => format!("{} BREAK TIME", glyphs.short_break),
     };
-    let mode = Paragraph::new(mode_text)
+    // Where this session falls in the cycle towards the next long break
+    if let Some((position, every)) = app.cycle_position() {
+        mode_text.push_str(&format!(" ({}/{})", position, every));
+    }
+    if app.incognito {
+        mode_text.push_str(&format!(" · {} INCOGNITO", glyphs.incognito));
+    }
+    let mut mode_lines = vec![Line::from(mode_text)];
+    if let (PomodoroMode::Break, true, Some(suggestion)) = (app.mode, app.break_suggestions, &app.break_suggestion) {
+        mode_lines.push(Line::styled(
+            format!("Try: {}", suggestion),
+            Style::default().fg(theme.text).remove_modifier(Modifier::BOLD),
+        ));
+    }
+    let mode = Paragraph::new(mode_lines)
         .style(Style::default().fg(mode_color).add_modifier(Modifier::BOLD))
         .alignment(Alignment::Center);
     frame.render_widget(mode, timer_chunks[0]);
     
-    // Timer display using ASCII art
-    let time_str = app.format_time();
-    let timer_color = if app.timer_running { Color::Yellow } else { Color::White };
+    // Timer display using ASCII art (counting up in a distinct color during overtime)
+    let time_str = app.timer_text();
+    let timer_color = if app.in_overtime {
+        theme.accent
+    } else if app.in_final_countdown() && app.remaining_seconds % 2 == 1 {
+        // Alternate colors each second as the session runs out
+        theme.accent
+    } else if app.timer_running {
+        theme.highlight
+    } else {
+        theme.text
+    };
     
     // Convert time to ASCII art
     let ascii_lines = crate::ascii_art::time_to_ascii_art(&time_str);
@@ -124,10 +272,7 @@ fn render_timer(frame: &mut Frame, app: &App, area: Rect) {
     frame.render_widget(timer_display, timer_chunks[1]);
     
     // Progress bar
-    let total_duration = match app.mode {
-        PomodoroMode::Work => app.work_duration,
-        PomodoroMode::Break => app.break_duration,
-    };
+    let total_duration = app.session_length();
     let elapsed = total_duration.saturating_sub(app.remaining_seconds);
     let progress_ratio = if total_duration > 0 {
         elapsed as f64 / total_duration as f64
@@ -136,55 +281,123 @@ fn render_timer(frame: &mut Frame, app: &App, area: Rect) {
     };
     
     let progress_color = match app.mode {
-        PomodoroMode::Work => Color::Red,
-        PomodoroMode::Break => Color::Green,
+        PomodoroMode::Work => theme.work,
+        PomodoroMode::Break => theme.rest,
     };
     
     let progress_label = format!("{}%", (progress_ratio * 100.0) as u16);
-    let gauge = Gauge::default()
-        .block(Block::default().borders(Borders::NONE))
-        .gauge_style(Style::default().fg(progress_color).bg(Color::DarkGray))
-        .ratio(progress_ratio)
-        .label(progress_label);
-    frame.render_widget(gauge, timer_chunks[2]);
+    match app.progress_style {
+        ProgressStyle::Gauge => {
+            let gauge = Gauge::default()
+                .block(Block::default().borders(Borders::NONE))
+                .gauge_style(Style::default().fg(progress_color).bg(theme.dim))
+                .ratio(progress_ratio)
+                .label(progress_label);
+            frame.render_widget(gauge, timer_chunks[2]);
+        }
+        ProgressStyle::Braille | ProgressStyle::Line => {
+            // A single row in the middle of the bar's area, leaving room for the label
+            let width = timer_chunks[2].width.saturating_sub(progress_label.len() as u16 + 1) as usize;
+            let (filled, empty) = if app.progress_style == ProgressStyle::Braille {
+                braille_bar(progress_ratio, width, glyphs)
+            } else {
+                countdown_line(progress_ratio, width, glyphs)
+            };
+            let bar = Paragraph::new(Line::from(vec![
+                Span::styled(filled, Style::default().fg(progress_color)),
+                Span::styled(empty, Style::default().fg(theme.dim)),
+                Span::styled(format!(" {}", progress_label), Style::default().fg(theme.text)),
+            ]));
+            let row = Rect { y: timer_chunks[2].y + timer_chunks[2].height / 2, height: 1, ..timer_chunks[2] };
+            frame.render_widget(bar, row);
+        }
+    }
     
     // Status
-    let status_text = if app.timer_running {
-        "▶ Running"
-    } else if app.remaining_seconds < match app.mode {
-        PomodoroMode::Work => app.work_duration,
-        PomodoroMode::Break => app.break_duration,
-    } {
-        "⏸ Paused"
+    let status_text = if app.in_overtime {
+        format!("+{} overtime │ [Space] Finish", app.format_overtime())
+    } else if let Some(end_time) = app.format_end_time() {
+        format!("{} Running │ ends at {}", glyphs.running, end_time)
+    } else if let Some(paused_for) = app.format_paused_for() {
+        format!("{} Paused for {}", glyphs.paused, paused_for)
+    } else if app.remaining_seconds < app.session_length() {
+        format!("{} Paused", glyphs.paused)
     } else {
-        "⏹ Ready"
+        format!("{} Ready", glyphs.stopped)
+    };
+    let status_color = if app.in_overtime { theme.accent } else { theme.text };
+    let mut status_spans = vec![Span::styled(status_text, Style::default().fg(status_color))];
+    if let Some(seconds) = app.break_unlocks_in() {
+        status_spans.push(Span::styled(
+            format!(" │ {} break unlocks in {:02}:{:02}", glyphs.lock, seconds / 60, seconds % 60),
+            Style::default().fg(theme.accent),
+        ));
+    }
+    let mut status_lines = vec![Line::from(status_spans)];
+    
+    // Daily goal estimate, with the pomodoro count beside it when both goals are set
+    let mut goal_spans = match app.sessions_to_goal() {
+        Some(0) => vec![Span::styled(format!("{} Daily goal reached!", glyphs.celebrate), Style::default().fg(theme.rest))],
+        Some(sessions) => vec![Span::styled(
+            format!("{} more session{} to hit your goal", sessions, if sessions == 1 { "" } else { "s" }),
+            Style::default().fg(theme.dim),
+        )],
+        None => Vec::new(),
     };
-    let status = Paragraph::new(status_text)
-        .style(Style::default().fg(Color::Gray))
+    if let Some((done, goal)) = app.pomodoro_progress() {
+        if !goal_spans.is_empty() {
+            goal_spans.push(Span::styled(" · ", Style::default().fg(theme.dim)));
+        }
+        let color = if done >= goal { theme.rest } else { theme.dim };
+        goal_spans.push(Span::styled(format!("{}/{} {}", done, goal, glyphs.pomodoro), Style::default().fg(color)));
+    }
+    if !goal_spans.is_empty() {
+        status_lines.push(Line::from(goal_spans));
+    }
+    if let Some((milestone, minutes_left)) = app.next_milestone() {
+        status_lines.push(Line::from(Span::styled(
+            format!("Next milestone: {} min ({} to go)", milestone, minutes_left),
+            Style::default().fg(theme.accent),
+        )));
+    }
+    let status = Paragraph::new(status_lines)
         .alignment(Alignment::Center);
     frame.render_widget(status, timer_chunks[3]);
 }
 
-fn render_tags(frame: &mut Frame, app: &App, area: Rect) {
+fn render_tag_list(frame: &mut Frame, app: &App, area: Rect) {
+    let theme = &app.theme;
+    let glyphs = app.glyphs();
     let tags_block = Block::default()
         .title(" Tags ")
         .borders(Borders::ALL)
-        .border_style(Style::default().fg(Color::Magenta));
+        .border_style(Style::default().fg(theme.accent));
     
+    // How long ago each tag was last worked on, right-aligned, to spot neglected ones
+    let width = tags_block.inner(area).width as usize;
     let items: Vec<ListItem> = app.tags
         .iter()
         .enumerate()
         .map(|(i, tag)| {
             let style = if i == app.selected_tag_index {
                 Style::default()
-                    .fg(Color::Yellow)
+                    .fg(theme.highlight)
                     .add_modifier(Modifier::BOLD)
                     .add_modifier(Modifier::REVERSED)
             } else {
-                Style::default().fg(Color::White)
+                Style::default().fg(theme.text)
             };
-            let prefix = if i == app.selected_tag_index { "▶ " } else { "  " };
-            ListItem::new(format!("{}{}", prefix, tag)).style(style)
+            let prefix = if i == app.selected_tag_index { glyphs.pointer } else { "  " };
+            // The first nine tags can be quick-started with their number key
+            let number = if i < 9 { format!("{} ", i + 1) } else { "  ".to_string() };
+            let mark = if app.marked_tags.contains(tag) { format!("{} ", glyphs.check) } else { String::new() };
+            let name = format!("{}{}{}{}", prefix, number, mark, tag_list_name(&app.tags, i, glyphs));
+            let ago = app.last_worked.get(tag).map(|start| relative_time(start)).unwrap_or_else(|| "never".to_string());
+            let gap = width.saturating_sub(name.chars().count() + ago.chars().count()).max(1);
+            ListItem::new(Line::from(vec![
+                Span::raw(format!("{}{}", name, " ".repeat(gap))),
+                Span::styled(ago, Style::default().fg(theme.dim)),
+            ])).style(style)
         })
         .collect();
     
@@ -195,13 +408,166 @@ fn render_tags(frame: &mut Frame, app: &App, area: Rect) {
     frame.render_widget(list, area);
 }
 
-fn render_tag_input_popup(frame: &mut Frame, app: &App) {
-    let area = centered_rect(50, 20, frame.area());
+fn render_tag_tabs(frame: &mut Frame, app: &App, area: Rect) {
+    let theme = &app.theme;
+    let glyphs = app.glyphs();
+    let block = Block::default()
+        .title(" Tags ")
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(theme.accent));
+    
+    let titles: Vec<String> = app.tags
+        .iter()
+        .enumerate()
+        .map(|(i, tag)| {
+            let number = if i < 9 { format!("{} ", i + 1) } else { String::new() };
+            let mark = if app.marked_tags.contains(tag) { format!("{} ", glyphs.check) } else { String::new() };
+            format!("{}{}{}", number, mark, tag)
+        })
+        .collect();
+    let first = first_visible_tab(&titles, app.selected_tag_index, block.inner(area).width as usize);
+    
+    let tabs = Tabs::new(titles[first..].to_vec())
+        .block(block)
+        .select(app.selected_tag_index.saturating_sub(first))
+        .style(Style::default().fg(theme.text))
+        .highlight_style(Style::default().fg(theme.highlight).add_modifier(Modifier::BOLD | Modifier::REVERSED))
+        .divider(Span::styled("│", Style::default().fg(theme.dim)));
+    frame.render_widget(tabs, area);
+}
+
+/// First tab to draw so that the selected one fits in `width` cells, given
+/// that each tab takes its title plus a space either side and a divider
+fn first_visible_tab(titles: &[String], selected: usize, width: usize) -> usize {
+    let mut used = 0;
+    for first in (0..=selected.min(titles.len().saturating_sub(1))).rev() {
+        used += titles[first].chars().count() + 3;
+        if used > width + 1 {
+            return (first + 1).min(selected);
+        }
+    }
+    0
+}
+
+/// A bar `width` cells wide filling from the left in half-cell braille steps,
+/// split into the filled part and the empty track
+fn braille_bar(ratio: f64, width: usize, glyphs: &Glyphs) -> (String, String) {
+    let [full, half, track] = glyphs.braille;
+    let halves = (ratio.clamp(0.0, 1.0) * width as f64 * 2.0).round() as usize;
+    let mut filled = full.repeat(halves / 2);
+    if halves % 2 == 1 {
+        filled.push_str(half);
+    }
+    let empty = track.repeat(width - halves.div_ceil(2));
+    (filled, empty)
+}
+
+/// A line that shrinks as time runs out: the remaining part, then the elapsed part
+fn countdown_line(ratio: f64, width: usize, glyphs: &Glyphs) -> (String, String) {
+    let remaining = ((1.0 - ratio.clamp(0.0, 1.0)) * width as f64).round() as usize;
+    (glyphs.line[0].repeat(remaining), glyphs.line[1].repeat(width - remaining))
+}
+
+/// Sub-tags listed right after their category (or a sibling) are indented and
+/// shown without the category prefix
+fn tag_list_name(tags: &[String], index: usize, glyphs: &Glyphs) -> String {
+    let tag = &tags[index];
+    let previous = index.checked_sub(1).map(|i| tags[i].as_str());
+    match (tag_category(tag), previous) {
+        (Some(category), Some(previous))
+            if previous == category || tag_category(previous) == Some(category) =>
+        {
+            format!("  {} {}", glyphs.sub_tag, &tag[category.len() + 1..])
+        }
+        _ => tag.clone(),
+    }
+}
+
+fn render_recent_sessions(frame: &mut Frame, app: &App, area: Rect) {
+    let theme = &app.theme;
+    let block = Block::default()
+        .title(if app.recent_blocks { " Recent Blocks " } else { " Recent " })
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(theme.dim));
+    
+    if app.recent_blocks {
+        render_session_blocks(frame, app, block, area);
+        return;
+    }
+    
+    if app.recent_sessions.is_empty() {
+        let empty = Paragraph::new(" No sessions yet")
+            .style(Style::default().fg(theme.dim))
+            .block(block);
+        frame.render_widget(empty, area);
+        return;
+    }
+    
+    let items: Vec<ListItem> = app.recent_sessions
+        .iter()
+        .map(|session| {
+            // Work and break rows are told apart by icon and color
+            let (icon, color) = if session.session_type == "break" {
+                (app.glyphs().short_break, theme.rest)
+            } else {
+                (app.glyphs().work, theme.work)
+            };
+            let mut spans = vec![
+                Span::styled(format!(" {} {}", icon, session.tag), Style::default().fg(color)),
+                Span::styled(
+                    format!(" · {} · {}", format_duration(session.duration), relative_time(&session.end_time)),
+                    Style::default().fg(theme.dim),
+                ),
+            ];
+            if let Some(note) = session.note.as_deref().filter(|note| !note.is_empty()) {
+                spans.push(Span::styled(format!(" · {}", note), Style::default().fg(theme.text)));
+            }
+            ListItem::new(Line::from(spans))
+        })
+        .collect();
+    
+    frame.render_widget(List::new(items).block(block), area);
+}
+
+/// Back-to-back work under one tag, one row per block with its session count
+fn render_session_blocks(frame: &mut Frame, app: &App, block: Block, area: Rect) {
+    let theme = &app.theme;
+    if app.session_blocks.is_empty() {
+        let empty = Paragraph::new(" No work sessions yet")
+            .style(Style::default().fg(theme.dim))
+            .block(block);
+        frame.render_widget(empty, area);
+        return;
+    }
+    
+    let items: Vec<ListItem> = app.session_blocks
+        .iter()
+        .map(|session_block| {
+            ListItem::new(Line::from(vec![
+                Span::styled(format!(" {} {}", app.glyphs().work, session_block.tag), Style::default().fg(theme.work)),
+                Span::styled(
+                    format!(" · {} session{}", session_block.sessions, if session_block.sessions == 1 { "" } else { "s" }),
+                    Style::default().fg(theme.text),
+                ),
+                Span::styled(
+                    format!(" · {} · {}", format_duration(session_block.duration), relative_time(&session_block.end_time)),
+                    Style::default().fg(theme.dim),
+                ),
+            ]))
+        })
+        .collect();
+    
+    frame.render_widget(List::new(items).block(block), area);
+}
+
+fn render_delete_confirm_popup(frame: &mut Frame, app: &App) {
+    let theme = &app.theme;
+    let area = centered_rect(50, 25, frame.area());
     
     let popup_block = Block::default()
-        .title(" New Tag ")
+        .title(" Delete Tag ")
         .borders(Borders::ALL)
-        .border_style(Style::default().fg(Color::Yellow));
+        .border_style(Style::default().fg(theme.work));
     
     frame.render_widget(Clear, area);
     frame.render_widget(popup_block.clone(), area);
@@ -211,39 +577,185 @@ fn render_tag_input_popup(frame: &mut Frame, app: &App) {
         .direction(Direction::Vertical)
         .margin(1)
         .constraints([
-            Constraint::Length(1),
-            Constraint::Length(3),
+            Constraint::Length(2),
+            Constraint::Length(2),
             Constraint::Length(1),
         ])
         .split(inner);
     
-    let label = Paragraph::new("Enter tag name:")
-        .style(Style::default().fg(Color::White));
+    let tag_name = app.get_tag_to_delete().unwrap_or("Unknown");
+    let label = Paragraph::new(format!("Are you sure you want to delete\nthe tag \"{}\"?", tag_name))
+        .style(Style::default().fg(theme.text))
+        .alignment(Alignment::Center);
     frame.render_widget(label, chunks[0]);
     
-    let input_style = match app.input_mode {
-        InputMode::Editing => Style::default().fg(Color::Yellow),
-        InputMode::Normal => Style::default().fg(Color::White),
-    };
-    
-    let input = Paragraph::new(format!("{}_", app.input_buffer))
-        .style(input_style)
-        .block(Block::default().borders(Borders::ALL));
-    frame.render_widget(input, chunks[1]);
+    let warning = Paragraph::new("This action cannot be undone!")
+        .style(Style::default().fg(theme.highlight))
+        .alignment(Alignment::Center);
+    frame.render_widget(warning, chunks[1]);
     
-    let help = Paragraph::new("[Enter] Save │ [Esc] Cancel")
-        .style(Style::default().fg(Color::DarkGray))
+    let help = Paragraph::new("[y] Yes, delete │ [n/Esc] Cancel")
+        .style(Style::default().fg(theme.dim))
         .alignment(Alignment::Center);
     frame.render_widget(help, chunks[2]);
 }
 
-fn render_delete_confirm_popup(frame: &mut Frame, app: &App) {
+fn render_catch_up_popup(frame: &mut Frame, app: &App) {
+    let theme = &app.theme;
+    let Some(state) = &app.abandoned_session else { return };
+    let area = centered_rect(50, 35, frame.area());
+    
+    let popup_block = Block::default()
+        .title(" Unfinished Session ")
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(theme.accent));
+    
+    frame.render_widget(Clear, area);
+    frame.render_widget(popup_block.clone(), area);
+    
+    let tags = if state.tags.is_empty() { app.default_tag.clone() } else { state.tags.join(" + ") };
+    let since = app.clock.now().timestamp() - state.saved_at;
+    let lines = vec![
+        Line::from(Span::styled(
+            format!("A {} session ({}) was still open when", state.session_type, tags),
+            Style::default().fg(theme.text),
+        )),
+        Line::from(Span::styled(
+            format!("the app last closed, {} ago.", format_duration(since.max(60))),
+            Style::default().fg(theme.text),
+        )),
+        Line::from(""),
+        Line::from(vec![
+            Span::styled("Timed before closing: ", Style::default().fg(theme.text)),
+            Span::styled(format_duration(state.elapsed_seconds as i64),
+                Style::default().fg(theme.primary).add_modifier(Modifier::BOLD)),
+        ]),
+        Line::from(""),
+        Line::from(Span::styled("[l] Log it │ [d/Esc] Discard", Style::default().fg(theme.dim))),
+    ];
+    
+    let inner = popup_block.inner(area);
+    let content = Paragraph::new(lines)
+        .alignment(Alignment::Center)
+        .block(Block::default().padding(ratatui::widgets::Padding::vertical(1)));
+    frame.render_widget(content, inner);
+}
+
+fn render_archived_tags_popup(frame: &mut Frame, app: &App) {
+    let theme = &app.theme;
+    let area = centered_rect(50, 50, frame.area());
+    
+    let popup_block = Block::default()
+        .title(" Archived Tags ")
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(theme.accent));
+    
+    frame.render_widget(Clear, area);
+    frame.render_widget(popup_block.clone(), area);
+    
+    let inner = popup_block.inner(area);
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .margin(1)
+        .constraints([
+            Constraint::Min(1),
+            Constraint::Length(1),
+        ])
+        .split(inner);
+    
+    if app.archived_tags.is_empty() {
+        let empty = Paragraph::new("No archived tags")
+            .style(Style::default().fg(theme.dim))
+            .alignment(Alignment::Center);
+        frame.render_widget(empty, chunks[0]);
+    } else {
+        let items: Vec<ListItem> = app.archived_tags
+            .iter()
+            .enumerate()
+            .map(|(i, tag)| {
+                let style = if i == app.archived_index {
+                    Style::default().fg(theme.highlight).add_modifier(Modifier::BOLD)
+                } else {
+                    Style::default().fg(theme.text)
+                };
+                let prefix = if i == app.archived_index { app.glyphs().pointer } else { "  " };
+                ListItem::new(format!("{}{}", prefix, tag)).style(style)
+            })
+            .collect();
+        frame.render_widget(List::new(items), chunks[0]);
+    }
+    
+    let help = Paragraph::new("[Enter/a] Restore │ [Esc] Close")
+        .style(Style::default().fg(theme.dim))
+        .alignment(Alignment::Center);
+    frame.render_widget(help, chunks[1]);
+}
+
+fn render_templates_popup(frame: &mut Frame, app: &App) {
+    let theme = &app.theme;
+    let area = centered_rect(60, 50, frame.area());
+    
+    let popup_block = Block::default()
+        .title(" Session Templates ")
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(theme.accent));
+    
+    frame.render_widget(Clear, area);
+    frame.render_widget(popup_block.clone(), area);
+    
+    let inner = popup_block.inner(area);
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .margin(1)
+        .constraints([
+            Constraint::Min(1),
+            Constraint::Length(1),
+        ])
+        .split(inner);
+    
+    if app.templates.is_empty() {
+        let empty = Paragraph::new("No templates yet. Press [n] to save the current tag and durations as one.")
+            .style(Style::default().fg(theme.dim))
+            .alignment(Alignment::Center)
+            .wrap(Wrap { trim: true });
+        frame.render_widget(empty, chunks[0]);
+    } else {
+        let items: Vec<ListItem> = app.templates
+            .iter()
+            .enumerate()
+            .map(|(i, template)| {
+                let style = if i == app.template_index {
+                    Style::default().fg(theme.highlight).add_modifier(Modifier::BOLD)
+                } else {
+                    Style::default().fg(theme.text)
+                };
+                let prefix = if i == app.template_index { app.glyphs().pointer } else { "  " };
+                ListItem::new(Line::from(vec![
+                    Span::styled(format!("{}{}", prefix, template.name), style),
+                    Span::styled(
+                        format!("  {} / {} / {}", template.tag, template.work_minutes, template.break_minutes),
+                        Style::default().fg(theme.dim),
+                    ),
+                ]))
+            })
+            .collect();
+        frame.render_widget(List::new(items), chunks[0]);
+    }
+    
+    let help = Paragraph::new("[Enter] Apply │ [Space] Apply & Start │ [n] New │ [d] Delete │ [Esc] Close")
+        .style(Style::default().fg(theme.dim))
+        .alignment(Alignment::Center);
+    frame.render_widget(help, chunks[1]);
+}
+
+fn render_reset_confirm_popup(frame: &mut Frame, app: &App) {
+    let theme = &app.theme;
     let area = centered_rect(50, 25, frame.area());
     
     let popup_block = Block::default()
-        .title(" Delete Tag ")
+        .title(" Reset Timer ")
         .borders(Borders::ALL)
-        .border_style(Style::default().fg(Color::Red));
+        .border_style(Style::default().fg(theme.work));
     
     frame.render_widget(Clear, area);
     frame.render_widget(popup_block.clone(), area);
@@ -253,45 +765,142 @@ fn render_delete_confirm_popup(frame: &mut Frame, app: &App) {
         .direction(Direction::Vertical)
         .margin(1)
         .constraints([
-            Constraint::Length(2),
             Constraint::Length(2),
             Constraint::Length(1),
         ])
         .split(inner);
     
-    let tag_name = app.get_tag_to_delete().unwrap_or("Unknown");
-    let label = Paragraph::new(format!("Are you sure you want to delete\nthe tag \"{}\"?", tag_name))
-        .style(Style::default().fg(Color::White))
+    let label = Paragraph::new(format!("Reset and lose {} of progress?", app.format_elapsed()))
+        .style(Style::default().fg(theme.text))
         .alignment(Alignment::Center);
     frame.render_widget(label, chunks[0]);
     
-    let warning = Paragraph::new("This action cannot be undone!")
-        .style(Style::default().fg(Color::Yellow))
+    let help = Paragraph::new("[y] Yes, reset │ [n/Esc] Cancel")
+        .style(Style::default().fg(theme.dim))
         .alignment(Alignment::Center);
-    frame.render_widget(warning, chunks[1]);
-    
-    let help = Paragraph::new("[y] Yes, delete │ [n/Esc] Cancel")
-        .style(Style::default().fg(Color::DarkGray))
-        .alignment(Alignment::Center);
-    frame.render_widget(help, chunks[2]);
+    frame.render_widget(help, chunks[1]);
 }
 
-fn centered_rect(percent_x: u16, percent_y: u16, area: Rect) -> Rect {
-    let popup_layout = Layout::default()
+fn render_rating_popup(frame: &mut Frame, app: &App) {
+    let theme = &app.theme;
+    let area = centered_rect(50, 25, frame.area());
+    
+    let popup_block = Block::default()
+        .title(" Focus Rating ")
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(theme.highlight));
+    
+    frame.render_widget(Clear, area);
+    frame.render_widget(popup_block.clone(), area);
+    
+    let inner = popup_block.inner(area);
+    let chunks = Layout::default()
         .direction(Direction::Vertical)
+        .margin(1)
         .constraints([
-            Constraint::Percentage((100 - percent_y) / 2),
-            Constraint::Percentage(percent_y),
-            Constraint::Percentage((100 - percent_y) / 2),
+            Constraint::Length(2),
+            Constraint::Length(1),
         ])
-        .split(area);
+        .split(inner);
     
-    Layout::default()
-        .direction(Direction::Horizontal)
-        .constraints([
-            Constraint::Percentage((100 - percent_x) / 2),
-            Constraint::Percentage(percent_x),
-            Constraint::Percentage((100 - percent_x) / 2),
+    let label = Paragraph::new("How focused were you? 1 = distracted, 5 = deep focus")
+        .style(Style::default().fg(theme.text))
+        .alignment(Alignment::Center)
+        .wrap(Wrap { trim: true });
+    frame.render_widget(label, chunks[0]);
+    
+    let help = Paragraph::new("[1-5] Rate │ [Esc] Skip")
+        .style(Style::default().fg(theme.dim))
+        .alignment(Alignment::Center);
+    frame.render_widget(help, chunks[1]);
+}
+
+fn render_today_summary_popup(frame: &mut Frame, app: &App) {
+    let theme = &app.theme;
+    let area = centered_rect(50, 40, frame.area());
+    let summary = &app.today_summary;
+    
+    let popup_block = Block::default()
+        .title(" Today at a Glance ")
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(theme.highlight));
+    
+    frame.render_widget(Clear, area);
+    frame.render_widget(popup_block.clone(), area);
+    
+    let row = |label: &str, value: String| {
+        Line::from(vec![
+            Span::styled(format!("{:<18}", label), Style::default().fg(theme.text)),
+            Span::styled(value, Style::default().fg(theme.primary).add_modifier(Modifier::BOLD)),
+        ])
+    };
+    let mut lines = vec![
+        row("Sessions", summary.sessions.to_string()),
+        row("Focused", format_duration(summary.seconds)),
+        row("Streak", format!("{} day{}", summary.streak, if summary.streak == 1 { "" } else { "s" })),
+    ];
+    if app.daily_goal > 0 {
+        let percent = summary.seconds / 60 * 100 / app.daily_goal as i64;
+        lines.push(row("Daily goal", format!("{}% of {} min", percent, app.daily_goal)));
+    }
+    lines.push(Line::from(""));
+    lines.push(Line::from(Span::styled("Press any key to continue", Style::default().fg(theme.dim))));
+    
+    let inner = popup_block.inner(area);
+    let content = Paragraph::new(lines)
+        .alignment(Alignment::Center)
+        .block(Block::default().padding(ratatui::widgets::Padding::vertical(1)));
+    frame.render_widget(content, inner);
+}
+
+/// A quick look at the cached figures, without leaving the timer
+fn render_stats_peek_popup(frame: &mut Frame, app: &App) {
+    let theme = &app.theme;
+    let area = centered_rect(40, 30, frame.area());
+    let summary = &app.today_summary;
+    
+    let popup_block = Block::default()
+        .title(format!(" {} Stats Peek ", app.glyphs().stats))
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(theme.accent));
+    
+    frame.render_widget(Clear, area);
+    frame.render_widget(popup_block.clone(), area);
+    
+    let row = |label: &str, value: String| {
+        Line::from(vec![
+            Span::styled(format!("{:<12}", label), Style::default().fg(theme.text)),
+            Span::styled(format!("{:>10}", value), Style::default().fg(theme.primary).add_modifier(Modifier::BOLD)),
         ])
-        .split(popup_layout[1])[1]
+    };
+    let lines = vec![
+        row("Today", format_duration(summary.seconds)),
+        row("This week", format_duration(summary.week_seconds)),
+        row("Streak", format!("{} day{}", summary.streak, if summary.streak == 1 { "" } else { "s" })),
+        row("Sessions", summary.sessions.to_string()),
+        Line::from(""),
+        Line::from(Span::styled("[S] or any key to close", Style::default().fg(theme.dim))),
+    ];
+    
+    let inner = popup_block.inner(area);
+    let content = Paragraph::new(lines)
+        .alignment(Alignment::Center)
+        .block(Block::default().padding(ratatui::widgets::Padding::vertical(1)));
+    frame.render_widget(content, inner);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_first_visible_tab_keeps_selection_in_view() {
+        let titles: Vec<String> = ["1 Work", "2 Study", "3 Reading", "4 Gym"].iter().map(|t| t.to_string()).collect();
+        // Everything fits: 9 + 10 + 12 + 8 cells, less the last divider
+        assert_eq!(first_visible_tab(&titles, 3, 38), 0);
+        // Too narrow for all four, so the leading tabs scroll off
+        assert_eq!(first_visible_tab(&titles, 3, 30), 1);
+        assert_eq!(first_visible_tab(&titles, 3, 10), 3);
+        assert_eq!(first_visible_tab(&titles, 0, 10), 0);
+    }
 }
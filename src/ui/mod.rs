@@ -1,7 +1,42 @@
+pub mod banner;
 pub mod home;
 pub mod stats;
 pub mod heatmap;
+pub mod popup;
+pub mod review;
+pub mod settings;
 
 pub use home::render_home;
 pub use stats::render_stats;
 pub use heatmap::render_heatmap;
+pub use review::render_week_review;
+pub use settings::render_settings;
+
+/// Formats a number of seconds as "1h 05m" or "25m"
+pub fn format_duration(seconds: i64) -> String {
+    let minutes = seconds / 60;
+    if minutes >= 60 {
+        format!("{}h {:02}m", minutes / 60, minutes % 60)
+    } else {
+        format!("{}m", minutes)
+    }
+}
+
+/// Describes how long ago a stored "%Y-%m-%d %H:%M:%S" timestamp was, e.g. "10m ago"
+pub fn relative_time(timestamp: &str) -> String {
+    let then = match chrono::NaiveDateTime::parse_from_str(timestamp, "%Y-%m-%d %H:%M:%S") {
+        Ok(then) => then,
+        Err(_) => return timestamp.to_string(),
+    };
+    let minutes = (chrono::Local::now().naive_local() - then).num_minutes().max(0);
+    
+    if minutes < 1 {
+        "just now".to_string()
+    } else if minutes < 60 {
+        format!("{}m ago", minutes)
+    } else if minutes < 24 * 60 {
+        format!("{}h ago", minutes / 60)
+    } else {
+        format!("{}d ago", minutes / (24 * 60))
+    }
+}
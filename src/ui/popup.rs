@@ -0,0 +1,74 @@
+use ratatui::{
+    Frame,
+    layout::{Alignment, Constraint, Direction, Layout, Rect},
+    style::Style,
+    widgets::{Block, Borders, Clear, Paragraph},
+};
+
+use crate::app::{App, InputMode};
+
+pub fn render_input_popup(frame: &mut Frame, app: &App, title: &str, prompt: &str) {
+    let theme = &app.theme;
+    let area = centered_rect(50, 20, frame.area());
+    
+    let popup_block = Block::default()
+        .title(title)
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(theme.highlight));
+    
+    frame.render_widget(Clear, area);
+    frame.render_widget(popup_block.clone(), area);
+    
+    let inner = popup_block.inner(area);
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .margin(1)
+        .constraints([
+            Constraint::Length(1),
+            Constraint::Length(3),
+            Constraint::Length(1),
+        ])
+        .split(inner);
+    
+    let label = Paragraph::new(prompt)
+        .style(Style::default().fg(theme.text));
+    frame.render_widget(label, chunks[0]);
+    
+    let input_style = match app.input_mode {
+        InputMode::Editing => Style::default().fg(theme.highlight),
+        InputMode::Normal => Style::default().fg(theme.text),
+    };
+    
+    let input = Paragraph::new(format!("{}_", app.input_buffer))
+        .style(input_style)
+        .block(Block::default().borders(Borders::ALL));
+    frame.render_widget(input, chunks[1]);
+    
+    // Validation errors replace the help line until the input changes
+    let help = match app.input_error {
+        Some(message) => Paragraph::new(message).style(Style::default().fg(theme.work)),
+        None => Paragraph::new("[Enter] Save │ [Esc] Cancel").style(Style::default().fg(theme.dim)),
+    }
+    .alignment(Alignment::Center);
+    frame.render_widget(help, chunks[2]);
+}
+
+pub fn centered_rect(percent_x: u16, percent_y: u16, area: Rect) -> Rect {
+    let popup_layout = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Percentage((100 - percent_y) / 2),
+            Constraint::Percentage(percent_y),
+            Constraint::Percentage((100 - percent_y) / 2),
+        ])
+        .split(area);
+    
+    Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([
+            Constraint::Percentage((100 - percent_x) / 2),
+            Constraint::Percentage(percent_x),
+            Constraint::Percentage((100 - percent_x) / 2),
+        ])
+        .split(popup_layout[1])[1]
+}
@@ -0,0 +1,62 @@
+use ratatui::{
+    Frame,
+    layout::{Alignment, Constraint, Direction, Layout},
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Padding, Paragraph, Wrap},
+};
+
+use crate::app::App;
+use crate::ui::banner::render_session_banner;
+use crate::ui::popup::centered_rect;
+
+/// The week review: one card at a time, like a short retrospective
+pub fn render_week_review(frame: &mut Frame, app: &App) {
+    let theme = &app.theme;
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .margin(1)
+        .constraints([
+            Constraint::Length(3),  // Title
+            Constraint::Min(9),     // Card
+            Constraint::Length(2),  // Help
+        ])
+        .split(frame.area());
+
+    let title = Paragraph::new(format!("{} Week in Review", app.glyphs().review))
+        .style(Style::default().fg(theme.primary).add_modifier(Modifier::BOLD))
+        .alignment(Alignment::Center)
+        .block(Block::default().borders(Borders::BOTTOM).border_style(Style::default().fg(theme.dim)));
+    frame.render_widget(title, chunks[0]);
+    render_session_banner(frame, app, chunks[0]);
+
+    if let Some(card) = app.review_cards.get(app.review_index) {
+        // Dots showing which card this is
+        let dots: String = (0..app.review_cards.len())
+            .map(|i| format!("{} ", if i == app.review_index { app.glyphs().dot } else { app.glyphs().hollow_dot }))
+            .collect();
+        let lines = vec![
+            Line::from(Span::styled(card.headline.as_str(),
+                Style::default().fg(theme.highlight).add_modifier(Modifier::BOLD))),
+            Line::from(""),
+            Line::from(Span::styled(card.detail.as_str(), Style::default().fg(theme.text))),
+            Line::from(""),
+            Line::from(Span::styled(dots.trim_end().to_string(), Style::default().fg(theme.dim))),
+        ];
+        let card_widget = Paragraph::new(lines)
+            .alignment(Alignment::Center)
+            .wrap(Wrap { trim: true })
+            .block(Block::default()
+                .title(format!(" {} ", card.title))
+                .title_alignment(Alignment::Center)
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(theme.accent))
+                .padding(Padding::vertical(1)));
+        frame.render_widget(card_widget, centered_rect(60, 60, chunks[1]));
+    }
+
+    let help = Paragraph::new(" [←/→] Previous/Next │ [Esc] Back │ [q] Quit ")
+        .style(Style::default().fg(theme.dim))
+        .alignment(Alignment::Center);
+    frame.render_widget(help, chunks[2]);
+}
@@ -0,0 +1,139 @@
+use ratatui::{
+    Frame,
+    layout::{Alignment, Constraint, Direction, Layout},
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, List, ListItem, ListState, Paragraph, Wrap},
+};
+
+use crate::app::{App, Screen, Setting};
+use crate::ui::banner::render_session_banner;
+use crate::ui::popup::{centered_rect, render_input_popup};
+
+pub fn render_settings(frame: &mut Frame, app: &App) {
+    let theme = &app.theme;
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .margin(1)
+        .constraints([
+            Constraint::Length(3),  // Title
+            Constraint::Min(5),     // Settings list
+            Constraint::Length(2),  // Help
+        ])
+        .split(frame.area());
+
+    // Title
+    let title = Paragraph::new(format!("{} Settings", app.glyphs().settings))
+        .style(Style::default().fg(theme.highlight).add_modifier(Modifier::BOLD))
+        .alignment(Alignment::Center)
+        .block(Block::default().borders(Borders::BOTTOM).border_style(Style::default().fg(theme.dim)));
+    frame.render_widget(title, chunks[0]);
+    render_session_banner(frame, app, chunks[0]);
+
+    // Settings list
+    let items: Vec<ListItem> = Setting::ALL
+        .iter()
+        .enumerate()
+        .map(|(i, setting)| {
+            let selected = i == app.settings_index;
+            let label_style = if selected {
+                Style::default().fg(theme.highlight).add_modifier(Modifier::BOLD)
+            } else {
+                Style::default().fg(theme.text)
+            };
+            let prefix = if selected { app.glyphs().pointer } else { "  " };
+            // Unsaved values stand out until they are saved or discarded
+            let value = if app.setting_modified(*setting) {
+                Span::styled(format!("{} {}", app.glyphs().dot, app.shown_setting_value(*setting)),
+                    Style::default().fg(theme.highlight).add_modifier(Modifier::ITALIC))
+            } else {
+                Span::styled(app.shown_setting_value(*setting), Style::default().fg(theme.primary))
+            };
+            ListItem::new(Line::from(vec![
+                Span::styled(format!("{}{:<40}", prefix, setting.label()), label_style),
+                value,
+            ]))
+        })
+        .collect();
+
+    let list = List::new(items)
+        .block(Block::default()
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(theme.secondary)));
+    // Scrolls to keep the selected setting in view on short terminals
+    let mut state = ListState::default().with_selected(Some(app.settings_index));
+    frame.render_stateful_widget(list, chunks[1], &mut state);
+
+    // Help bar
+    let help_text = match &app.status_message {
+        Some(message) => format!(" {} ", message),
+        None if app.has_unsaved_settings() => format!(" {} Unsaved │ [s] Save │ [x] Discard │ [↑/↓] Select │ [Enter/Space] Change │ [q] Quit ", app.glyphs().dot),
+        None => " [↑/↓] Select │ [Enter/Space] Change │ [b] Backup │ [P] Purge Old │ [h/Esc] Home │ [q] Quit ".to_string(),
+    };
+    let help = Paragraph::new(help_text)
+        .style(Style::default().fg(theme.dim))
+        .alignment(Alignment::Center);
+    frame.render_widget(help, chunks[2]);
+    
+    // Render value input popup for text settings
+    if app.current_screen == Screen::SettingInput {
+        render_input_popup(frame, app, " Edit Setting ", app.selected_setting().label());
+    }
+    
+    if app.current_screen == Screen::BackupDone {
+        render_backup_popup(frame, app);
+    }
+    
+    if app.current_screen == Screen::PurgeConfirm {
+        render_purge_popup(frame, app);
+    }
+}
+
+fn render_backup_popup(frame: &mut Frame, app: &App) {
+    let theme = &app.theme;
+    let area = centered_rect(60, 30, frame.area());
+    let lines = vec![
+        Line::from(Span::styled(app.backup_message.as_str(), Style::default().fg(theme.text))),
+        Line::from(""),
+        Line::from(Span::styled("Press any key to continue", Style::default().fg(theme.dim))),
+    ];
+    
+    let popup = Paragraph::new(lines)
+        .alignment(Alignment::Center)
+        .wrap(Wrap { trim: true })
+        .block(Block::default()
+            .title(" Backup ")
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(theme.highlight))
+            .padding(ratatui::widgets::Padding::vertical(1)));
+    frame.render_widget(Clear, area);
+    frame.render_widget(popup, area);
+}
+
+fn render_purge_popup(frame: &mut Frame, app: &App) {
+    let theme = &app.theme;
+    let area = centered_rect(60, 30, frame.area());
+    let Some(cutoff) = app.purge_cutoff() else {
+        return;
+    };
+    let lines = vec![
+        Line::from(Span::styled(
+            format!("Delete {} sessions from before {}?", app.db.count_sessions_before(cutoff), cutoff),
+            Style::default().fg(theme.text).add_modifier(Modifier::BOLD),
+        )),
+        Line::from(Span::styled("This can't be undone. Back up first with [b] to keep a copy.", Style::default().fg(theme.text))),
+        Line::from(""),
+        Line::from(Span::styled("[y] Delete │ [n/Esc] Cancel", Style::default().fg(theme.dim))),
+    ];
+    
+    let popup = Paragraph::new(lines)
+        .alignment(Alignment::Center)
+        .wrap(Wrap { trim: true })
+        .block(Block::default()
+            .title(" Purge Old Sessions ")
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(theme.work))
+            .padding(ratatui::widgets::Padding::vertical(1)));
+    frame.render_widget(Clear, area);
+    frame.render_widget(popup, area);
+}
@@ -1,70 +1,351 @@
 use ratatui::{
     Frame,
     layout::{Alignment, Constraint, Direction, Layout},
-    style::{Color, Modifier, Style},
+    style::{Modifier, Style},
     text::{Line, Span},
-    widgets::{Bar, BarChart, BarGroup, Block, Borders, Paragraph},
+    widgets::{Bar, BarChart, BarGroup, Block, Borders, Clear, Gauge, List, ListItem, ListState, Paragraph, Wrap},
 };
 
-use crate::app::{App, StatsView};
+use chrono::{Datelike, NaiveDate};
+
+use crate::app::{App, BarColoring, Screen, StatsView};
+use crate::ui::banner::render_session_banner;
+use crate::ui::format_duration;
+use crate::ui::popup::centered_rect;
+use crate::ui::heatmap::{day_labels, intensity_level, HeatScale};
 
 pub fn render_stats(frame: &mut Frame, app: &App) {
+    let theme = &app.theme;
+    // Weekly target for the selected tag, if it has one
+    let target = app.get_stats_tag()
+        .and_then(|tag| app.db.get_tag_weekly_target(tag).map(|minutes| (tag, minutes)));
+    let weekly_goal = app.weekly_goal_progress();
+    
     let chunks = Layout::default()
         .direction(Direction::Vertical)
         .margin(1)
         .constraints([
             Constraint::Length(3),  // Title
             Constraint::Length(3),  // Controls
+            Constraint::Length(1),  // Week-over-week trend
+            Constraint::Length(1),  // Work:break ratio
+            Constraint::Length(if target.is_some() { 3 } else { 0 }),  // Weekly target
+            Constraint::Length(if weekly_goal.is_some() { 3 } else { 0 }),  // Weekly goal
             Constraint::Min(10),    // Chart
             Constraint::Length(2),  // Help
         ])
         .split(frame.area());
     
     // Title
-    let title = Paragraph::new("📊 Statistics")
-        .style(Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD))
+    let title = Paragraph::new(format!("{} Statistics", app.glyphs().stats))
+        .style(Style::default().fg(theme.primary).add_modifier(Modifier::BOLD))
         .alignment(Alignment::Center)
-        .block(Block::default().borders(Borders::BOTTOM).border_style(Style::default().fg(Color::DarkGray)));
+        .block(Block::default().borders(Borders::BOTTOM).border_style(Style::default().fg(theme.dim)));
     frame.render_widget(title, chunks[0]);
+    render_session_banner(frame, app, chunks[0]);
     
     // Controls bar
     render_controls(frame, app, chunks[1]);
     
+    // Trend
+    render_trend(frame, app, chunks[2]);
+    
+    // Work vs. break time
+    render_work_break_ratio(frame, app, chunks[3]);
+    
+    // Weekly target progress
+    if let Some((tag, target_minutes)) = target {
+        render_weekly_target(frame, app, tag, target_minutes, chunks[4]);
+    }
+    
+    // Weekly goal progress, over all tags
+    if let Some((done_minutes, goal_minutes)) = weekly_goal {
+        render_weekly_goal(frame, app, done_minutes, goal_minutes, chunks[5]);
+    }
+    
     // Chart
-    render_chart(frame, app, chunks[2]);
+    render_chart(frame, app, chunks[6]);
     
     // Help bar
-    let help_text = " [Tab] Toggle View │ [←/→] Change Tag │ [h] Home │ [m] Heatmap │ [q] Quit ";
+    let help_text = match &app.status_message {
+        Some(message) => format!(" {} ", message),
+        None => format!(" [Tab] Toggle View │ [←/→] Change Tag │ [g] Split by Tag │ [c] By Category │ [+/-] Weekly Target │ [M] Merge Tag │ [l] Leaderboard │ [,/.] Pick Bar │ [Enter] Sessions │ [r] Review Week │ [f] Format: {} │ [x] Export │ [h] Home │ [m] Heatmap │ [[/]] Screens │ [q] Quit ", app.export_format.name()),
+    };
     let help = Paragraph::new(help_text)
-        .style(Style::default().fg(Color::DarkGray))
+        .style(Style::default().fg(theme.dim))
         .alignment(Alignment::Center);
-    frame.render_widget(help, chunks[3]);
+    frame.render_widget(help, chunks[7]);
+    
+    if app.current_screen == Screen::MergeTags {
+        render_merge_popup(frame, app);
+    }
+    
+    if app.current_screen == Screen::BucketSessions {
+        render_bucket_sessions_popup(frame, app);
+    }
+    
+    if app.current_screen == Screen::ExportDone {
+        render_export_popup(frame, app);
+    }
 }
 
-fn render_controls(frame: &mut Frame, app: &App, area: ratatui::layout::Rect) {
+fn render_export_popup(frame: &mut Frame, app: &App) {
+    let theme = &app.theme;
+    let area = centered_rect(60, 30, frame.area());
+    let lines = vec![
+        Line::from(Span::styled(app.export_message.as_str(), Style::default().fg(theme.text))),
+        Line::from(""),
+        Line::from(Span::styled("Press any key to continue", Style::default().fg(theme.dim))),
+    ];
+    
+    let popup = Paragraph::new(lines)
+        .alignment(Alignment::Center)
+        .wrap(Wrap { trim: true })
+        .block(Block::default()
+            .title(" Export ")
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(theme.highlight))
+            .padding(ratatui::widgets::Padding::vertical(1)));
+    frame.render_widget(Clear, area);
+    frame.render_widget(popup, area);
+}
+
+/// The individual sessions behind the highlighted bar
+fn render_bucket_sessions_popup(frame: &mut Frame, app: &App) {
+    let theme = &app.theme;
+    let area = centered_rect(70, 60, frame.area());
+    let buckets = app.stats_buckets();
+    let bucket = app.selected_bucket(buckets.len()).map(|i| buckets[i].0.as_str()).unwrap_or("");
+    let popup_block = Block::default()
+        .title(format!(" Sessions · {} ", bucket))
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(theme.accent));
+    
+    frame.render_widget(Clear, area);
+    frame.render_widget(popup_block.clone(), area);
+    
+    let inner = popup_block.inner(area);
     let chunks = Layout::default()
-        .direction(Direction::Horizontal)
-        .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
-        .split(area);
+        .direction(Direction::Vertical)
+        .margin(1)
+        .constraints([Constraint::Min(1), Constraint::Length(1)])
+        .split(inner);
     
-    // View toggle
-    let weekly_style = if app.stats_view == StatsView::Weekly {
-        Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD)
+    if app.bucket_sessions.is_empty() {
+        let empty = Paragraph::new("No sessions")
+            .style(Style::default().fg(theme.dim))
+            .alignment(Alignment::Center);
+        frame.render_widget(empty, chunks[0]);
     } else {
-        Style::default().fg(Color::DarkGray)
+        let items: Vec<ListItem> = app.bucket_sessions
+            .iter()
+            .map(|session| {
+                let mut spans = vec![
+                    Span::styled(session_span(&session.start_time, &session.end_time), Style::default().fg(theme.text)),
+                    Span::styled(format!("  {:>7}  ", format_duration(session.duration)), Style::default().fg(theme.primary).add_modifier(Modifier::BOLD)),
+                    Span::styled(session.tag.clone(), Style::default().fg(theme.accent)),
+                ];
+                if let Some(note) = session.note.as_deref().filter(|note| !note.is_empty()) {
+                    spans.push(Span::styled(format!(" · {}", note), Style::default().fg(theme.dim)));
+                }
+                ListItem::new(Line::from(spans))
+            })
+            .collect();
+        let list = List::new(items)
+            .highlight_style(Style::default().fg(theme.highlight).add_modifier(Modifier::BOLD))
+            .highlight_symbol(app.glyphs().pointer);
+        let mut state = ListState::default().with_selected(Some(app.bucket_session_index));
+        frame.render_stateful_widget(list, chunks[0], &mut state);
+    }
+    
+    let help = Paragraph::new("[↑↓] Scroll │ [Esc] Close")
+        .style(Style::default().fg(theme.dim))
+        .alignment(Alignment::Center);
+    frame.render_widget(help, chunks[1]);
+}
+
+/// A session's span from stored timestamps, e.g. "Mon Jun 10  09:00–09:25"
+fn session_span(start: &str, end: &str) -> String {
+    let parse = |timestamp: &str| chrono::NaiveDateTime::parse_from_str(timestamp, "%Y-%m-%d %H:%M:%S").ok();
+    match (parse(start), parse(end)) {
+        (Some(start), Some(end)) => format!("{}–{}", start.format("%a %b %-d  %H:%M"), end.format("%H:%M")),
+        _ => start.to_string(),
+    }
+}
+
+/// Destination picker for merging the selected tag into another
+fn render_merge_popup(frame: &mut Frame, app: &App) {
+    let theme = &app.theme;
+    let area = centered_rect(50, 50, frame.area());
+    let popup_block = Block::default()
+        .title(format!(" Merge \"{}\" into… ", app.get_stats_tag().unwrap_or("")))
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(theme.accent));
+    
+    frame.render_widget(Clear, area);
+    frame.render_widget(popup_block.clone(), area);
+    
+    let inner = popup_block.inner(area);
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .margin(1)
+        .constraints([Constraint::Min(1), Constraint::Length(1)])
+        .split(inner);
+    
+    if app.merge_targets.is_empty() {
+        let empty = Paragraph::new("No other tags to merge into")
+            .style(Style::default().fg(theme.dim))
+            .alignment(Alignment::Center);
+        frame.render_widget(empty, chunks[0]);
+    } else {
+        let items: Vec<ListItem> = app.merge_targets
+            .iter()
+            .enumerate()
+            .map(|(i, tag)| {
+                let style = if i == app.merge_index {
+                    Style::default().fg(theme.highlight).add_modifier(Modifier::BOLD)
+                } else {
+                    Style::default().fg(theme.text)
+                };
+                let prefix = if i == app.merge_index { app.glyphs().pointer } else { "  " };
+                ListItem::new(format!("{}{}", prefix, tag)).style(style)
+            })
+            .collect();
+        frame.render_widget(List::new(items), chunks[0]);
+    }
+    
+    let help = Paragraph::new("[↑↓] Choose │ [Enter] Merge (moves all sessions) │ [Esc] Cancel")
+        .style(Style::default().fg(theme.dim))
+        .alignment(Alignment::Center);
+    frame.render_widget(help, chunks[1]);
+}
+
+fn render_trend(frame: &mut Frame, app: &App, area: ratatui::layout::Rect) {
+    let theme = &app.theme;
+    let (this_week, last_week) = app.db.get_week_over_week(app.get_stats_tag()).unwrap_or((0, 0));
+    
+    let mut spans = vec![
+        Span::raw(" This week: "),
+        Span::styled(format_duration(this_week), Style::default().fg(theme.text).add_modifier(Modifier::BOLD)),
+        Span::raw("  "),
+    ];
+    
+    if last_week > 0 {
+        let change = (this_week - last_week) as f64 / last_week as f64 * 100.0;
+        let (arrow, color) = if this_week >= last_week {
+            (app.glyphs().up, theme.rest)
+        } else {
+            (app.glyphs().down, theme.work)
+        };
+        spans.push(Span::styled(format!("{} {:.0}%", arrow, change.abs()), Style::default().fg(color)));
+        spans.push(Span::styled(" vs last week", Style::default().fg(theme.dim)));
+    } else if this_week > 0 {
+        spans.push(Span::styled(format!("{} new", app.glyphs().up), Style::default().fg(theme.rest)));
+        spans.push(Span::styled(" (nothing logged last week)", Style::default().fg(theme.dim)));
+    }
+    
+    frame.render_widget(Paragraph::new(Line::from(spans)), area);
+}
+
+/// Work time against break time over the period of the current view
+fn render_work_break_ratio(frame: &mut Frame, app: &App, area: ratatui::layout::Rect) {
+    let theme = &app.theme;
+    let since = app.stats_period_start().map(|date| date.to_string());
+    let (work, rest) = app.db.get_work_break_totals(since.as_deref(), app.get_stats_tag()).unwrap_or((0, 0));
+    
+    let mut spans = vec![Span::raw(" Work:Break ")];
+    if work == 0 && rest == 0 {
+        spans.push(Span::styled("no sessions yet", Style::default().fg(theme.dim)));
+    } else if rest == 0 {
+        spans.push(Span::styled("no breaks logged", Style::default().fg(theme.work)));
+    } else {
+        spans.push(Span::styled(
+            format!("= {:.1}:1", work as f64 / rest as f64),
+            Style::default().fg(theme.text).add_modifier(Modifier::BOLD),
+        ));
+    }
+    
+    // Two-segment bar, 20 cells wide
+    if work + rest > 0 {
+        let work_cells = ((work as f64 / (work + rest) as f64) * 20.0).round() as usize;
+        spans.push(Span::raw("  "));
+        spans.push(Span::styled(app.glyphs().full.repeat(work_cells), Style::default().fg(theme.work)));
+        spans.push(Span::styled(app.glyphs().full.repeat(20 - work_cells), Style::default().fg(theme.rest)));
+        spans.push(Span::styled(
+            format!(" {} work / {} break", format_duration(work), format_duration(rest)),
+            Style::default().fg(theme.dim),
+        ));
+    }
+    
+    frame.render_widget(Paragraph::new(Line::from(spans)), area);
+}
+
+fn render_weekly_target(frame: &mut Frame, app: &App, tag: &str, target_minutes: i64, area: ratatui::layout::Rect) {
+    let theme = &app.theme;
+    let done_minutes = app.db.get_week_total_for_tag(tag, app.current_week_start()) / 60;
+    let remaining = (target_minutes - done_minutes).max(0);
+    let ratio = (done_minutes as f64 / target_minutes as f64).min(1.0);
+    
+    let label = if remaining == 0 {
+        format!("{} / {} min {} target reached", done_minutes, target_minutes, app.glyphs().check)
+    } else {
+        format!("{} / {} min ({} min to go)", done_minutes, target_minutes, remaining)
     };
-    let monthly_style = if app.stats_view == StatsView::Monthly {
-        Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD)
+    let color = if remaining == 0 { theme.rest } else { theme.accent };
+    
+    let gauge = Gauge::default()
+        .block(Block::default().borders(Borders::ALL).title(" This Week's Target "))
+        .gauge_style(Style::default().fg(color).bg(theme.dim))
+        .ratio(ratio)
+        .label(label);
+    frame.render_widget(gauge, area);
+}
+
+fn render_weekly_goal(frame: &mut Frame, app: &App, done_minutes: u64, goal_minutes: u64, area: ratatui::layout::Rect) {
+    let theme = &app.theme;
+    let remaining = goal_minutes.saturating_sub(done_minutes);
+    let ratio = (done_minutes as f64 / goal_minutes as f64).min(1.0);
+    
+    let label = if remaining == 0 {
+        format!("{} / {} min {} goal met", done_minutes, goal_minutes, app.glyphs().check)
     } else {
-        Style::default().fg(Color::DarkGray)
+        format!("{} / {} min ({} min to go)", done_minutes, goal_minutes, remaining)
     };
+    let color = if remaining == 0 { theme.rest } else { theme.primary };
     
-    let view_line = Line::from(vec![
-        Span::raw(" View: "),
-        Span::styled("[ Weekly ]", weekly_style),
-        Span::raw("  "),
-        Span::styled("[ Monthly ]", monthly_style),
-    ]);
+    let gauge = Gauge::default()
+        .block(Block::default().borders(Borders::ALL).title(format!(" Weekly Goal · since {} ", app.current_week_start().format("%a %b %-d"))))
+        .gauge_style(Style::default().fg(color).bg(theme.dim))
+        .ratio(ratio)
+        .label(label);
+    frame.render_widget(gauge, area);
+}
+
+fn render_controls(frame: &mut Frame, app: &App, area: ratatui::layout::Rect) {
+    let theme = &app.theme;
+    let chunks = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
+        .split(area);
+    
+    // View toggle
+    let mut view_spans = vec![Span::raw(" View: ")];
+    for (view, label) in [
+        (StatsView::Weekly, "[ Weekly ]"),
+        (StatsView::Monthly, "[ Monthly ]"),
+        (StatsView::Yearly, "[ Yearly ]"),
+        (StatsView::Weekdays, "[ Weekdays ]"),
+        (StatsView::Hours, "[ Time of Day ]"),
+    ] {
+        let style = if app.stats_view == view {
+            Style::default().fg(theme.highlight).add_modifier(Modifier::BOLD)
+        } else {
+            Style::default().fg(theme.dim)
+        };
+        view_spans.push(Span::styled(label, style));
+        view_spans.push(Span::raw(" "));
+    }
+    let view_line = Line::from(view_spans);
     let view = Paragraph::new(view_line)
         .block(Block::default().borders(Borders::ALL));
     frame.render_widget(view, chunks[0]);
@@ -76,7 +357,7 @@ fn render_controls(frame: &mut Frame, app: &App, area: ratatui::layout::Rect) {
     };
     let tag_line = Line::from(vec![
         Span::raw(" Tag: "),
-        Span::styled(format!("◀ {} ▶", tag_name), Style::default().fg(Color::Magenta)),
+        Span::styled(format!("{} {} {}", app.glyphs().left, tag_name, app.glyphs().right), Style::default().fg(theme.accent)),
     ]);
     let tag = Paragraph::new(tag_line)
         .block(Block::default().borders(Borders::ALL));
@@ -84,54 +365,399 @@ fn render_controls(frame: &mut Frame, app: &App, area: ratatui::layout::Rect) {
 }
 
 fn render_chart(frame: &mut Frame, app: &App, area: ratatui::layout::Rect) {
+    let theme = &app.theme;
     let chart_block = Block::default()
         .title(match app.stats_view {
             StatsView::Weekly => " Weekly Activity (minutes) ",
             StatsView::Monthly => " Monthly Activity (minutes) ",
+            StatsView::Yearly => " Yearly Activity (minutes) ",
+            StatsView::Weekdays => " Average per Weekday (minutes) ",
+            StatsView::Hours => " Typical Day (all history, by hour) ",
         })
         .borders(Borders::ALL)
-        .border_style(Style::default().fg(Color::Blue));
+        .border_style(Style::default().fg(theme.secondary));
+    
+    if app.stats_leaderboard {
+        render_leaderboard(frame, app, area);
+        return;
+    }
+    
+    if app.stats_view == StatsView::Weekdays {
+        render_weekday_chart(frame, app, chart_block, area);
+        return;
+    }
+    
+    if app.stats_view == StatsView::Hours {
+        render_hour_strip(frame, app, chart_block, area);
+        return;
+    }
+    
+    if app.stats_breakdown && app.get_stats_tag().is_none() {
+        render_breakdown_chart(frame, app, chart_block, area);
+        return;
+    }
     
     // Get data based on view type
-    let data = match app.stats_view {
-        StatsView::Weekly => app.db.get_weekly_stats(app.get_stats_tag()).unwrap_or_default(),
-        StatsView::Monthly => app.db.get_monthly_stats(app.get_stats_tag()).unwrap_or_default(),
-    };
+    let data = app.stats_buckets();
+    let selected = app.selected_bucket(data.len());
     
     if data.is_empty() {
         let no_data = Paragraph::new("\n\n  No data available yet. Complete some Pomodoro sessions to see statistics!")
-            .style(Style::default().fg(Color::DarkGray))
+            .style(Style::default().fg(theme.dim))
             .block(chart_block);
         frame.render_widget(no_data, area);
         return;
     }
     
+    // Days that reach the daily goal stand out from the rest
+    let goal = match (app.stats_view, app.get_stats_tag()) {
+        (StatsView::Weekly, None) if app.daily_goal > 0 => Some(app.daily_goal),
+        _ => None,
+    };
+    
     // Convert data to bar chart format
+    let max_minutes = data.iter().map(|(_, value)| value / 60).max().unwrap_or(0);
     let bars: Vec<Bar> = data
         .iter()
-        .map(|(label, value)| {
-            let short_label = if app.stats_view == StatsView::Weekly {
-                // Show day of week
-                label.chars().skip(5).collect::<String>() // Skip year, show MM-DD
+        .enumerate()
+        .map(|(i, (label, value))| {
+            let short_label = short_bucket_label(app, label);
+            let minutes = (*value as u64) / 60; // Convert to minutes
+            let color = match goal {
+                Some(goal) if minutes >= goal => theme.rest,
+                _ if app.bar_coloring == BarColoring::Intensity => {
+                    theme.heat[intensity_level(minutes as i64, max_minutes, HeatScale::Relative)]
+                }
+                _ => theme.primary,
+            };
+            // The highlighted bar (Enter lists its sessions) has a marked label
+            let label_style = if selected == Some(i) {
+                Style::default().fg(theme.highlight).add_modifier(Modifier::BOLD | Modifier::REVERSED)
             } else {
-                label.clone()
+                Style::default()
             };
             Bar::default()
-                .value((*value as u64) / 60) // Convert to minutes
-                .label(Line::from(short_label))
-                .style(Style::default().fg(Color::Cyan))
+                .value(minutes)
+                .label(Line::styled(short_label, label_style))
+                .style(Style::default().fg(color))
         })
         .collect();
     
     let bar_group = BarGroup::default().bars(&bars);
     
+    // Only a handful of years exist, so give them wide bars
+    let (bar_width, bar_gap) = match app.stats_view {
+        StatsView::Yearly => (9, 4),
+        _ => (5, 2),
+    };
+    
+    // Average focus rating of each bar, written under it on the bottom border
+    let ratings = &app.stats_ratings;
+    let rated = ratings.iter().any(Option::is_some);
+    let chart_block = if rated {
+        let star = app.glyphs().star;
+        let cells: String = ratings
+            .iter()
+            .map(|rating| {
+                let text = rating.map(|r| format!("{}{:.1}", star, r)).unwrap_or_default();
+                format!("{:^width$}{:gap$}", text, "", width = bar_width as usize, gap = bar_gap as usize)
+            })
+            .collect();
+        chart_block
+            .title(Line::from(format!(" {} avg focus rating ", star)).right_aligned())
+            .title_bottom(Line::styled(cells, Style::default().fg(theme.highlight)))
+    } else {
+        chart_block
+    };
+    
+    let chart_block = match goal {
+        Some(goal) => {
+            // Moved aside when ratings take the left of the border
+            let legend = Line::from(vec![
+                Span::raw(" "),
+                Span::styled(app.glyphs().square, Style::default().fg(theme.rest)),
+                Span::styled(format!(" goal of {} min reached ", goal), Style::default().fg(theme.text)),
+            ]);
+            chart_block.title_bottom(if rated { legend.right_aligned() } else { legend })
+        }
+        None => chart_block,
+    };
+    
     let bar_chart = BarChart::default()
         .block(chart_block)
         .data(bar_group)
+        .bar_width(bar_width)
+        .bar_gap(bar_gap)
+        .bar_style(Style::default().fg(theme.primary))
+        .bar_set(app.glyphs().bars.clone())
+        .value_style(Style::default().fg(theme.text).add_modifier(Modifier::BOLD));
+    
+    frame.render_widget(bar_chart, area);
+}
+
+/// Tags ranked by focus time over the current view's period, with each one's share
+fn render_leaderboard(frame: &mut Frame, app: &App, area: ratatui::layout::Rect) {
+    let theme = &app.theme;
+    let today = app.db.today();
+    let since = app.stats_period_start();
+    let period = match since {
+        Some(_) if app.stats_view == StatsView::Weekly => "Last 7 Days",
+        Some(_) => "Last 12 Months",
+        None => "All Time",
+    };
+    let block = Block::default()
+        .title(format!(" Tag Leaderboard · {} ", period))
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(theme.secondary));
+    
+    let from = since.unwrap_or(NaiveDate::MIN); // Sorts before every stored date
+    let totals = app.db.get_tag_totals(from, today).unwrap_or_default();
+    let since = since.map(|date| date.to_string());
+    let (total, _) = app.db.get_work_break_totals(since.as_deref(), None).unwrap_or((0, 0));
+    
+    if totals.is_empty() {
+        let empty = Paragraph::new("\n\n  No work logged in this period yet.")
+            .style(Style::default().fg(theme.dim))
+            .block(block);
+        frame.render_widget(empty, area);
+        return;
+    }
+    
+    let name_width = totals.iter().map(|(tag, _)| tag.chars().count()).max().unwrap_or(0);
+    let lines: Vec<Line> = leaderboard(&totals, total)
+        .into_iter()
+        .enumerate()
+        .map(|(i, (tag, seconds, share))| {
+            let cells = (share as usize * 20).div_ceil(100).min(20);
+            Line::from(vec![
+                Span::styled(format!(" {:>2}. {:<width$}  ", i + 1, tag, width = name_width), Style::default().fg(theme.text)),
+                Span::styled(format!("{:>8}  ", format_duration(seconds)), Style::default().fg(theme.text).add_modifier(Modifier::BOLD)),
+                Span::styled(app.glyphs().full.repeat(cells), Style::default().fg(theme.primary)),
+                Span::styled(app.glyphs().empty.repeat(20 - cells), Style::default().fg(theme.dim)),
+                Span::styled(format!(" {:>3}%", share), Style::default().fg(theme.dim)),
+            ])
+        })
+        .collect();
+    frame.render_widget(Paragraph::new(lines).block(block), area);
+}
+
+/// Tags busiest first with their seconds and percentage of `total` focus
+/// time. A session with several tags counts towards each, so shares can add
+/// up to more than 100%.
+pub fn leaderboard(totals: &[(String, i64)], total: i64) -> Vec<(String, i64, i64)> {
+    let mut rows: Vec<_> = totals.iter()
+        .map(|(tag, seconds)| (tag.clone(), *seconds, if total > 0 { seconds * 100 / total } else { 0 }))
+        .collect();
+    rows.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    rows
+}
+
+/// Average work per weekday across all history, in the configured week order
+fn render_weekday_chart(frame: &mut Frame, app: &App, chart_block: Block, area: ratatui::layout::Rect) {
+    let theme = &app.theme;
+    let today = app.db.today();
+    let averages = match app.db.get_weekday_totals(app.get_stats_tag()) {
+        Ok((totals, Some(first))) => weekday_averages(&totals, first, today),
+        _ => {
+            let no_data = Paragraph::new("\n\n  No data available yet. Complete some Pomodoro sessions to see statistics!")
+                .style(Style::default().fg(theme.dim))
+                .block(chart_block);
+            frame.render_widget(no_data, area);
+            return;
+        }
+    };
+    
+    let offset = app.week_start.num_days_from_monday() as usize;
+    let bars: Vec<Bar> = day_labels(app.week_start)
+        .iter()
+        .enumerate()
+        .map(|(i, label)| {
+            Bar::default()
+                .value((averages[(offset + i) % 7] / 60) as u64)
+                .label(Line::from(*label))
+                .style(Style::default().fg(theme.primary))
+        })
+        .collect();
+    
+    let bar_chart = BarChart::default()
+        .block(chart_block)
+        .data(BarGroup::default().bars(&bars))
         .bar_width(5)
         .bar_gap(2)
-        .bar_style(Style::default().fg(Color::Cyan))
-        .value_style(Style::default().fg(Color::White).add_modifier(Modifier::BOLD));
+        .bar_set(app.glyphs().bars.clone())
+        .value_style(Style::default().fg(theme.text).add_modifier(Modifier::BOLD));
+    frame.render_widget(bar_chart, area);
+}
+
+/// All history folded into one day: a strip of 24 hourly cells shaded like the
+/// heatmap, relative to the busiest hour
+fn render_hour_strip(frame: &mut Frame, app: &App, chart_block: Block, area: ratatui::layout::Rect) {
+    let theme = &app.theme;
+    let totals = app.db.get_hourly_distribution(app.get_stats_tag()).unwrap_or([0; 24]);
+    let total: i64 = totals.iter().sum();
+    if total == 0 {
+        let no_data = Paragraph::new("\n\n  No data available yet. Complete some Pomodoro sessions to see statistics!")
+            .style(Style::default().fg(theme.dim))
+            .block(chart_block);
+        frame.render_widget(no_data, area);
+        return;
+    }
+    
+    let inner = chart_block.inner(area);
+    frame.render_widget(chart_block, area);
+    
+    let cell = (inner.width / 24).max(1) as usize;
+    let max_minutes = totals.iter().max().copied().unwrap_or(0) / 60;
+    let strip = Line::from(
+        totals
+            .iter()
+            .map(|seconds| {
+                let level = intensity_level(seconds / 60, max_minutes, HeatScale::Relative);
+                Span::styled(app.glyphs().full.repeat(cell), Style::default().fg(theme.heat[level]))
+            })
+            .collect::<Vec<_>>(),
+    );
+    // Label every third hour, padded to span its three cells
+    let labels: String = (0..24)
+        .step_by(3)
+        .map(|hour| format!("{:<width$}", format!("{:02}", hour), width = cell * 3))
+        .collect();
+    
+    let (busiest, _) = totals.iter().enumerate().max_by_key(|(hour, seconds)| (**seconds, std::cmp::Reverse(*hour))).unwrap();
+    let share = |hours: std::ops::Range<usize>| totals[hours].iter().sum::<i64>() * 100 / total;
+    let summary = format!(
+        "Busiest hour {:02}:00–{:02}:00 │ {}% before noon │ {}% after 18:00",
+        busiest, (busiest + 1) % 24, share(0..12), share(18..24)
+    );
+    
+    let lines = vec![
+        Line::from(""),
+        strip.clone(),
+        strip.clone(),
+        strip,
+        Line::from(Span::styled(labels, Style::default().fg(theme.dim))),
+        Line::from(""),
+        Line::from(Span::styled(summary, Style::default().fg(theme.text))),
+    ];
+    frame.render_widget(Paragraph::new(lines).alignment(Alignment::Center), inner);
+}
+
+/// Average work seconds per weekday (Monday first) from all-time `totals`,
+/// dividing by how many of that weekday fell between `first` and `today`.
+/// Weekdays without work average zero.
+pub fn weekday_averages(totals: &[i64; 7], first: NaiveDate, today: NaiveDate) -> [i64; 7] {
+    let mut counts = [0i64; 7];
+    for day in first.iter_days().take_while(|day| *day <= today) {
+        counts[day.weekday().num_days_from_monday() as usize] += 1;
+    }
+    std::array::from_fn(|i| if counts[i] > 0 { totals[i] / counts[i] } else { 0 })
+}
+
+fn short_bucket_label(app: &App, label: &str) -> String {
+    if app.stats_view == StatsView::Weekly {
+        // Show day of week
+        label.chars().skip(5).collect::<String>() // Skip year, show MM-DD
+    } else {
+        label.to_string()
+    }
+}
+
+/// "All Tags" chart with one bar per tag in every day/month group
+fn render_breakdown_chart(frame: &mut Frame, app: &App, chart_block: Block, area: ratatui::layout::Rect) {
+    let theme = &app.theme;
+    let data = match app.stats_view {
+        StatsView::Weekly => app.db.get_weekly_stats_by_tag(app.stats_by_category).unwrap_or_default(),
+        StatsView::Monthly => app.db.get_monthly_stats_by_tag(app.stats_by_category).unwrap_or_default(),
+        StatsView::Yearly | StatsView::Weekdays | StatsView::Hours => app.db.get_yearly_stats_by_tag(app.stats_by_category).unwrap_or_default(),
+    };
+    
+    if data.is_empty() {
+        let no_data = Paragraph::new("\n\n  No data available yet. Complete some Pomodoro sessions to see statistics!")
+            .style(Style::default().fg(theme.dim))
+            .block(chart_block);
+        frame.render_widget(no_data, area);
+        return;
+    }
+    
+    // Every group lists the same tags in the same order and color
+    let mut tags: Vec<&str> = data.iter().map(|(_, tag, _)| tag.as_str()).collect();
+    tags.sort();
+    tags.dedup();
+    let palette = [theme.primary, theme.accent, theme.rest, theme.highlight, theme.work, theme.secondary];
+    let tag_color = |i: usize| palette[i % palette.len()];
+    
+    let mut buckets: Vec<&str> = Vec::new();
+    for (bucket, _, _) in &data {
+        if buckets.last() != Some(&bucket.as_str()) {
+            buckets.push(bucket);
+        }
+    }
+    
+    let groups: Vec<BarGroup> = buckets
+        .iter()
+        .map(|bucket| {
+            let bars: Vec<Bar> = tags
+                .iter()
+                .enumerate()
+                .map(|(i, tag)| {
+                    let seconds = data
+                        .iter()
+                        .find(|(b, t, _)| b == bucket && t == tag)
+                        .map(|(_, _, total)| *total)
+                        .unwrap_or(0);
+                    Bar::default()
+                        .value((seconds as u64) / 60)
+                        .style(Style::default().fg(tag_color(i)))
+                })
+                .collect();
+            BarGroup::default()
+                .label(Line::from(short_bucket_label(app, bucket)))
+                .bars(&bars)
+        })
+        .collect();
+    
+    let mut legend: Vec<Span> = vec![Span::raw(" ")];
+    for (i, tag) in tags.iter().enumerate() {
+        legend.push(Span::styled(format!("{} ", app.glyphs().square), Style::default().fg(tag_color(i))));
+        legend.push(Span::styled(format!("{}  ", tag), Style::default().fg(theme.text)));
+    }
+    
+    let mut bar_chart = BarChart::default()
+        .block(chart_block.title_bottom(Line::from(legend)))
+        .bar_width(3)
+        .bar_gap(0)
+        .group_gap(3)
+        .bar_set(app.glyphs().bars.clone())
+        .value_style(Style::default().fg(theme.text).add_modifier(Modifier::BOLD));
+    for group in groups {
+        bar_chart = bar_chart.data(group);
+    }
     
     frame.render_widget(bar_chart, area);
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_leaderboard_ranks_tags_with_shares() {
+        let totals = vec![("Work".to_string(), 1800), ("Study".to_string(), 5400), ("Art".to_string(), 1800)];
+        assert_eq!(leaderboard(&totals, 7200), vec![
+            ("Study".to_string(), 5400, 75),
+            ("Art".to_string(), 1800, 25),
+            ("Work".to_string(), 1800, 25),
+        ]);
+        assert_eq!(leaderboard(&totals, 0)[0].2, 0);
+    }
+
+    #[test]
+    fn test_weekday_averages_count_days_without_work() {
+        // Mon 2024-06-03 to Wed 2024-06-12: two Mondays, Tuesdays and Wednesdays
+        let first = NaiveDate::from_ymd_opt(2024, 6, 3).unwrap();
+        let today = NaiveDate::from_ymd_opt(2024, 6, 12).unwrap();
+        let totals = [3600, 0, 1200, 600, 0, 0, 0];
+        
+        assert_eq!(weekday_averages(&totals, first, today), [1800, 0, 600, 600, 0, 0, 0]);
+    }
+}